@@ -0,0 +1,77 @@
+#![warn(clippy::all, clippy::pedantic)]
+use std::fs;
+use std::hint::black_box;
+
+use advent2024::day::day18::{first_blocking_byte, BlockingSearch};
+use advent2024::day::{self, input_path, registry, Day};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// Benchmark the parse, part 1, and part 2 steps of a day separately,
+/// using the real puzzle input from `resources/`.
+macro_rules! bench_day {
+    ($c:expr, $day:literal, $module:ident :: $solver:ident) => {
+        let raw = fs::read_to_string(input_path($day)).expect("missing puzzle input");
+        let input = <day::$module::$solver>::parse(&raw).expect("invalid puzzle input");
+        let mut group = $c.benchmark_group(concat!("day", $day));
+        group.sample_size(10);
+        group.bench_function("parse", |b| b.iter(|| <day::$module::$solver>::parse(black_box(&raw))));
+        group.bench_function("part1", |b| b.iter(|| <day::$module::$solver>::part1(black_box(&input)).map(|a| a.to_string())));
+        group.bench_function("part2", |b| b.iter(|| <day::$module::$solver>::part2(black_box(&input)).map(|a| a.to_string())));
+        group.finish();
+    };
+}
+
+fn bench_days(c: &mut Criterion) {
+    bench_day!(c, 1, day1::Day1);
+    bench_day!(c, 2, day2::Day2);
+    bench_day!(c, 3, day3::Day3);
+    bench_day!(c, 4, day4::Day4);
+    bench_day!(c, 5, day5::Day5);
+    bench_day!(c, 6, day6::Day6);
+    bench_day!(c, 7, day7::Day7);
+    bench_day!(c, 8, day8::Day8);
+    bench_day!(c, 9, day9::Day9);
+    bench_day!(c, 10, day10::Day10);
+    bench_day!(c, 11, day11::Day11);
+    bench_day!(c, 12, day12::Day12);
+    bench_day!(c, 13, day13::Day13);
+    bench_day!(c, 14, day14::Day14);
+    bench_day!(c, 15, day15::Day15);
+    bench_day!(c, 16, day16::Day16);
+    bench_day!(c, 17, day17::Day17);
+    bench_day!(c, 18, day18::Day18);
+    bench_day!(c, 19, day19::Day19);
+    bench_day!(c, 20, day20::Day20);
+    bench_day!(c, 21, day21::Day21);
+    bench_day!(c, 22, day22::Day22);
+    bench_day!(c, 23, day23::Day23);
+    bench_day!(c, 24, day24::Day24);
+    bench_day!(c, 25, day25::Day25);
+}
+
+/// Benchmark the full solve for every registered day through the public `solve_day` API
+fn bench_solve_day(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve_day");
+    group.sample_size(10);
+    for runner in registry() {
+        let day = runner.day();
+        let raw = fs::read_to_string(input_path(day)).expect("missing puzzle input");
+        group.bench_function(format!("day{day}"), |b| b.iter(|| advent2024::solve_day(day, black_box(&raw))));
+    }
+    group.finish();
+}
+
+/// Compare the ways of finding the first byte that blocks the day 18 exit
+fn bench_day18_blocking(c: &mut Criterion) {
+    let raw = fs::read_to_string(input_path(18)).expect("missing puzzle input");
+    let bytes = day::day18::Day18::parse(&raw).expect("invalid puzzle input");
+    let mut group = c.benchmark_group("day18_blocking");
+    group.sample_size(10);
+    for (name, search) in [("reverse_union", BlockingSearch::ReverseUnion), ("binary_search", BlockingSearch::BinarySearch)] {
+        group.bench_function(name, |b| b.iter(|| first_blocking_byte(black_box(&bytes), 71, search)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_days, bench_solve_day, bench_day18_blocking);
+criterion_main!(benches);
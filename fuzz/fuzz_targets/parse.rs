@@ -0,0 +1,14 @@
+//! Feed arbitrary text to a day's parser, which should return an error for malformed input rather than panic.
+//! The first byte picks the day, so one target covers them all:
+//! ```sh
+//! cargo +nightly fuzz run parse
+//! ```
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&day, input)) = data.split_first() else { return };
+    let Ok(input) = std::str::from_utf8(input) else { return };
+    let _ = advent2024::parse_day(i32::from(day % 25) + 1, input);
+});
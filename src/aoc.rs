@@ -0,0 +1,199 @@
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const YEAR: i32 = 2024;
+const USER_AGENT: &str = "github.com/jacobhyphenated/advent2024";
+
+/// Where submitted answers and their verdicts are remembered between runs
+const SUBMISSION_CACHE: &str = ".aoc/submissions.tsv";
+
+/// A minimal client for talking to adventofcode.com.
+///
+/// Requests are authenticated with the session cookie from a logged in browser.
+/// The session is read from the `AOC_SESSION` environment variable, or the session file
+/// (`.aoc/session` unless `advent.toml` says otherwise).
+pub struct AocClient {
+    session: String,
+}
+
+/// The result of submitting an answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    /// Submitted too recently, try again later
+    RateLimited,
+    /// The part was already solved (or is not unlocked yet)
+    AlreadySolved,
+}
+
+impl AocClient {
+    /// # Errors
+    /// If no session token can be found
+    pub fn from_env(session_file: &Path) -> Result<Self, String> {
+        let session = env::var("AOC_SESSION")
+            .or_else(|_| fs::read_to_string(session_file))
+            .map_err(|_| format!("No session token found. Set AOC_SESSION or create {}", session_file.display()))?;
+        Ok(Self { session: session.trim().to_string() })
+    }
+
+    /// Post an answer for the day and part, and interpret the response
+    ///
+    /// # Errors
+    /// If the request fails or the response is not recognized
+    pub fn submit(&self, day: i32, part: u8, answer: &str) -> Result<Verdict, String> {
+        let url = format!("https://adventofcode.com/{YEAR}/day/{day}/answer");
+        let body = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|e| format!("Failed to submit answer: {e}"))?
+            .into_string()
+            .map_err(|e| format!("Failed to read response: {e}"))?;
+        Verdict::from_response(&body).ok_or_else(|| "Unrecognized response from adventofcode.com".to_string())
+    }
+}
+
+impl Verdict {
+    fn from_response(body: &str) -> Option<Self> {
+        if body.contains("That's the right answer") {
+            Some(Self::Correct)
+        } else if body.contains("your answer is too high") {
+            Some(Self::TooHigh)
+        } else if body.contains("your answer is too low") {
+            Some(Self::TooLow)
+        } else if body.contains("That's not the right answer") {
+            Some(Self::Incorrect)
+        } else if body.contains("You gave an answer too recently") {
+            Some(Self::RateLimited)
+        } else if body.contains("You don't seem to be solving the right level") {
+            Some(Self::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "correct" => Some(Self::Correct),
+            "too high" => Some(Self::TooHigh),
+            "too low" => Some(Self::TooLow),
+            "incorrect" => Some(Self::Incorrect),
+            "rate limited" => Some(Self::RateLimited),
+            "already solved" => Some(Self::AlreadySolved),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Correct => "correct",
+            Self::TooHigh => "too high",
+            Self::TooLow => "too low",
+            Self::Incorrect => "incorrect",
+            Self::RateLimited => "rate limited",
+            Self::AlreadySolved => "already solved",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Previously submitted answers, stored as tab separated `day part answer verdict` lines.
+/// Rate limited submissions are not stored, since those answers were never checked.
+struct SubmissionCache {
+    path: PathBuf,
+    entries: Vec<(i32, u8, String, Verdict)>,
+}
+
+impl SubmissionCache {
+    fn load(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let [day, part, answer, verdict] = line.split('\t').collect::<Vec<_>>().try_into().ok()?;
+                Some((day.parse().ok()?, part.parse().ok()?, answer.to_string(), Verdict::parse(verdict)?))
+            })
+            .collect();
+        Self { path: path.to_path_buf(), entries }
+    }
+
+    /// The verdict for this exact answer, or the correct answer if the part is already solved
+    fn lookup(&self, day: i32, part: u8, answer: &str) -> Option<(&str, Verdict)> {
+        self.entries.iter()
+            .filter(|(d, p, _, _)| *d == day && *p == part)
+            .find(|(_, _, a, verdict)| a == answer || *verdict == Verdict::Correct)
+            .map(|(_, _, a, verdict)| (a.as_str(), *verdict))
+    }
+
+    fn record(&mut self, day: i32, part: u8, answer: &str, verdict: Verdict) -> std::io::Result<()> {
+        if verdict == Verdict::RateLimited {
+            return Ok(());
+        }
+        self.entries.push((day, part, answer.to_string(), verdict));
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = self.entries.iter()
+            .map(|(day, part, answer, verdict)| format!("{day}\t{part}\t{answer}\t{verdict}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(&self.path, contents)
+    }
+}
+
+/// Submit the answer for a day and part, unless the same answer has already been submitted
+/// or the part has already been solved. Returns the (possibly cached) verdict.
+///
+/// # Errors
+/// If there is no session token, or the submission fails
+pub fn submit(day: i32, part: u8, answer: &str, session_file: &Path) -> Result<Verdict, String> {
+    let mut cache = SubmissionCache::load(Path::new(SUBMISSION_CACHE));
+    if let Some((previous, verdict)) = cache.lookup(day, part, answer) {
+        println!("Already submitted {previous} for day {day} part {part} (cached)");
+        return Ok(verdict);
+    }
+    let client = AocClient::from_env(session_file)?;
+    let verdict = client.submit(day, part, answer)?;
+    cache.record(day, part, answer, verdict)
+        .map_err(|e| format!("Failed to save submission cache: {e}"))?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verdict_from_response() {
+        let wrong = "<article><p>That's not the right answer; your answer is too low. \
+            If you're stuck, make sure you're using the full input data</p></article>";
+        assert_eq!(Some(Verdict::TooLow), Verdict::from_response(wrong));
+        let right = "<article><p>That's the right answer! You are one gold star closer</p></article>";
+        assert_eq!(Some(Verdict::Correct), Verdict::from_response(right));
+        let limited = "<article><p>You gave an answer too recently; you have to wait after submitting</p></article>";
+        assert_eq!(Some(Verdict::RateLimited), Verdict::from_response(limited));
+        assert_eq!(None, Verdict::from_response("<html></html>"));
+    }
+
+    #[test]
+    fn test_submission_cache() {
+        let path = env::temp_dir().join("advent2024_submission_cache_test.tsv");
+        let _ = fs::remove_file(&path);
+        let mut cache = SubmissionCache::load(&path);
+        cache.record(6, 2, "1700", Verdict::TooLow).unwrap();
+        cache.record(6, 2, "1800", Verdict::RateLimited).unwrap();
+
+        let cache = SubmissionCache::load(&path);
+        assert_eq!(Some(("1700", Verdict::TooLow)), cache.lookup(6, 2, "1700"));
+        assert_eq!(None, cache.lookup(6, 2, "1800"));
+        assert_eq!(None, cache.lookup(6, 1, "1700"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use advent2024::config::Config;
+use advent2024::day::{as_millis, DayRunner};
+
+/// Summary statistics for a set of timing samples, in milliseconds
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+impl Stats {
+    /// # Panics
+    /// If there are no samples
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "No samples to summarize");
+        let mut millis = samples.iter().map(|&d| as_millis(d)).collect::<Vec<_>>();
+        millis.sort_unstable_by(f64::total_cmp);
+        let count = millis.len() as f64;
+        let mid = millis.len() / 2;
+        let median = if millis.len() % 2 == 0 {
+            f64::midpoint(millis[mid - 1], millis[mid])
+        } else {
+            millis[mid]
+        };
+        let mean = millis.iter().sum::<f64>() / count;
+        let variance = millis.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / count;
+        Self { min: millis[0], median, mean, stddev: variance.sqrt() }
+    }
+}
+
+/// Benchmark each day `iterations` times with its input and context from the `config`,
+/// and print a table of statistics for the parse, part 1, and part 2 steps.
+pub fn run_bench(runners: &[Box<dyn DayRunner>], iterations: usize, config: &Config) {
+    println!(
+        "Day | Step   | {:>10} | {:>10} | {:>10} | {:>10}",
+        "min ms", "median ms", "mean ms", "stddev ms",
+    );
+    let dashes = "-".repeat(10);
+    println!("----|--------|-{dashes}-|-{dashes}-|-{dashes}-|-{dashes}");
+    for runner in runners {
+        let day = runner.day();
+        let bench = match runner.bench(&config.input_path(day), iterations, &config.context(day)) {
+            Ok(bench) => bench,
+            Err(e) => {
+                println!("{day:>3} | {e}");
+                continue;
+            }
+        };
+        for (step, samples) in [("parse", &bench.parse), ("part 1", &bench.part1), ("part 2", &bench.part2)] {
+            let stats = Stats::from_samples(samples);
+            println!(
+                "{day:>3} | {step:<6} | {:>10.3} | {:>10.3} | {:>10.3} | {:>10.3}",
+                stats.min, stats.median, stats.mean, stats.stddev,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples);
+        assert!((stats.min - 1.0).abs() < f64::EPSILON);
+        assert!((stats.median - 2.5).abs() < f64::EPSILON);
+        assert!((stats.mean - 2.5).abs() < f64::EPSILON);
+        assert!((stats.stddev - 1.25_f64.sqrt()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,383 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use advent2024::day::OutputFormat;
+use crate::report::ReportFormat;
+
+/// Command line options for the runner.
+///
+/// Days are given as plain numbers. An optional subcommand may be given first:
+/// * `submit` post the computed answer for a single day and `--part` to adventofcode.com
+/// * `verify` compare the answers for the days (or every day) against `answers.toml`
+/// * `tui` open an interactive dashboard of every day
+/// * `debug` step through the program in a single day's puzzle input interactively (only day 17)
+/// * `new-day` generate the module and an empty input file for a single new day
+///
+/// Flags may appear anywhere in the argument list. Any flag that isn't given falls back to the
+/// setting in `advent.toml` (see [`Config`](advent2024::config::Config)), if there is one:
+/// * `--all` run every implemented day and print a summary
+/// * `--part <1|2>` only run the specified part of each day
+/// * `--input <path>` read the puzzle input from `path` instead of `dayN.txt` in the input directory
+/// * `--bench <N>` time parsing and each part N times and report statistics
+/// * `--format <text|json>` print human readable text (the default) or one JSON object per day
+/// * `--report <markdown|csv>` write a table of the answers and timings to the `output` directory
+/// * `--timeout <secs>` give up on a day that takes longer than `secs` seconds and report it as timed out
+/// * `--visualize` draw the puzzle input for a single day (an animation for days 6, 15, and 18,
+///   a DOT graph for day 24, or pictures for day 14)
+/// * `--delay <ms>` how long to show each frame of a `--visualize` animation
+/// * `--disasm` print the program in the puzzle input of a single day as pseudo-code (only day 17)
+/// * `--svg` save a picture of the solution for a single day as `output/dayN.svg` (days 12, 16, and 20)
+/// * `--algo <name>` solve a single day with one of its alternate algorithms instead of the default (or configured) one
+/// * `--crosscheck` solve each day with every one of its algorithms and report any answers that differ
+/// * `--verbose` (or `-v`) log the time spent in each step and debug events from the solvers to stderr
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub days: Vec<i32>,
+    pub all: bool,
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+    pub bench: Option<usize>,
+    pub format: Option<OutputFormat>,
+    pub report: Option<ReportFormat>,
+    pub timeout: Option<Duration>,
+    pub verbose: bool,
+    pub render: Option<Render>,
+    pub delay: Option<Duration>,
+    pub algorithm: Option<String>,
+    pub crosscheck: bool,
+}
+
+/// Print the puzzle input in a readable form instead of solving it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Render {
+    /// A Graphviz DOT graph (day 24), or image files (day 14)
+    Picture,
+    /// Pseudo-code for a program (day 17)
+    Disassembly,
+    /// An SVG picture of the solution (days 12, 16, and 20)
+    Svg,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub enum Command {
+    #[default]
+    Run,
+    Submit,
+    Verify,
+    Tui,
+    NewDay,
+    Debug,
+}
+
+impl Options {
+    /// Parse the command line arguments (not including the program name)
+    ///
+    /// # Errors
+    /// Returns a message describing the invalid argument
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.iter().peekable();
+        if args.next_if(|arg| *arg == "submit").is_some() {
+            options.command = Command::Submit;
+        } else if args.next_if(|arg| *arg == "verify").is_some() {
+            options.command = Command::Verify;
+        } else if args.next_if(|arg| *arg == "tui").is_some() {
+            options.command = Command::Tui;
+        } else if args.next_if(|arg| *arg == "new-day").is_some() {
+            options.command = Command::NewDay;
+        } else if args.next_if(|arg| *arg == "debug").is_some() {
+            options.command = Command::Debug;
+        }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--all" => options.all = true,
+                "--verbose" | "-v" => options.verbose = true,
+                "--crosscheck" => options.crosscheck = true,
+                "--visualize" | "--disasm" | "--svg" => {
+                    if options.render.is_some() {
+                        return Err("only one of --visualize, --disasm, and --svg can be used".to_string());
+                    }
+                    options.render = Some(match arg.as_str() {
+                        "--visualize" => Render::Picture,
+                        "--disasm" => Render::Disassembly,
+                        _ => Render::Svg,
+                    });
+                }
+                "--part" => {
+                    let part = args.next().ok_or("--part requires a value")?;
+                    match part.parse::<u8>() {
+                        Ok(part @ (1 | 2)) => options.part = Some(part),
+                        _ => return Err(format!("Invalid part: {part}")),
+                    }
+                }
+                "--input" => {
+                    let path = args.next().ok_or("--input requires a file path")?;
+                    options.input = Some(PathBuf::from(path));
+                }
+                "--bench" => {
+                    let iterations = args.next().ok_or("--bench requires a number of iterations")?;
+                    match iterations.parse::<usize>() {
+                        Ok(iterations) if iterations > 0 => options.bench = Some(iterations),
+                        _ => return Err(format!("Invalid number of iterations: {iterations}")),
+                    }
+                }
+                "--format" => {
+                    options.format = match args.next().map(String::as_str) {
+                        Some("text") => Some(OutputFormat::Text),
+                        Some("json") => Some(OutputFormat::Json),
+                        Some(format) => return Err(format!("Invalid format: {format}")),
+                        None => return Err("--format requires a value".to_string()),
+                    };
+                }
+                "--report" => {
+                    options.report = match args.next().map(String::as_str) {
+                        Some("markdown" | "md") => Some(ReportFormat::Markdown),
+                        Some("csv") => Some(ReportFormat::Csv),
+                        Some(format) => return Err(format!("Invalid report format: {format}")),
+                        None => return Err("--report requires a value".to_string()),
+                    };
+                }
+                "--timeout" => {
+                    let seconds = args.next().ok_or("--timeout requires a number of seconds")?;
+                    let timeout = seconds.parse::<f64>().ok()
+                        .filter(|&seconds| seconds > 0.0)
+                        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                        .ok_or_else(|| format!("Invalid timeout: {seconds}"))?;
+                    options.timeout = Some(timeout);
+                }
+                "--algo" => {
+                    let name = args.next().ok_or("--algo requires an algorithm name")?;
+                    options.algorithm = Some(name.clone());
+                }
+                "--delay" => {
+                    let millis = args.next().ok_or("--delay requires a number of milliseconds")?;
+                    match millis.parse::<u64>() {
+                        Ok(millis) => options.delay = Some(Duration::from_millis(millis)),
+                        _ => return Err(format!("Invalid delay: {millis}")),
+                    }
+                }
+                day => {
+                    let day = day.parse::<i32>().map_err(|_| format!("Invalid argument: {day}"))?;
+                    options.days.push(day);
+                }
+            }
+        }
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Check for flags that can't be used together
+    fn validate(&self) -> Result<(), String> {
+        if self.all && self.part.is_some() {
+            return Err("--part cannot be combined with --all".to_string());
+        }
+        if self.input.is_some() && (self.all || self.days.len() > 1) {
+            return Err("--input can only be used when running a single day".to_string());
+        }
+        if self.bench.is_some() && (self.part.is_some() || self.input.is_some() || self.timeout.is_some()) {
+            return Err("--bench cannot be combined with --part, --input, or --timeout".to_string());
+        }
+        if self.report.is_some() && self.part.is_some() {
+            return Err("--report cannot be combined with --part".to_string());
+        }
+        if self.command == Command::Submit && (self.days.len() != 1 || self.part.is_none()) {
+            return Err("submit requires a single day and --part".to_string());
+        }
+        if self.delay.is_some() && self.render != Some(Render::Picture) {
+            return Err("--delay can only be used with --visualize".to_string());
+        }
+        if self.render.is_some()
+            && (self.all || self.days.len() != 1 || self.part.is_some() || self.bench.is_some()) {
+            return Err("--visualize, --disasm, and --svg require a single day, and cannot be combined with --part or --bench".to_string());
+        }
+        if self.algorithm.is_some()
+            && (self.all || self.days.len() != 1 || self.render.is_some() || self.command != Command::Run) {
+            return Err("--algo requires a single day, and cannot be combined with a command or --visualize, --disasm, or --svg".to_string());
+        }
+        if self.crosscheck
+            && (self.part.is_some() || self.bench.is_some() || self.algorithm.is_some() || self.report.is_some()
+                || self.render.is_some() || self.command != Command::Run) {
+            return Err("--crosscheck cannot be combined with a command, --part, --bench, --algo, --report, or --visualize".to_string());
+        }
+        if self.command == Command::NewDay && self.days.len() != 1 {
+            return Err("new-day requires a single day".to_string());
+        }
+        if self.command == Command::Debug && self.days.len() != 1 {
+            return Err("debug requires a single day".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_days_and_part() {
+        let options = Options::parse(&args("6 --part 2 22")).unwrap();
+        assert_eq!(vec![6, 22], options.days);
+        assert_eq!(Some(2), options.part);
+        assert!(!options.all);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Options::parse(&args("6 --part 3")).is_err());
+        assert!(Options::parse(&args("6 --part")).is_err());
+        assert!(Options::parse(&args("six")).is_err());
+        assert!(Options::parse(&args("--all --part 1")).is_err());
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let options = Options::parse(&args("16 --input examples/day16.txt")).unwrap();
+        assert_eq!(vec![16], options.days);
+        assert_eq!(Some(PathBuf::from("examples/day16.txt")), options.input);
+        assert!(Options::parse(&args("16 17 --input day16.txt")).is_err());
+        assert!(Options::parse(&args("16 --input")).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let options = Options::parse(&args("--all --bench 10")).unwrap();
+        assert!(options.all);
+        assert_eq!(Some(10), options.bench);
+        assert!(Options::parse(&args("1 --bench 0")).is_err());
+        assert!(Options::parse(&args("1 --bench 5 --part 1")).is_err());
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let options = Options::parse(&args("--all --format json")).unwrap();
+        assert_eq!(Some(OutputFormat::Json), options.format);
+        assert_eq!(None, Options::parse(&args("1")).unwrap().format);
+        assert!(Options::parse(&args("1 --format yaml")).is_err());
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let options = Options::parse(&args("--all --report csv")).unwrap();
+        assert_eq!(Some(ReportFormat::Csv), options.report);
+        let options = Options::parse(&args("1 2 --report markdown")).unwrap();
+        assert_eq!(Some(ReportFormat::Markdown), options.report);
+        assert!(Options::parse(&args("1 --report pdf")).is_err());
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        let options = Options::parse(&args("--all --timeout 2.5")).unwrap();
+        assert_eq!(Some(Duration::from_millis(2500)), options.timeout);
+        assert!(Options::parse(&args("1 --timeout 0")).is_err());
+        assert!(Options::parse(&args("1 --timeout soon")).is_err());
+        // too long to fit in a Duration
+        assert_eq!(Err("Invalid timeout: inf".to_string()), Options::parse(&args("1 --timeout inf")));
+        assert_eq!(Err("Invalid timeout: 1e30".to_string()), Options::parse(&args("1 --timeout 1e30")));
+        assert!(Options::parse(&args("1 --bench 10 --timeout 5")).is_err());
+    }
+
+    #[test]
+    fn test_parse_verbose() {
+        assert!(Options::parse(&args("16 --verbose")).unwrap().verbose);
+        assert!(Options::parse(&args("-v 16")).unwrap().verbose);
+        assert!(!Options::parse(&args("16")).unwrap().verbose);
+    }
+
+    #[test]
+    fn test_parse_visualize() {
+        let options = Options::parse(&args("--visualize 24")).unwrap();
+        assert_eq!(Some(Render::Picture), options.render);
+        assert_eq!(vec![24], options.days);
+        assert!(Options::parse(&args("--visualize")).is_err());
+        assert!(Options::parse(&args("--visualize 23 24")).is_err());
+        assert!(Options::parse(&args("--visualize 24 --part 1")).is_err());
+        let options = Options::parse(&args("6 --visualize --delay 20")).unwrap();
+        assert_eq!(Some(Duration::from_millis(20)), options.delay);
+        assert!(Options::parse(&args("6 --delay 20")).is_err());
+        assert!(Options::parse(&args("6 --visualize --delay soon")).is_err());
+    }
+
+    #[test]
+    fn test_parse_disasm() {
+        let options = Options::parse(&args("17 --disasm")).unwrap();
+        assert_eq!(Some(Render::Disassembly), options.render);
+        assert_eq!(vec![17], options.days);
+        assert!(Options::parse(&args("--all --disasm")).is_err());
+        assert!(Options::parse(&args("17 --disasm --visualize")).is_err());
+    }
+
+    #[test]
+    fn test_parse_algo() {
+        let options = Options::parse(&args("18 --algo binary-search --bench 10")).unwrap();
+        assert_eq!(Some("binary-search".to_string()), options.algorithm);
+        assert_eq!(Some(10), options.bench);
+        assert!(Options::parse(&args("18 --algo")).is_err());
+        assert!(Options::parse(&args("--all --algo binary-search")).is_err());
+        assert!(Options::parse(&args("verify 18 --algo binary-search")).is_err());
+        assert!(Options::parse(&args("18 --svg --algo binary-search")).is_err());
+    }
+
+    #[test]
+    fn test_parse_crosscheck() {
+        assert!(Options::parse(&args("14 18 --crosscheck")).unwrap().crosscheck);
+        assert!(Options::parse(&args("--all --crosscheck")).unwrap().crosscheck);
+        assert!(Options::parse(&args("18 --crosscheck --algo binary-search")).is_err());
+        assert!(Options::parse(&args("18 --crosscheck --part 2")).is_err());
+    }
+
+    #[test]
+    fn test_parse_svg() {
+        let options = Options::parse(&args("--svg 16")).unwrap();
+        assert_eq!(Some(Render::Svg), options.render);
+        assert_eq!(vec![16], options.days);
+        assert!(Options::parse(&args("12 20 --svg")).is_err());
+        assert!(Options::parse(&args("12 --svg --visualize")).is_err());
+        assert!(Options::parse(&args("12 --svg --delay 20")).is_err());
+    }
+
+    #[test]
+    fn test_parse_submit() {
+        let options = Options::parse(&args("submit 6 --part 2")).unwrap();
+        assert_eq!(Command::Submit, options.command);
+        assert_eq!(vec![6], options.days);
+        assert!(Options::parse(&args("submit 6")).is_err());
+        assert!(Options::parse(&args("submit 6 7 --part 1")).is_err());
+    }
+
+    #[test]
+    fn test_parse_verify() {
+        let options = Options::parse(&args("verify")).unwrap();
+        assert_eq!(Command::Verify, options.command);
+        assert!(options.days.is_empty());
+        let options = Options::parse(&args("verify 3 4")).unwrap();
+        assert_eq!(vec![3, 4], options.days);
+    }
+
+    #[test]
+    fn test_parse_new_day() {
+        let options = Options::parse(&args("new-day 5")).unwrap();
+        assert_eq!(Command::NewDay, options.command);
+        assert_eq!(vec![5], options.days);
+        assert!(Options::parse(&args("new-day")).is_err());
+        assert!(Options::parse(&args("new-day 5 6")).is_err());
+    }
+
+    #[test]
+    fn test_parse_debug() {
+        let options = Options::parse(&args("debug 17 --input examples/day17.txt")).unwrap();
+        assert_eq!(Command::Debug, options.command);
+        assert_eq!(vec![17], options.days);
+        assert!(Options::parse(&args("debug")).is_err());
+    }
+
+    #[test]
+    fn test_parse_tui() {
+        assert_eq!(Command::Tui, Options::parse(&args("tui")).unwrap().command);
+        assert_eq!(Command::Run, Options::parse(&args("1")).unwrap().command);
+    }
+}
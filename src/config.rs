@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::day::{Context, OutputFormat};
+use crate::util::error::{AocError, Result};
+
+/// The runner looks for its config in this file, in the directory it is run from
+pub const CONFIG_FILE: &str = "advent.toml";
+
+/// Settings for the runner, read from [`CONFIG_FILE`]. Every setting is optional,
+/// and the command line takes precedence over the file.
+///
+/// ```toml
+/// input_dir = "resources"
+/// session_file = ".aoc/session"
+/// format = "json"
+///
+/// [day18]
+/// algorithm = "binary-search"
+/// size = 71
+/// fallen = 1024
+/// ```
+///
+/// Each `[dayN]` table picks one of the day's algorithms and sets any of its parameters
+/// (see [`Day::PARAMS`](crate::day::Day::PARAMS)). Parameters are integers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// The puzzle inputs are read from `dayN.txt` in this directory
+    pub input_dir: PathBuf,
+    /// The Advent of Code session token is read from this file, if `AOC_SESSION` isn't set
+    pub session_file: PathBuf,
+    pub format: OutputFormat,
+    pub days: BTreeMap<i32, Context>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            input_dir: PathBuf::from("resources"),
+            session_file: PathBuf::from(".aoc/session"),
+            format: OutputFormat::default(),
+            days: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Read the config file, or use the defaults if there isn't one
+    ///
+    /// # Errors
+    /// If the file cannot be read or is invalid
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| match e {
+                AocError::Config(message) => AocError::Config(format!("{}: {message}", path.display())),
+                e => e,
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(AocError::Io(path.to_path_buf(), e)),
+        }
+    }
+
+    /// Parse the contents of a config file
+    ///
+    /// # Errors
+    /// If the text is not valid toml, or has a setting that doesn't exist or has the wrong type
+    pub fn parse(text: &str) -> Result<Self> {
+        let table = text.parse::<toml::Table>().map_err(|e| AocError::Config(e.to_string()))?;
+        let mut config = Self::default();
+        for (key, value) in &table {
+            match (key.as_str(), value) {
+                ("input_dir", toml::Value::String(dir)) => config.input_dir = PathBuf::from(dir),
+                ("session_file", toml::Value::String(file)) => config.session_file = PathBuf::from(file),
+                ("format", toml::Value::String(format)) => config.format = match format.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    other => return Err(AocError::Config(format!("format must be text or json, found {other}"))),
+                },
+                (name, toml::Value::Table(settings)) => {
+                    let day = name.strip_prefix("day")
+                        .and_then(|day| day.parse().ok())
+                        .ok_or_else(|| AocError::Config(format!("unknown section [{name}]")))?;
+                    config.days.insert(day, day_context(name, settings)?);
+                }
+                (key, value) => return Err(AocError::Config(format!("unknown setting {key} = {value}"))),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Where to find the puzzle input for a day
+    #[must_use]
+    pub fn input_path(&self, day: i32) -> PathBuf {
+        self.input_dir.join(format!("day{day}.txt"))
+    }
+
+    /// The algorithm and parameters to solve a day with
+    #[must_use]
+    pub fn context(&self, day: i32) -> Context {
+        self.days.get(&day).cloned().unwrap_or_default()
+    }
+}
+
+/// The context from a `[dayN]` table: the algorithm is a string, everything else is a parameter
+fn day_context(name: &str, settings: &toml::Table) -> Result<Context> {
+    let mut context = Context::default();
+    for (key, value) in settings {
+        context = match (key.as_str(), value) {
+            ("algorithm", toml::Value::String(algorithm)) => context.with_algorithm(Some(algorithm)),
+            (param, toml::Value::Integer(value)) if param != "algorithm" => context.with_param(param, *value),
+            (key, value) => return Err(AocError::Config(format!("invalid setting {key} = {value} in [{name}]"))),
+        };
+    }
+    Ok(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse("input_dir = \"inputs\"
+format = \"json\"
+
+[day18]
+algorithm = \"binary-search\"
+size = 7
+
+[day20]
+min_saving = 50
+").unwrap();
+        assert_eq!(PathBuf::from("inputs/day18.txt"), config.input_path(18));
+        assert_eq!(PathBuf::from(".aoc/session"), config.session_file);
+        assert_eq!(OutputFormat::Json, config.format);
+        assert_eq!(Context::default().with_algorithm(Some("binary-search")).with_param("size", 7), config.context(18));
+        assert_eq!(Context::default().with_param("min_saving", 50), config.context(20));
+        assert_eq!(Context::default(), config.context(1));
+        assert_eq!(Config::default(), Config::parse("").unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for text in ["input_dir = ", "inputs = \"inputs\"", "format = \"xml\"", "[days]\nsize = 7",
+                "[day18]\nsize = \"7\"", "[day18]\nalgorithm = 1"] {
+            assert!(matches!(Config::parse(text), Err(AocError::Config(_))), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_load_missing() {
+        assert_eq!(Config::default(), Config::load(Path::new("missing/advent.toml")).unwrap());
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::Path;
+
+use advent2024::config::Config;
+use advent2024::day::{Answer, DayRunner};
+
+/// The answers for both parts from one algorithm, or why it failed
+type Answers = Result<(Answer, Answer), String>;
+
+/// Compare the answers from every algorithm to the first (the default),
+/// returning a description of each difference
+#[must_use]
+pub fn disagreements(results: &[(&str, Answers)]) -> Vec<String> {
+    let parts = |answers: &Answers| match answers {
+        Ok((part1, part2)) => [Ok(part1.clone()), Ok(part2.clone())],
+        Err(e) => [Err(e.clone()), Err(e.clone())],
+    };
+    let describe = |answer: &Result<Answer, String>| match answer {
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("an error ({e})"),
+    };
+    let Some((default, default_answers)) = results.first() else {
+        return Vec::new();
+    };
+    let expected = parts(default_answers);
+    results.iter().skip(1)
+        .flat_map(|(name, answers)| {
+            let expected = &expected;
+            parts(answers).into_iter().zip(1 ..).filter_map(move |(actual, part)| {
+                let expected = &expected[part - 1];
+                (actual != *expected).then(|| {
+                    format!("part {part}: {name} gave {}, but {default} gave {}", describe(&actual), describe(expected))
+                })
+            })
+        })
+        .collect()
+}
+
+/// Solve each day with every one of its algorithms and report any answers that don't agree,
+/// to check a new algorithm before trusting it. Days with only one algorithm are skipped.
+/// Uses the input file at `input` if provided, otherwise the one from the `config`,
+/// and any parameters the `config` sets for the day. Returns true if every algorithm agreed.
+pub fn crosscheck(runners: &[Box<dyn DayRunner>], input: Option<&Path>, config: &Config) -> bool {
+    let mut success = true;
+    for runner in runners {
+        let day = runner.day();
+        let algorithms = runner.algorithms();
+        if algorithms.is_empty() {
+            println!("Day {day}: only one algorithm");
+            continue;
+        }
+        let path = input.map_or_else(|| config.input_path(day), Path::to_path_buf);
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) => {
+                success = false;
+                println!("Day {day}: failed to read {}: {e}", path.display());
+                continue;
+            }
+        };
+        let results = algorithms.iter()
+            .map(|&algorithm| {
+                let context = config.context(day).with_algorithm(Some(algorithm));
+                (algorithm, runner.answers(&raw, &context).map_err(|e| e.to_string()))
+            })
+            .collect::<Vec<_>>();
+        let differences = disagreements(&results);
+        if differences.is_empty() {
+            println!("Day {day}: {} agree", algorithms.join(", "));
+        } else {
+            success = false;
+            for difference in differences {
+                println!("Day {day} {difference}");
+            }
+        }
+    }
+    success
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent2024::day::find_day;
+
+    fn answers(part1: i64, part2: i64) -> (Answer, Answer) {
+        (Answer::Int(part1), Answer::Int(part2))
+    }
+
+    #[test]
+    fn test_disagreements() {
+        assert!(disagreements(&[]).is_empty());
+        assert!(disagreements(&[("a", Ok(answers(1, 2))), ("b", Ok(answers(1, 2)))]).is_empty());
+        assert_eq!(
+            vec!["part 2: b gave 3, but a gave 2"],
+            disagreements(&[("a", Ok(answers(1, 2))), ("b", Ok(answers(1, 3)))]),
+        );
+        assert_eq!(
+            vec!["part 1: b gave an error (slow), but a gave 1", "part 2: b gave an error (slow), but a gave 2"],
+            disagreements(&[("a", Ok(answers(1, 2))), ("b", Err("slow".to_string()))]),
+        );
+    }
+
+    #[test]
+    fn test_crosscheck_without_input() {
+        let runners = [find_day(18).unwrap()];
+        assert!(!crosscheck(&runners, Some(Path::new("resources/missing.txt")), &Config::default()));
+    }
+}
@@ -0,0 +1,101 @@
+use std::convert::Infallible;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// The answer to one part of a puzzle.
+/// Most answers are numbers, so they are kept as numbers to be compared or range checked
+/// without parsing. Anything else (ex: a comma separated list) is text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+}
+
+impl Answer {
+    /// The answer as a number, or `None` if it is text
+    #[must_use]
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            Self::Text(_) => None,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(value) => Display::fmt(value, f),
+            Self::Text(text) => f.pad(text),
+        }
+    }
+}
+
+/// Read an answer the way it would be written: a number if the whole string is one, otherwise text
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse().map_or_else(|_| Self::Text(s.to_string()), Self::Int))
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Self::Int(value.into())
+            }
+        })*
+    };
+}
+
+answer_from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! answer_from_wide_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            /// A number too large for an `i64` is kept as text
+            fn from(value: $t) -> Self {
+                i64::try_from(value).map_or_else(|_| Self::Text(value.to_string()), Self::Int)
+            }
+        })*
+    };
+}
+
+answer_from_wide_int!(u64, u128, i128, usize, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::Int(7036), 7036_usize.into());
+        assert_eq!(Answer::Int(-3), (-3_i32).into());
+        assert_eq!(Answer::Text(u64::MAX.to_string()), u64::MAX.into());
+        assert_eq!(Answer::Text("4,6,3".to_string()), "4,6,3".into());
+        assert_eq!(Some(12), Answer::Int(12).as_int());
+        assert_eq!(None, Answer::from("co,de").as_int());
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(Ok(Answer::Int(1_151_792)), "1151792".parse());
+        assert_eq!(Ok(Answer::Text("6,1".to_string())), "6,1".parse());
+        assert_eq!("  42", format!("{:>4}", Answer::Int(42)));
+        assert_eq!("ab  |", format!("{:<4}|", Answer::from("ab")));
+    }
+}
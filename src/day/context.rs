@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+
+use crate::util::error::{AocError, Result};
+
+/// How to solve a day: which of its algorithms to use, and values for its parameters.
+/// Built from `advent.toml` (see [`Config`](crate::config::Config)) and the command line,
+/// and passed to every step that solves a day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Context {
+    /// One of the day's [`Day::ALGORITHMS`](super::Day::ALGORITHMS), or `None` for the default
+    pub algorithm: Option<String>,
+    /// Values for the day's [`Day::PARAMS`](super::Day::PARAMS) by name. Anything not set uses the day's default.
+    pub params: BTreeMap<String, i64>,
+}
+
+impl Context {
+    /// Solve with the named algorithm, or the default for `None`
+    #[must_use]
+    pub fn with_algorithm(mut self, algorithm: Option<&str>) -> Self {
+        self.algorithm = algorithm.map(str::to_string);
+        self
+    }
+
+    /// Set a parameter
+    #[must_use]
+    pub fn with_param(mut self, name: &str, value: i64) -> Self {
+        self.params.insert(name.to_string(), value);
+        self
+    }
+
+    #[must_use]
+    pub fn algorithm(&self) -> Option<&str> {
+        self.algorithm.as_deref()
+    }
+
+    /// The value of a parameter, or `default` if it isn't set
+    ///
+    /// # Errors
+    /// If the value doesn't fit in a `T`
+    pub fn param<T: TryFrom<i64>>(&self, name: &str, default: T) -> Result<T> {
+        match self.params.get(name) {
+            Some(&value) => T::try_from(value)
+                .map_err(|_| AocError::Config(format!("{name} = {value} is out of range"))),
+            None => Ok(default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        let context = Context::default().with_algorithm(Some("memo")).with_param("size", 7);
+        assert_eq!(Some("memo"), context.algorithm());
+        assert_eq!(7, context.param("size", 71).unwrap());
+        assert_eq!(1024_usize, context.param("fallen", 1024).unwrap());
+        assert!(context.with_param("size", -1).param::<usize>("size", 71).is_err());
+    }
+}
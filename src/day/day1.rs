@@ -1,5 +1,6 @@
-use super::Day;
-use std::fs;
+use super::{Day, IntoAnswer};
+use crate::util::collections::Counter;
+use crate::util::error::{AocError, Result};
 
 /// Day 1: Historian Hysteria 
 /// 
@@ -15,74 +16,54 @@ use std::fs;
 pub struct Day1;
 
 impl Day<(Vec<i32>, Vec<i32>)> for Day1 {
-    fn read_input() -> (Vec<i32>, Vec<i32>) {
-        let input = fs::read_to_string("resources/day1.txt").expect("file day1.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<(Vec<i32>, Vec<i32>)> {
+        parse_input(input)
     }
 
-    fn part1(input: &(Vec<i32>, Vec<i32>)) -> impl std::fmt::Display {
+    fn part1(input: &(Vec<i32>, Vec<i32>)) -> Result<impl IntoAnswer> {
         let (mut left, mut right) = input.clone();
         left.sort_unstable();
         right.sort_unstable();
-        left.into_iter().zip(right)
+        Ok(left.into_iter().zip(right)
             .map(|(a, b)| i32::max(a, b) - i32::min(a, b))
-            .sum::<i32>()
+            .sum::<i32>())
     }
 
-    #[allow(clippy::cast_possible_truncation)]
-    fn part2(input: &(Vec<i32>, Vec<i32>)) -> impl std::fmt::Display {
+    // a count is at most the length of the list, which fits in an i32 for any puzzle input
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn part2(input: &(Vec<i32>, Vec<i32>)) -> Result<impl IntoAnswer> {
         let (left, right) = input;
-        left.iter()
-            .map(|lhs| {
-                let count = right.iter()
-                    .filter(|rhs| lhs == *rhs)
-                    .count();
-                lhs * count as i32 
-            })
-            .sum::<i32>()
+        let right = right.iter().collect::<Counter<_>>();
+        Ok(left.iter()
+            .map(|lhs| lhs * right.count(&lhs) as i32)
+            .sum::<i32>())
     }
 }
 
-fn parse_input(input: &str) -> (Vec<i32>, Vec<i32>) {
+fn parse_input(input: &str) -> Result<(Vec<i32>, Vec<i32>)> {
     let lines = input.lines()
         .map(|line| line
             .split_whitespace()
-            .map(|item| item.parse().expect("Invalid Int"))
-            .collect::<Vec<i32>>()
+            .map(str::parse)
+            .collect::<Result<Vec<i32>, _>>()
         )
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, _>>()?;
     let mut left = Vec::new();
     let mut right = Vec::new();
     for line in lines {
-        left.push(line[0]);
-        right.push(line[1]);
+        let [lhs, rhs] = line[..] else {
+            return Err(AocError::parse(format!("expected 2 numbers per line, found {}", line.len())));
+        };
+        left.push(lhs);
+        right.push(rhs);
     }
-    (left, right)
+    Ok((left, right))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "3   4
-        4   3
-        2   5
-        1   3
-        3   9
-        3   3";
-
-    #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST_INPUT);
-        let result =  Day1::part1(&input);
-        assert_eq!("11", result.to_string())
-    }
-
-    #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST_INPUT);
-        let result =  Day1::part2(&input);
-        assert_eq!("31", result.to_string())
-    }
+    use crate::aoc_test;
 
+    aoc_test!(day1, part1, example 1, "11");
+    aoc_test!(day1, part2, example 1, "31");
 }
\ No newline at end of file
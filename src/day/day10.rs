@@ -1,7 +1,9 @@
-use crate::util::vec2d::{Directions, Vec2d};
+use crate::util::grid::prelude::*;
 
-use super::Day;
-use std::{collections::HashSet, fs};
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::pathfinding::bfs;
+use std::collections::HashSet;
 
 /// Day 10: Hoof It
 /// We need to reconstruct possible trails from a topographic map. The map (puzzle input)
@@ -16,120 +18,142 @@ use std::{collections::HashSet, fs};
 pub struct Day10;
 
 impl Day<Vec2d<i32>> for Day10 {
-    fn read_input() -> Vec2d<i32> {
-        let input = fs::read_to_string("resources/day10.txt").expect("file day10.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec2d<i32>> {
+        parse_input(input)
     }
 
     // Solved via breadth first search
-    fn part1(input: &Vec2d<i32>) -> impl std::fmt::Display {
-        let trail_starts = input.grid.iter()
-            .enumerate()
-            .filter(|(_, &digit)| digit == 0)
-            .map(|(idx, _)| input.idx_to_point(idx))
-            .collect::<Vec<_>>();
-        let mut sum = 0;
-        for start in trail_starts {
-            let mut queue = Vec::new();
-            queue.push(start);
-            let mut end_points = HashSet::new();
-            while let Some(current) = queue.pop() {
-                if input[current] == 9 {
-                    end_points.insert(current);
-                    continue;
-                }
-                [Directions::Up, Directions::Down, Directions::Left, Directions::Right]
-                    .into_iter()
-                    .filter_map(|direction| input.next_point(current, direction))
-                    .filter(|&point| input[point] == input[current] + 1)
-                    .for_each(|point| queue.push(point));
-            }
-            sum += end_points.len();
-        }
-        sum
+    fn part1(input: &Vec2d<i32>) -> Result<impl IntoAnswer> {
+        Ok(input.find_all(&0).into_iter()
+            .map(|start| bfs(start, |&point| uphill(input, point))
+                .filter(|&(point, _)| input[point] == 9)
+                .count()
+            )
+            .sum::<usize>())
+    }
 
+    fn part2(input: &Vec2d<i32>) -> Result<impl IntoAnswer> {
+        let counts = trail_counts(input);
+        Ok(input.find_all(&0).into_iter()
+            .map(|start| counts[start])
+            .sum::<u64>())
     }
+}
 
-    // Very close to part 1, but greedily keep track of the number of trails while traversing them
-    fn part2(input: &Vec2d<i32>) -> impl std::fmt::Display {
-        let trail_starts = input.grid.iter()
-            .enumerate()
-            .filter(|(_, &digit)| digit == 0)
-            .map(|(idx, _)| input.idx_to_point(idx))
-            .collect::<Vec<_>>();
-        let mut sum = 0;
-        for start in trail_starts {
-            let mut queue = Vec::new();
-            queue.push(start);
-            let mut num_trails = 1;
-            while let Some(current) = queue.pop() {
-                if input[current] == 9 {
-                    continue;
-                }
-                let next_points = [Directions::Up, Directions::Down, Directions::Left, Directions::Right]
-                    .into_iter()
-                    .filter_map(|direction| input.next_point(current, direction))
-                    .filter(|&point| input[point] == input[current] + 1)
-                    .collect::<Vec<_>>();
-                
-                // Count the number of times the trail branches into a new path
-                // subtract if the branch hits a dead end
-                if next_points.is_empty() {
-                    num_trails -= 1;
-                } else {
-                    num_trails += next_points.len() - 1;
-                }
-
-                for p in next_points {
-                    queue.push(p);
-                }
-            }
-            sum += num_trails;
+/// The number of distinct trails from each point up to any 9.
+/// A 9 is the end of exactly one trail, and every other point has the trails of each point
+/// one higher next to it. Filling in the counts from the top down means every neighbor
+/// is already counted when it's needed.
+fn trail_counts(input: &Vec2d<i32>) -> Vec2d<u64> {
+    let mut counts = Vec2d { grid: vec![0; input.grid.len()], line_len: input.line_len };
+    for height in (0 ..= 9).rev() {
+        for point in input.find_all(&height) {
+            counts[point] = if height == 9 {
+                1
+            } else {
+                uphill(input, point).into_iter().map(|next| counts[next]).sum()
+            };
         }
-        sum
     }
+    counts
+}
+
+/// Every trail from a single trailhead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trails {
+    pub trailhead: Point,
+    /// The 9s that can be reached from the trailhead (the score)
+    pub summits: HashSet<Point>,
+    /// The number of distinct trails to any summit (the rating)
+    pub rating: u64,
+    /// The distinct trails, each from the trailhead to a summit.
+    /// Only the first `max_paths` are listed, so this can be shorter than the rating.
+    pub paths: Vec<Vec<Point>>,
 }
 
-fn parse_input(input: &str) -> Vec2d<i32> {
-    let grid = input.lines()
-        .flat_map(|line| line.trim()
-            .chars()
-            .map(|c| c.to_digit(10).unwrap().try_into().unwrap())
-            .collect::<Vec<_>>()
-        )
-        .collect();
-    let line_len = input.lines().next().unwrap().len();
-    Vec2d {
-        grid,
-        line_len: line_len as i32,
+/// The trails from each trailhead, listing at most `max_paths` full trails for each one
+#[must_use]
+pub fn trails(input: &Vec2d<i32>, max_paths: usize) -> Vec<Trails> {
+    let counts = trail_counts(input);
+    input.find_all(&0).into_iter()
+        .map(|trailhead| {
+            let summits = bfs(trailhead, |&point| uphill(input, point))
+                .map(|(point, _)| point)
+                .filter(|&point| input[point] == 9)
+                .collect();
+            let mut paths = Vec::new();
+            collect_paths(input, &mut vec![trailhead], max_paths, &mut paths);
+            Trails { trailhead, summits, rating: counts[trailhead], paths }
+        })
+        .collect()
+}
+
+/// Depth first search for the trails continuing from the end of `path`, until there are `max_paths`
+fn collect_paths(input: &Vec2d<i32>, path: &mut Vec<Point>, max_paths: usize, paths: &mut Vec<Vec<Point>>) {
+    let Some(&current) = path.last() else {
+        return;
+    };
+    if input[current] == 9 {
+        if paths.len() < max_paths {
+            paths.push(path.clone());
+        }
+        return;
+    }
+    for next in uphill(input, current) {
+        if paths.len() >= max_paths {
+            return;
+        }
+        path.push(next);
+        collect_paths(input, path, max_paths, paths);
+        path.pop();
     }
 }
 
+/// The neighboring points that are exactly one higher than `point`
+fn uphill(input: &Vec2d<i32>, point: Point) -> Vec<Point> {
+    Directions::cardinal()
+        .into_iter()
+        .filter_map(|direction| input.next_point(point, direction))
+        .filter(|&next| input[next] == input[point] + 1)
+        .collect()
+}
+
+fn parse_input(input: &str) -> Result<Vec2d<i32>> {
+    Vec2d::parse_with(input, |c| c.to_digit(10)
+        .and_then(|digit| digit.try_into().ok())
+        .ok_or_else(|| AocError::parse(format!("invalid height {c}")))
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
 
-    const TEST: &str = "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732";
+    aoc_test!(day10, part1, example 1, "36");
+    aoc_test!(day10, part2, example 1, "81");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day10::part1(&input);
-        assert_eq!("36", result.to_string())
-    }
+    fn test_trails() {
+        let input = parse_input(&example(10, 1)).unwrap();
+        let listed = trails(&input, 10);
+        assert_eq!(9, listed.len());
+        assert_eq!(36, listed.iter().map(|t| t.summits.len()).sum::<usize>());
+        assert_eq!(81, listed.iter().map(|t| t.rating).sum::<u64>());
 
-    #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result =  Day10::part2(&input);
-        assert_eq!("81", result.to_string())
-    }
+        // the first trailhead has a rating of 20, more than the 10 trails listed
+        let first = &listed[0];
+        assert_eq!((Point::new(2, 0), 20, 10), (first.trailhead, first.rating, first.paths.len()));
+        for path in &first.paths {
+            assert_eq!((0 ..= 9).collect::<Vec<_>>(), path.iter().map(|&point| input[point]).collect::<Vec<_>>());
+            assert!(first.summits.contains(&path[9]));
+        }
+        let distinct = first.paths.iter().collect::<HashSet<_>>();
+        assert_eq!(10, distinct.len());
 
+        // with no cap, every trail is listed
+        let all = trails(&input, usize::MAX);
+        assert!(all.iter().all(|t| u64::try_from(t.paths.len()).unwrap() == t.rating));
+    }
 }
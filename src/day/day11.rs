@@ -1,12 +1,15 @@
-use super::Day;
-use std::{collections::HashMap, fs};
+use super::{Answer, Context, Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::hash::{FxBuildHasher, FxHashMap};
+use crate::util::memo::Memo;
+use tracing::debug;
 
 /// Day 11: Plutonian Pebbles
 /// 
 /// A line of rocks changes every time you blink. It changes according to the rules
 /// * If the rock is 0, it becomes 1
 /// * If the rock has an even number of digits, it splits into two rocks.
-///     ex: `22 -> 2 2` or 9908 -> 99 8
+///   ex: `22 -> 2 2` or 9908 -> 99 8
 /// * Otherwise the rock becomes itself * 2024
 /// 
 /// Part 1: How many rocks exist if you blink 25 times?
@@ -15,17 +18,33 @@ use std::{collections::HashMap, fs};
 pub struct Day11;
 
 impl Day<Vec<i64>> for Day11 {
-    fn read_input() -> Vec<i64> {
-        let input = fs::read_to_string("resources/day11.txt").expect("file day11.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec<i64>> {
+        parse_input(input)
     }
 
-    fn part1(input: &Vec<i64>) -> impl std::fmt::Display {
-        count_rocks(input, 25)
+    fn part1(input: &Vec<i64>) -> Result<impl IntoAnswer> {
+        Ok(count_rocks(input, 25))
     }
 
-    fn part2(input: &Vec<i64>) -> impl std::fmt::Display {
-        count_rocks(input, 75)
+    fn part2(input: &Vec<i64>) -> Result<impl IntoAnswer> {
+        Ok(count_rocks(input, 75))
+    }
+
+    const ALGORITHMS: &'static [&'static str] = &["counts", "memo"];
+
+    fn part1_with(input: &Vec<i64>, context: &Context) -> Result<Answer> {
+        Ok(count_with(input, 25, context.algorithm()).into())
+    }
+
+    fn part2_with(input: &Vec<i64>, context: &Context) -> Result<Answer> {
+        Ok(count_with(input, 75, context.algorithm()).into())
+    }
+}
+
+fn count_with(rocks: &[i64], blinks: i64, algorithm: Option<&str>) -> i64 {
+    match algorithm {
+        Some("memo") => count_rocks_memo(rocks, blinks),
+        _ => count_rocks(rocks, blinks),
     }
 }
 
@@ -33,57 +52,96 @@ impl Day<Vec<i64>> for Day11 {
 /// But rock numbers will repeat, and there will be multiples of the same rocks at a given time.
 /// Instead of a list of all rocks, keep of count of the different rock values that exist
 fn count_rocks(rocks: &[i64], blinks: i64) -> i64 {
-    let mut memo: HashMap<i64, Vec<i64>> = HashMap::new();
     let mut rock_counts = rocks.iter()
         .map(|&r| (r, 1)) // start with 1 of each rock
-        .collect::<HashMap<_,_>>();
+        .collect::<FxHashMap<_,_>>();
+    // Swap between two maps rather than building a new one every blink
+    let mut updated_counts = FxHashMap::with_capacity_and_hasher(rock_counts.capacity(), FxBuildHasher::default());
 
     for _ in 0 .. blinks {
-        let mut updated_counts = HashMap::new();
-        for rock in rock_counts.keys() {
-            let current_count = rock_counts[rock];
-            for &new_rock in blink_rock(*rock, &mut memo) {
-                *updated_counts.entry(new_rock).or_insert(0) += current_count;
+        updated_counts.clear();
+        for (&rock, &current_count) in &rock_counts {
+            let (new_rock, split_rock) = blink_rock(rock);
+            *updated_counts.entry(new_rock).or_insert(0) += current_count;
+            if let Some(split_rock) = split_rock {
+                *updated_counts.entry(split_rock).or_insert(0) += current_count;
             }
         }
-        rock_counts = updated_counts;
+        std::mem::swap(&mut rock_counts, &mut updated_counts);
     }
     rock_counts.values().sum()
 }
 
-/// Calculate the next rock or rocks that exist after a blink from the passed in rock
-/// 
-/// The `memo` here is left over from a failed DFS implementation. It's probably not necessary
-/// as all it does is remember the result of a single blick applied to a i32.
-/// But it's staying because I made it work with lifetimes and it probably saves a few ms overall.
-fn blink_rock(rock: i64, memo: &mut HashMap<i64, Vec<i64>>) -> &Vec<i64> {
-    memo.entry(rock).or_insert_with(|| {
-        if rock == 0 {
-            vec![rock + 1]
-        } else if rock.to_string().len() % 2 == 0 {
-            let rock_string = rock.to_string();
-            vec![&rock_string[.. rock_string.len() / 2], &rock_string[rock_string.len() / 2 ..]].into_iter()
-                .map(|s| s.parse().unwrap())
-                .collect()
-        } else {
-            vec![rock * 2024]
-        }
+/// Count the rocks each starting rock turns into one at a time, recursively,
+/// remembering how many rocks a given rock turns into after a given number of blinks.
+fn count_rocks_memo(rocks: &[i64], blinks: i64) -> i64 {
+    let mut memo = Memo::new();
+    let total = rocks.iter().map(|&rock| rocks_after(&mut memo, rock, blinks)).sum();
+    let stats = memo.stats();
+    debug!(hits = stats.hits, misses = stats.misses, entries = stats.len, "memo");
+    total
+}
+
+fn rocks_after(memo: &mut Memo<(i64, i64), i64>, rock: i64, blinks: i64) -> i64 {
+    if blinks == 0 {
+        return 1;
+    }
+    memo.get_or_compute((rock, blinks), |memo| {
+        let (new_rock, split_rock) = blink_rock(rock);
+        rocks_after(memo, new_rock, blinks - 1) + split_rock.map_or(0, |split| rocks_after(memo, split, blinks - 1))
     })
-} 
+}
+
+/// Calculate the next rock after a blink from the passed in rock,
+/// and the second rock if it splits in two
+fn blink_rock(rock: i64) -> (i64, Option<i64>) {
+    if rock == 0 {
+        return (1, None);
+    }
+    let digits = rock.ilog10() + 1;
+    if digits.is_multiple_of(2) {
+        // the left half of the digits, and the right half
+        let half = 10_i64.pow(digits / 2);
+        (rock / half, Some(rock % half))
+    } else {
+        (rock * 2024, None)
+    }
+}
 
-fn parse_input(input: &str) -> Vec<i64> {
-    input.split_whitespace().map(|s| s.parse().unwrap()).collect()
+fn parse_input(input: &str) -> Result<Vec<i64>> {
+    let rocks = input.split_whitespace().map(str::parse).collect::<Result<Vec<i64>, _>>()?;
+    match rocks.iter().find(|&&rock| rock < 0) {
+        Some(rock) => Err(AocError::parse(format!("rocks can't have negative numbers, found {rock}"))),
+        None => Ok(rocks),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
+
+    aoc_test!(day11, part1, example 1, "55312");
+
+    #[test]
+    fn test_memo() {
+        let input = parse_input(&example(11, 1)).unwrap();
+        let memo = Context::default().with_algorithm(Some("memo"));
+        assert_eq!(Answer::Int(55312), Day11::part1_with(&input, &memo).unwrap());
+        assert_eq!(count_rocks(&input, 30), count_rocks_memo(&input, 30));
+    }
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input("125 17");
-        let result =  Day11::part1(&input);
-        assert_eq!("55312", result.to_string())
+    fn test_blink_rock() {
+        assert_eq!((1, None), blink_rock(0));
+        assert_eq!((99, Some(8)), blink_rock(9908));
+        assert_eq!((10, Some(0)), blink_rock(1000));
+        assert_eq!((2024, None), blink_rock(1));
     }
 
+    #[test]
+    fn test_negative_rock() {
+        assert!(matches!(parse_input("-5 3"), Err(AocError::Parse(_))));
+    }
 }
@@ -1,7 +1,10 @@
 use crate::util::grid::prelude::*;
 
-use super::Day;
-use std::{collections::HashSet, fs};
+use super::{Day, IntoAnswer};
+use crate::util::dsu::DisjointSet;
+use crate::util::error::Result;
+use crate::util::viz::{distinct_color, Svg};
+use std::collections::HashSet;
 
 /// Day 12: Garden Groups
 /// 
@@ -12,7 +15,7 @@ use std::{collections::HashSet, fs};
 /// Part 1: For each grouping, find the area and the perimiter. Multiply together and sum.
 /// 
 /// Part 2: Instead of perimeter, use the number of sides in a the shape.
-/// ```
+/// ```text
 /// .......
 /// ..iii..  area = 9
 /// ..iii..  perimeter = 12
@@ -22,45 +25,63 @@ use std::{collections::HashSet, fs};
 pub struct Day12;
 
 impl Day<Vec2d<char>> for Day12 {
-    fn read_input() -> Vec2d<char> {
-        let input = fs::read_to_string("resources/day12.txt").expect("file day12.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec2d<char>> {
+        parse_input(input)
     }
 
-    fn part1(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let regions = group_regions(input);
-        regions.into_iter()
-            .map(|region| region.len() * calc_perimeter(input, &region))
-            .sum::<usize>()
+    fn part1(input: &Vec2d<char>) -> Result<impl IntoAnswer> {
+        Ok(analyze(input).into_iter()
+            .map(|region| region.area * region.perimeter)
+            .sum::<usize>())
     }
 
-    fn part2(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let regions = group_regions(input);
-        regions.into_iter()
-            .map(|region| region.len() * calc_perimeter_sides(input, &region))
-            .sum::<usize>()
+    fn part2(input: &Vec2d<char>) -> Result<impl IntoAnswer> {
+        Ok(analyze(input).into_iter()
+            .map(|region| region.area * region.sides)
+            .sum::<usize>())
     }
 }
 
+/// The measurements of a single region of plants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionStats {
+    pub plant: char,
+    pub area: usize,
+    pub perimeter: usize,
+    pub sides: usize,
+    pub points: HashSet<Point>,
+}
+
+/// Measure every region in the garden, in the order their top left plant appears
+#[must_use]
+pub fn analyze(input: &Vec2d<char>) -> Vec<RegionStats> {
+    group_regions(input).into_iter()
+        .map(|points| RegionStats {
+            plant: points.iter().next().map_or(' ', |&point| input[point]),
+            area: points.len(),
+            perimeter: calc_perimeter(input, &points),
+            sides: calc_perimeter_sides(input, &points),
+            points,
+        })
+        .collect()
+}
+
 fn group_regions(input: &Vec2d<char>) -> Vec<HashSet<Point>> {
-    let mut regions: Vec<HashSet<Point>> = Vec::new();
-    for (idx, &c) in input.grid.iter().enumerate() {
-        let point = input.idx_to_point(idx);
-        if regions.iter().any(|region| region.contains(&point)) {
-            continue;
-        }
-        let mut region = HashSet::new();
-        let mut search = vec![point];
-        while let Some(p) = search.pop() {
-            region.insert(p);
-            [Directions::Up, Directions::Down, Directions::Left, Directions::Right].into_iter()
-                .filter_map(|direction| input.next_point(p, direction))
-                .filter(|&neighbor| input[neighbor] == c && !region.contains(&neighbor))
-                .for_each(|neighbor| search.push(neighbor));
-        }
-        regions.push(region);
+    DisjointSet::from_grid(input, |a, b| a == b)
+        .groups()
+        .into_iter()
+        .map(|group| group.into_iter().map(|idx| input.idx_to_point(idx)).collect())
+        .collect()
+}
+
+/// An SVG picture of the garden, with each region filled in its own color
+#[must_use]
+pub fn to_svg(input: &Vec2d<char>) -> String {
+    let mut svg = Svg::new(input.line_len, input.height());
+    for (idx, region) in group_regions(input).into_iter().enumerate() {
+        svg.region(region, &distinct_color(idx));
     }
-    regions
+    svg.to_string()
 }
 
 fn calc_perimeter(input: &Vec2d<char>, region: &HashSet<Point>) -> usize {
@@ -68,9 +89,8 @@ fn calc_perimeter(input: &Vec2d<char>, region: &HashSet<Point>) -> usize {
     for &point in region {
         let c = input[point];
         // count all border spaces that are not the same character as the region
-        perimeter += [Directions::Up, Directions::Down, Directions::Left, Directions::Right].into_iter()
-            .map(|direction| input.next_point(point, direction))
-            .filter(|border| border.map(|p| input[p]).unwrap_or('?') != c)
+        perimeter += Directions::cardinal().into_iter()
+            .filter(|&direction| input.get(input.next_unbounded(point, direction)) != Some(&c))
             .count();
     }
     perimeter
@@ -115,44 +135,51 @@ fn calc_perimeter_sides(input: &Vec2d<char>, region: &HashSet<Point>) -> usize {
     exterior_corners + interior_corners
 }
 
-fn parse_input(input: &str) -> Vec2d<char>{
-    let chars = input.lines()
-        .flat_map(|line| line.trim().chars().collect::<Vec<_>>())
-        .collect();
-    let line_len = input.lines().next().unwrap().len();
-    Vec2d {
-        grid: chars,
-        line_len: line_len as i32,
-    }
+fn parse_input(input: &str) -> Result<Vec2d<char>> {
+    input.parse()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
 
-    const TEST: &str = "RRRRIICCFF
-RRRRIICCCF
-VVRRRCCFFF
-VVRCCCJFFF
-VVVVCJJCFE
-VVIVCCJJEE
-VVIIICJJEE
-MIIIIIJJEE
-MIIISIJEEE
-MMMISSJEEE";
+    aoc_test!(day12, part1, example 1, "1930");
+    aoc_test!(day12, part2, example 1, "1206");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day12::part1(&input);
-        assert_eq!("1930", result.to_string())
+    fn test_analyze() {
+        let input = parse_input("AAAA\nBBCD\nBBCC\nEEEC").unwrap();
+        let stats = analyze(&input).into_iter()
+            .map(|region| (region.plant, region.area, region.perimeter, region.sides))
+            .collect::<Vec<_>>();
+        let expected = vec![('A', 4, 10, 4), ('B', 4, 8, 4), ('C', 4, 10, 8), ('D', 1, 4, 4), ('E', 3, 8, 4)];
+        assert_eq!(expected, stats);
     }
 
     #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result =  Day12::part2(&input);
-        assert_eq!("1206", result.to_string())
+    fn test_interior_corners() {
+        // the E counts the sides on the inside of each arm
+        let input = parse_input("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE").unwrap();
+        let e = &analyze(&input)[0];
+        assert_eq!(('E', 17, 12), (e.plant, e.area, e.sides));
+
+        // the two B regions touch diagonally, but the A region around them has sides on both
+        let input = parse_input("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA").unwrap();
+        let regions = analyze(&input);
+        assert_eq!(3, regions.len());
+        let a = &regions[0];
+        assert_eq!(('A', 28, 12), (a.plant, a.area, a.sides));
+        assert!(regions[1 ..].iter().all(|b| (b.plant, b.area, b.sides) == ('B', 4, 4)));
+        assert!(!a.points.contains(&Point::new(3, 1)));
     }
 
+    #[test]
+    fn test_to_svg() {
+        let input = parse_input(&example(12, 1)).unwrap();
+        let svg = to_svg(&input);
+        assert_eq!(11, svg.matches("<path").count());
+        assert_eq!(100, svg.matches('M').count());
+    }
 }
@@ -1,7 +1,10 @@
 use crate::util::grid::prelude::*;
 
-use super::Day;
-use std::fs;
+use super::{Answer, Context, Day, IntoAnswer};
+use crate::parse;
+use crate::util::error::Result;
+use crate::util::math::{gcd, mod_inverse};
+use crate::util::parse::numbered_sections;
 
 /// Day 13: Claw Contraption
 /// 
@@ -16,7 +19,7 @@ use std::fs;
 /// Part 2: Actually, the prize is located an additiona `10,000,000,000,000` further in the x and y directions.
 pub struct Day13;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Claw {
     button_a: Point64,
     button_b: Point64,
@@ -24,45 +27,131 @@ pub struct Claw {
 }
 
 impl Day<Vec<Claw>> for Day13 {
-    fn read_input() -> Vec<Claw> {
-        let input = fs::read_to_string("resources/day13.txt").expect("file day13.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec<Claw>> {
+        parse_input(input)
     }
 
-    fn part1(input: &Vec<Claw>) -> impl std::fmt::Display {
-        input.iter()
-            .filter_map(linear_algebra)
-            .sum::<i64>()
+    fn part1(input: &Vec<Claw>) -> Result<impl IntoAnswer> {
+        Ok(total_tokens(input, 0, cramer))
     }
 
-    fn part2(input: &Vec<Claw>) -> impl std::fmt::Display {
-        let offset: i64 = 10_000_000_000_000;
-        input.iter()
-            .map(|claw| {
-                Claw {
-                    button_a: claw.button_a,
-                    button_b: claw.button_b,
-                    prize: claw.prize + offset
-                }
-            })
-            .filter_map(|claw| linear_algebra(&claw))
-            .sum::<i64>()
+    fn part2(input: &Vec<Claw>) -> Result<impl IntoAnswer> {
+        Ok(total_tokens(input, PRIZE_OFFSET, cramer))
+    }
+
+    const ALGORITHMS: &'static [&'static str] = &["cramer", "float"];
+
+    fn part1_with(input: &Vec<Claw>, context: &Context) -> Result<Answer> {
+        Ok(total_tokens(input, 0, solver(context.algorithm())).into())
+    }
+
+    fn part2_with(input: &Vec<Claw>, context: &Context) -> Result<Answer> {
+        Ok(total_tokens(input, PRIZE_OFFSET, solver(context.algorithm())).into())
     }
 }
 
-/// This problem can be solved using linear algebra. Consider the following matrix:
+/// How much further away the prizes are in part 2
+const PRIZE_OFFSET: i64 = 10_000_000_000_000;
+
+fn solver(algorithm: Option<&str>) -> fn(&Claw) -> Option<i64> {
+    match algorithm {
+        Some("float") => linear_algebra,
+        _ => cramer,
+    }
+}
+
+/// The fewest tokens to win every prize that can be won, with each prize moved `offset` further away
+fn total_tokens(claws: &[Claw], offset: i64, solve: fn(&Claw) -> Option<i64>) -> i64 {
+    claws.iter()
+        .filter_map(|claw| solve(&Claw { prize: claw.prize + offset, ..*claw }))
+        .sum()
+}
+
+/// Solve the two equations `a * ax + b * bx = px` and `a * ay + b * by = py` exactly with Cramer's rule:
+/// ```text
+/// a = (px * by - py * bx) / det
+/// b = (ax * py - ay * px) / det
 /// ```
+/// where `det = ax * by - ay * bx`. The prize can only be won if both divisions are exact and not negative.
+/// The products are done in `i128`, as part 2 prizes times button moves can get close to the `i64` limit.
+///
+/// If the determinant is zero, both buttons move the claw along the same line, see [`collinear`].
+fn cramer(claw: &Claw) -> Option<i64> {
+    let wide = |point: Point64| (i128::from(point.x), i128::from(point.y));
+    let ((ax, ay), (bx, by), (px, py)) = (wide(claw.button_a), wide(claw.button_b), wide(claw.prize));
+    let det = ax * by - ay * bx;
+    if det == 0 {
+        return collinear(claw);
+    }
+    let (a_numerator, b_numerator) = (px * by - py * bx, ax * py - ay * px);
+    if a_numerator % det != 0 || b_numerator % det != 0 {
+        return None;
+    }
+    let (a_presses, b_presses) = (a_numerator / det, b_numerator / det);
+    if a_presses < 0 || b_presses < 0 {
+        return None;
+    }
+    i64::try_from(3 * a_presses + b_presses).ok()
+}
+
+/// Both buttons move the claw in the same direction, so there can be many ways to reach the prize
+/// (or none, if the prize isn't on that line). Work along one axis, and pick the cheapest way.
+///
+/// Every solution to `a * ax + b * bx = px` is a step of `bx / gcd` more A presses for `ax / gcd` fewer B presses.
+/// A press costs 3 tokens, so more A presses are cheaper only if `3 * bx < ax`. The cheapest is then
+/// either the fewest A presses, or the fewest B presses, and each is a modular inverse away.
+fn collinear(claw: &Claw) -> Option<i64> {
+    let (a, b, prize) = (claw.button_a, claw.button_b, claw.prize);
+    if a.x * prize.y != a.y * prize.x || b.x * prize.y != b.y * prize.x {
+        return None;
+    }
+    // use the y axis only if neither button moves along x
+    let (a_move, b_move, target) = if a.x != 0 || b.x != 0 { (a.x, b.x, prize.x) } else { (a.y, b.y, prize.y) };
+    let (a_presses, b_presses) = match (a_move, b_move) {
+        (0, 0) => (0, 0),
+        (0, _) => (0, target / b_move),
+        (_, 0) => (target / a_move, 0),
+        _ => {
+            let divisor = gcd(a_move, b_move);
+            if target % divisor != 0 {
+                return None;
+            }
+            let (a_step, b_step, target_step) = (a_move / divisor, b_move / divisor, target / divisor);
+            // the presses of one button, modulo the step, are fixed by the other
+            let fewest = |presses: i64, step: i64| {
+                let presses = i128::from(target_step) * i128::from(mod_inverse(presses, step)?) % i128::from(step);
+                i64::try_from(presses).ok()
+            };
+            if 3 * b_move < a_move {
+                let b_presses = fewest(b_step, a_step)?;
+                ((target - b_presses * b_move) / a_move, b_presses)
+            } else {
+                let a_presses = fewest(a_step, b_step)?;
+                (a_presses, (target - a_presses * a_move) / b_move)
+            }
+        }
+    };
+    let reached = a_presses >= 0 && b_presses >= 0 && a * a_presses + b * b_presses == prize;
+    reached.then_some(3 * a_presses + b_presses)
+}
+
+/// This problem can be solved using linear algebra. Consider the following matrix:
+/// ```text
 /// [ax bx | px]
 /// [ay by | py]
 /// ```
 /// Button a = (ax, ay), button b = (bx, by) and the prize = (px, py).
 /// 
 /// If we reduce the matrix, we get:
-/// ```
+/// ```text
 /// [1 0 | a_presses]
 /// [0 1 | b_presses]
 /// ```
 /// where `a_presses` and `b_presses` are whole numbers in a solvable claw machine
+///
+/// This was the original floating point solver, kept for comparison with [`cramer`] (`--algo float`).
+// the x and y names follow the equations, and a bad rounding is caught by the check at the end
+#[allow(clippy::similar_names, clippy::cast_possible_truncation)]
 fn linear_algebra(claw: &Claw) -> Option<i64> {
     let (mut ax, mut ay) = claw.button_a.to_f64();
     let (bx, mut by) = claw.button_b.to_f64();
@@ -95,29 +184,19 @@ fn linear_algebra(claw: &Claw) -> Option<i64> {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Claw> {
-    input.split("\n\n")
-        .map(|claw_string| {
-            let lines = claw_string.lines().collect::<Vec<_>>();
-            let parse_button = |line_str: &str| {
-                let point = line_str.split(": ").last().unwrap()
-                    .split(", ")
-                    .map(|pt| pt.split('+').last().unwrap().parse::<i64>().unwrap())
-                    .collect::<Vec<_>>();
-                Point64::new(point[0], point[1])
-            };
-            let button_a = parse_button(lines[0]);
-            let button_b = parse_button(lines[1]);
-            let prize = lines[2].split(": ").last().unwrap()
-                .split(", ")
-                .map(|pt| pt.split('=').last().unwrap().parse::<i64>().unwrap())
-                .collect::<Vec<_>>();
-            let prize = Point64::new(prize[0], prize[1]);
-            Claw {
-                button_a,
-                button_b,
-                prize
-            }
+fn parse_input(input: &str) -> Result<Vec<Claw>> {
+    numbered_sections(input).into_iter()
+        .map(|(line, claw_string)| {
+            let (ax, ay, bx, by, px, py) = parse!(
+                claw_string,
+                "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}" => i64, i64, i64, i64, i64, i64;
+                line line
+            )?;
+            Ok(Claw {
+                button_a: Point64::new(ax, ay),
+                button_b: Point64::new(bx, by),
+                prize: Point64::new(px, py),
+            })
         })
         .collect()
 }
@@ -125,28 +204,36 @@ fn parse_input(input: &str) -> Vec<Claw> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
 
-    const TEST: &str = "Button A: X+94, Y+34
-Button B: X+22, Y+67
-Prize: X=8400, Y=5400
-
-Button A: X+26, Y+66
-Button B: X+67, Y+21
-Prize: X=12748, Y=12176
-
-Button A: X+17, Y+86
-Button B: X+84, Y+37
-Prize: X=7870, Y=6450
-
-Button A: X+69, Y+23
-Button B: X+27, Y+71
-Prize: X=18641, Y=10279";
+    aoc_test!(day13, part1, example 1, "480");
+    aoc_test!(day13, part2, example 1, "875318608908");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day13::part1(&input);
-        assert_eq!("480", result.to_string())
+    fn test_float_solver() {
+        let input = parse_input(&example(13, 1)).unwrap();
+        let float = Context::default().with_algorithm(Some("float"));
+        assert_eq!(Answer::Int(480), Day13::part1_with(&input, &float).unwrap());
+        assert_eq!(Answer::Int(875_318_608_908), Day13::part2_with(&input, &float).unwrap());
     }
 
+    #[test]
+    fn test_collinear() {
+        let claw = |a: (i64, i64), b: (i64, i64), prize: (i64, i64)| Claw {
+            button_a: Point64::new(a.0, a.1),
+            button_b: Point64::new(b.0, b.1),
+            prize: Point64::new(prize.0, prize.1),
+        };
+        // B is cheaper per step
+        assert_eq!(Some(10), cramer(&claw((2, 2), (1, 1), (10, 10))));
+        // A moves more than 3 times as far, so use it as much as possible
+        assert_eq!(Some(7), cramer(&claw((6, 6), (1, 1), (13, 13))));
+        assert_eq!(Some(4), cramer(&claw((4, 4), (6, 6), (10, 10))));
+        assert_eq!(Some(3 + 1_000_000_000), cramer(&claw((4, 6), (6, 9), (6_000_000_004, 9_000_000_006))));
+        // off the line, or between reachable spots
+        assert_eq!(None, cramer(&claw((2, 2), (1, 1), (10, 11))));
+        assert_eq!(None, cramer(&claw((4, 4), (6, 6), (7, 7))));
+        assert_eq!(Some(5), cramer(&claw((0, 0), (0, 2), (0, 10))));
+    }
 }
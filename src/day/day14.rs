@@ -1,7 +1,12 @@
-use crate::util::point::Point;
+use crate::util::grid::prelude::*;
 
-use super::Day;
-use std::{collections::HashSet, fs};
+use super::{Answer, Context, Day, IntoAnswer};
+use crate::parse;
+use crate::util::error::{AocError, Result};
+use crate::util::math::crt;
+use crate::util::raster::{animated_gif, Raster, BLACK};
+use std::collections::HashSet;
+use std::time::Duration;
 
 /// Day 14: Restroom Redoubt
 /// 
@@ -19,6 +24,10 @@ use std::{collections::HashSet, fs};
 /// Find the fewest number of seconds until that christmas tree appears.
 pub struct Day14;
 
+/// The width and height of the area the robots move around in, unless the `width` and `height`
+/// parameters are set (the example uses 11 by 7)
+const FLOOR: Point = Point::new(101, 103);
+
 #[derive(Debug, Clone)]
 pub struct Robot {
     position: Point,
@@ -26,90 +35,260 @@ pub struct Robot {
 }
 
 impl Day<Vec<Robot>> for Day14 {
-    fn read_input() -> Vec<Robot> {
-        let input = fs::read_to_string("resources/day14.txt").expect("file day14.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec<Robot>> {
+        parse_input(input)
     }
 
-    fn part1(input: &Vec<Robot>) -> impl std::fmt::Display {
-        let mut final_positions = Vec::new();
-        for robot in input {
-            let total_velocity = robot.velocity * 100;
-            let final_position = total_velocity + robot.position;
-            final_positions.push(Point::new(
-                // Note: make sure to do euclid modulo instead of the `%` remainder operator
-                final_position.x.rem_euclid(101),
-                final_position.y.rem_euclid(103),
-            ));
-        }
-        let (mut q1, mut q2, mut q3, mut q4) = (0, 0, 0, 0);
-        for point in final_positions {
-            if point.x < 50 && point.y < 51 {
-                q1 += 1;
-            } else if point.x > 50 && point.y < 51 {
-                q2 += 1;
-            } else if point.x < 50 && point.y > 51 {
-                q3 += 1;
-            } else if point.x > 50 && point.y > 51 {
-                q4 += 1;
-            }
-        }
-        q1 * q2 * q3 * q4
+    fn part1(input: &Vec<Robot>) -> Result<impl IntoAnswer> {
+        Self::part1_with(input, &Context::default())
     }
 
-    // Tried a couple of different approaches. This one worked:
-    // assume the easter egg occurs when each robot is in a unique position.
-    fn part2(input: &Vec<Robot>) -> impl std::fmt::Display {
-        let mut seconds = 0;
-        let mut updated_robots = input.to_owned();
-        loop {
-            seconds += 1;
-            updated_robots = updated_robots.into_iter().map(|robot| {
-                let next_position = robot.position + robot.velocity;
-                Robot {
-                    velocity: robot.velocity,
-                    position: Point::new(
-                        next_position.x.rem_euclid(101),
-                        next_position.y.rem_euclid(103),
-                    )
-                }
-            })
-            .collect();
-            let positions = updated_robots.iter()
-                .map(|r| r.position)
-                .collect::<HashSet<_>>();
-            if updated_robots.len() == positions.len() {
-                // Assume that for the xmas tree picture, all robots will be used in a unique position
-                print_robots(&positions);
-                return seconds;
-            }
-        }
+    // Tried a couple of different approaches. At first, assumed the easter egg occurs
+    // when each robot is in a unique position. Looking for the least spread out robots is more reliable.
+    fn part2(input: &Vec<Robot>) -> Result<impl IntoAnswer> {
+        Self::part2_with(input, &Context::default())
+    }
+
+    const ALGORITHMS: &'static [&'static str] = &["variance", "unique-positions", "largest-cluster"];
+    const PARAMS: &'static [&'static str] = &["width", "height"];
+
+    fn part1_with(input: &Vec<Robot>, context: &Context) -> Result<Answer> {
+        Ok(safety_factor(input, floor_size(context)?, 100).into())
+    }
+
+    fn part2_with(input: &Vec<Robot>, context: &Context) -> Result<Answer> {
+        let detector = match context.algorithm() {
+            Some("unique-positions") => TreeDetector::UniquePositions,
+            Some("largest-cluster") => TreeDetector::LargestCluster,
+            _ => TreeDetector::Variance,
+        };
+        Ok(find_tree(input, floor_size(context)?, detector)?.seconds.into())
     }
 }
 
-fn print_robots(robots: &HashSet<Point>) {
-    for y in 0..103 {
-        for x in 0..101 {
-            if robots.contains(&Point::new(x,y)) {
-                print!("X");
-            } else {
-                print!(".");
-            }
+/// The size of the floor from the `width` and `height` parameters,
+/// which have to be positive and small enough to number every position on the floor
+fn floor_size(context: &Context) -> Result<Point> {
+    let floor = Point::new(context.param("width", FLOOR.x)?, context.param("height", FLOOR.y)?);
+    if floor.x <= 0 || floor.y <= 0 || floor.x.checked_mul(floor.y).is_none() {
+        return Err(AocError::Config(format!("width = {}, height = {} is out of range", floor.x, floor.y)));
+    }
+    Ok(floor)
+}
+
+/// Move every robot for `seconds` on a floor of size `floor`.
+/// Split the floor into 4 quadrants (ignoring the robots in the exact middles)
+/// and multiply the number of robots in each quadrant.
+fn safety_factor(robots: &[Robot], floor: Point, seconds: i32) -> usize {
+    let mut final_positions = Vec::new();
+    for robot in robots {
+        // Note: make sure to do euclid modulo instead of the `%` remainder operator
+        final_positions.push((robot.position + robot.velocity * seconds).rem_euclid(floor));
+    }
+    let middle = Point::new(floor.x / 2, floor.y / 2);
+    let (mut q1, mut q2, mut q3, mut q4) = (0, 0, 0, 0);
+    for point in final_positions {
+        if point.x < middle.x && point.y < middle.y {
+            q1 += 1;
+        } else if point.x > middle.x && point.y < middle.y {
+            q2 += 1;
+        } else if point.x < middle.x && point.y > middle.y {
+            q3 += 1;
+        } else if point.x > middle.x && point.y > middle.y {
+            q4 += 1;
         }
-        println!();
     }
+    q1 * q2 * q3 * q4
 }
 
-fn parse_input(input: &str) -> Vec<Robot> {
-    input.lines().map(|line|{
-        let parts = line.split_whitespace()
-            .map(|part| part.split('=').last().unwrap())
-            .flat_map(|coord| coord.split(',').map(|i| i.parse::<i32>().unwrap()))
-            .collect::<Vec<_>>();
-        Robot {
-            position: Point::new(parts[0], parts[1]),
-            velocity: Point::new(parts[2], parts[3])
+/// How many seconds before and after the tree to show in the animation
+const ANIMATION_SECONDS: i32 = 10;
+
+/// The width and height of each floor tile in the pictures, in pixels
+const PIXEL_SCALE: usize = 4;
+
+const ROBOT_COLOR: [u8; 3] = [34, 139, 34];
+
+/// How to tell that the robots have formed the christmas tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeDetector {
+    /// Every robot is in a different position.
+    /// This happens to work for the puzzle input, but nothing says the picture can't have overlapping robots.
+    UniquePositions,
+    /// The robots are bunched together in the picture, so the positions have the least variance
+    Variance,
+    /// The picture is drawn with many robots next to each other, in one large connected group
+    LargestCluster,
+}
+
+/// The first second that the christmas tree appears, and a picture of the floor at that second
+#[derive(Debug, Clone)]
+pub struct TreeFrame {
+    pub seconds: i32,
+    pub rendering: String,
+}
+
+/// The part of the robots that have to be in a single connected group for [`TreeDetector::LargestCluster`]
+const CLUSTER_FRACTION: usize = 4;
+
+/// Every robot is back where it started after `floor.x * floor.y` seconds (or sooner),
+/// so the tree has to appear before then.
+fn find_tree(robots: &[Robot], floor: Point, detector: TreeDetector) -> Result<TreeFrame> {
+    let period = 0 .. floor.x * floor.y;
+    let seconds = match detector {
+        TreeDetector::UniquePositions => period.into_iter().find(|&seconds| {
+            let positions = positions_at(robots, floor, seconds);
+            positions.iter().collect::<HashSet<_>>().len() == positions.len()
+        }),
+        TreeDetector::Variance => min_variance_second(robots, floor),
+        TreeDetector::LargestCluster => period.into_iter().find(|&seconds| {
+            largest_cluster(floor, &positions_at(robots, floor, seconds)) * CLUSTER_FRACTION >= robots.len()
+        }),
+    };
+    let seconds = seconds.ok_or_else(|| AocError::no_solution("the robots never form a christmas tree"))?;
+    Ok(TreeFrame { seconds, rendering: render_robots(floor, &positions_at(robots, floor, seconds)) })
+}
+
+/// Pictures of the christmas tree, to save as image files
+#[derive(Debug, Clone)]
+pub struct TreePictures {
+    pub seconds: i32,
+    /// The frame the tree appears in, as text
+    pub rendering: String,
+    /// The frame the tree appears in
+    pub png: Vec<u8>,
+    /// An animation of the seconds before and after the tree appears
+    pub gif: Vec<u8>,
+}
+
+/// Find the christmas tree, and draw the robots at that second as text and a PNG,
+/// and the seconds around it as an animated GIF.
+///
+/// # Errors
+/// If the `width` or `height` parameter is out of range, or the robots never form a christmas tree
+pub fn tree_pictures(robots: &[Robot], context: &Context) -> Result<TreePictures> {
+    let floor = floor_size(context)?;
+    let frame = find_tree(robots, floor, TreeDetector::Variance)?;
+    let raster_at = |seconds| {
+        let floor = floor_grid(floor, false, &positions_at(robots, floor, seconds), true);
+        Raster::from_grid(&floor, PIXEL_SCALE, |&robot| if robot { ROBOT_COLOR } else { BLACK })
+    };
+    let frames = (frame.seconds - ANIMATION_SECONDS ..= frame.seconds + ANIMATION_SECONDS)
+        .map(raster_at)
+        .collect::<Vec<_>>();
+    Ok(TreePictures {
+        seconds: frame.seconds,
+        rendering: frame.rendering,
+        png: raster_at(frame.seconds).to_png(),
+        gif: animated_gif(&frames, Duration::from_millis(200)),
+    })
+}
+
+fn positions_at(robots: &[Robot], floor: Point, seconds: i32) -> Vec<Point> {
+    robots.iter()
+        .map(|robot| (robot.position + robot.velocity * seconds).rem_euclid(floor))
+        .collect()
+}
+
+/// The x positions repeat every `floor.x` seconds, and the y positions every `floor.y` seconds.
+/// Find the second with the least variance for each axis on its own,
+/// then the second where both happen at once (the chinese remainder theorem).
+fn min_variance_second(robots: &[Robot], floor: Point) -> Option<i32> {
+    // proportional to the variance: n * sum(x^2) - sum(x)^2
+    let spread = |values: Vec<i64>| {
+        let n = i64::try_from(values.len()).unwrap_or(i64::MAX);
+        n * values.iter().map(|v| v * v).sum::<i64>() - values.iter().sum::<i64>().pow(2)
+    };
+    let best_x = (0 .. floor.x).min_by_key(|&seconds| spread(robots.iter()
+        .map(|robot| i64::from((robot.position.x + robot.velocity.x * seconds).rem_euclid(floor.x)))
+        .collect()))?;
+    let best_y = (0 .. floor.y).min_by_key(|&seconds| spread(robots.iter()
+        .map(|robot| i64::from((robot.position.y + robot.velocity.y * seconds).rem_euclid(floor.y)))
+        .collect()))?;
+    let (seconds, _) = crt([(best_x.into(), floor.x.into()), (best_y.into(), floor.y.into())])?;
+    i32::try_from(seconds).ok()
+}
+
+/// The number of robot positions in the largest group of positions that are next to each other
+fn largest_cluster(floor: Point, positions: &[Point]) -> usize {
+    let occupied = floor_grid(floor, false, positions, true);
+    let mut seen = HashSet::new();
+    let mut largest = 0;
+    for &position in positions {
+        if seen.contains(&position) {
+            continue;
         }
+        let cluster = occupied.flood_fill(position, |&robot| robot);
+        largest = largest.max(cluster.len());
+        seen.extend(cluster);
+    }
+    largest
+}
+
+fn render_robots(floor: Point, positions: &[Point]) -> String {
+    floor_grid(floor, '.', positions, 'X').to_string()
+}
+
+/// A grid the size of the floor, with `robot` at every position and `empty` everywhere else
+fn floor_grid<T: Clone>(floor: Point, empty: T, positions: &[Point], robot: T) -> Vec2d<T> {
+    let mut grid = Vec2d {
+        grid: vec![empty; (floor.x * floor.y).unsigned_abs() as usize],
+        line_len: floor.x,
+    };
+    for &position in positions {
+        grid[position] = robot.clone();
+    }
+    grid
+}
+
+fn parse_input(input: &str) -> Result<Vec<Robot>> {
+    input.lines().enumerate().map(|(idx, line)|{
+        let (px, py, vx, vy) = parse!(line, "p={},{} v={},{}" => i32, i32, i32, i32; line idx + 1)?;
+        Ok(Robot {
+            position: Point::new(px, py),
+            velocity: Point::new(vx, vy)
+        })
     })
     .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::example;
+
+    #[test]
+    fn test_part_1() {
+        let input = parse_input(&example(14, 1)).unwrap();
+        let example = Context::default().with_param("width", 11).with_param("height", 7);
+        assert_eq!(Answer::Int(12), Day14::part1_with(&input, &example).unwrap());
+        assert_eq!(12, safety_factor(&input, Point::new(11, 7), 100));
+    }
+
+    #[test]
+    fn test_floor_size() {
+        assert_eq!(FLOOR, floor_size(&Context::default()).unwrap());
+        for (width, height) in [(0, 7), (11, -1), (100_000, 100_000)] {
+            let context = Context::default().with_param("width", width).with_param("height", height);
+            assert!(matches!(floor_size(&context), Err(AocError::Config(_))), "{width} by {height}");
+        }
+    }
+
+    #[test]
+    fn test_find_tree() {
+        // 9 robots that come together in a 3x3 square after 40 seconds
+        let floor = Point::new(31, 29);
+        let robots = (0 .. 9)
+            .map(|idx| {
+                let velocity = Point::new(idx * 3 - 12, idx * 2 - 8);
+                let target = Point::new(14 + idx % 3, 13 + idx / 3);
+                Robot { position: (target - velocity * 40).rem_euclid(floor), velocity }
+            })
+            .collect::<Vec<_>>();
+        for detector in [TreeDetector::Variance, TreeDetector::LargestCluster] {
+            let frame = find_tree(&robots, floor, detector).unwrap();
+            assert_eq!(40, frame.seconds);
+            assert_eq!(Some("..............XXX.............."), frame.rendering.lines().nth(14));
+        }
+    }
+}
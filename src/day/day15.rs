@@ -1,7 +1,10 @@
 use crate::util::grid::prelude::*;
 
-use super::Day;
-use std::fs;
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::parse::two_sections;
+use crate::util::viz::{frame_interval, render, Color};
+use tracing::debug;
 
 /// Day 15: Warehouse Woes
 /// 
@@ -18,7 +21,7 @@ use std::fs;
 /// 
 /// Part 2: The warehouse is actually twice as wide, and boxes take up two spaces horizontally.
 /// The robot still takes up one space, but may push multiple boxes like so:
-/// ```
+/// ```text
 /// ##############
 /// ##......##..##
 /// ##..........##
@@ -28,7 +31,7 @@ use std::fs;
 /// ##############
 /// ```
 /// `^`
-/// ```
+/// ```text
 /// ##############
 /// ##......##..##
 /// ##...[][]...##
@@ -40,97 +43,205 @@ use std::fs;
 /// Return the score based on the first part of the box (`[`)
 pub struct Day15;
 
-pub type Warehouse = (Vec2d<char>, Vec<Directions>);
+pub type Input = (Vec2d<char>, Vec<Directions>);
+
+impl Day<Input> for Day15 {
+    fn parse(input: &str) -> Result<Input> {
+        parse_input(input)
+    }
 
-impl Day<Warehouse> for Day15 {
-    fn read_input() -> Warehouse {
-        let input = fs::read_to_string("resources/day15.txt").expect("file day15.txt not found");
-        parse_input(&input)
+    fn part1(input: &Input) -> Result<impl IntoAnswer> {
+        let (grid, moves) = input;
+        let mut warehouse = Warehouse::new(grid.clone())?;
+        for &movement in moves {
+            warehouse.step(movement)?;
+        }
+        debug!("warehouse after all moves:\n{}", warehouse.grid());
+        Ok(warehouse.score())
     }
 
-    fn part1(input: &Warehouse) -> impl std::fmt::Display {
-        let mut grid = input.0.clone();
-        let mut robot = grid.find(&'@').unwrap();
-
-        for &movement in &input.1 {
-            let Some(next) = grid.next_point(robot, movement) else {
-                continue;
-            };
-            if grid[next] == '#' {
-                continue;
-            }
-            if grid[next] == 'O' {
-                move_box(next, &mut grid, movement);
-            }
-            if grid[next] == '.' {
-                grid[next] = '@';
-                grid[robot] = '.';
-                robot = next;
-            }
+    fn part2(input: &Input) -> Result<impl IntoAnswer> {
+        let (grid, moves) = input;
+        let mut warehouse = Warehouse::wide(grid)?;
+        for &movement in moves {
+            warehouse.step(movement)?;
         }
-        grid.grid.iter().enumerate()
-            .filter(|&(_, c)| *c == 'O')
-            .map(|(idx, _)| grid.idx_to_point(idx))
-            .map(|point| point.y * 100 + point.x)
-            .sum::<i32>()
+        debug!("warehouse after all moves:\n{}", warehouse.grid());
+        Ok(warehouse.score())
+    }
+}
+
+/// What happened when the robot tried to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveResult {
+    /// A wall, or boxes that can't be pushed, are in the way
+    Blocked,
+    /// The robot moved into an empty space
+    Moved,
+    /// The robot moved and pushed one or more boxes ahead of it
+    Pushed,
+}
 
+/// The warehouse floor, with the robot moving one instruction at a time.
+/// Works with both the boxes from part 1 (`O`) and the wide boxes from part 2 (`[]`).
+///
+/// Also keeps track of how many times each box has moved, and how many times the robot
+/// has been on each tile, for checking the push logic and drawing heat maps.
+#[derive(Clone)]
+pub struct Warehouse {
+    grid: Vec2d<char>,
+    robot: Point,
+    /// How many times the box on each tile has moved. The count moves along with the box,
+    /// and both halves of a wide box have the same count.
+    box_moves: Vec2d<u32>,
+    visits: Vec2d<u32>,
+}
+
+impl Warehouse {
+    /// # Errors
+    /// If there is no robot in the warehouse
+    pub fn new(grid: Vec2d<char>) -> Result<Self> {
+        let robot = grid.find(&'@').ok_or_else(|| AocError::parse("no robot @ in the warehouse"))?;
+        let box_moves = Vec2d { grid: vec![0; grid.grid.len()], line_len: grid.line_len };
+        let mut visits = box_moves.clone();
+        visits[robot] = 1;
+        Ok(Self { grid, robot, box_moves, visits })
     }
 
-    fn part2(input: &Warehouse) -> impl std::fmt::Display {
-        let (input_grid, instructions) = input;
-        let updated_grid = input_grid.grid.iter()
-            .flat_map(|&c| match c {
-                '#' => vec!['#', '#'],
-                'O' => vec!['[', ']'],
-                '.' => vec!['.', '.'],
-                '@' => vec!['@', '.'],
-                _ => panic!("Invalid grid character"),
-            })
-            .collect::<Vec<_>>();
-        let mut grid = Vec2d {
-            grid: updated_grid,
-            line_len: input_grid.line_len * 2,
+    /// The warehouse for part 2, where everything except the robot is twice as wide
+    ///
+    /// # Errors
+    /// If there is no robot in the warehouse, or anything other than walls, boxes, and empty space
+    pub fn wide(grid: &Vec2d<char>) -> Result<Self> {
+        Self::new(widen(grid)?)
+    }
+
+    #[must_use]
+    pub fn grid(&self) -> &Vec2d<char> {
+        &self.grid
+    }
+
+    #[must_use]
+    pub fn robot(&self) -> Point {
+        self.robot
+    }
+
+    /// Where each box is (the left side of wide boxes), and how many times it has moved
+    pub fn box_moves(&self) -> impl Iterator<Item = (Point, u32)> + '_ {
+        self.grid.iter_with_points()
+            .filter(|&(_, &c)| c == 'O' || c == '[')
+            .map(|(point, _)| (point, self.box_moves[point]))
+    }
+
+    /// How many times the robot has been on each tile, including where it started.
+    /// Tiles the robot never visited are 0.
+    #[must_use]
+    pub fn visits(&self) -> &Vec2d<u32> {
+        &self.visits
+    }
+
+    /// Try to move the robot one space, pushing any boxes in the way
+    ///
+    /// # Errors
+    /// If a wide box in the way is missing its other half
+    pub fn step(&mut self, direction: Directions) -> Result<MoveResult> {
+        let (grid, box_moves) = (&mut self.grid, &mut self.box_moves);
+        let Some(next) = grid.next_point(self.robot, direction) else {
+            return Ok(MoveResult::Blocked);
         };
-        let mut robot = grid.find(&'@').unwrap();
-
-        for &movement in instructions {
-            let Some(next) = grid.next_point(robot, movement) else {
-                continue;
-            };
-            if grid[next] == '#' {
-                continue;
-            }
-            if grid[next] == '[' || grid[next] == ']' {
-                move_large_box(next, &mut grid, movement);
-            }
-            if grid[next] == '.' {
-                grid[next] = '@';
-                grid[robot] = '.';
-                robot = next;
-            }
+        let tile = grid[next];
+        let result = match tile {
+            '.' => MoveResult::Moved,
+            'O' if move_box(next, grid, box_moves, direction) => MoveResult::Pushed,
+            '[' | ']' if move_large_box(next, grid, box_moves, direction)? => MoveResult::Pushed,
+            _ => MoveResult::Blocked,
+        };
+        if result != MoveResult::Blocked {
+            grid[next] = '@';
+            grid[self.robot] = '.';
+            self.robot = next;
+            self.visits[next] += 1;
         }
-        grid.grid.iter().enumerate()
-            .filter(|&(_, c)| *c == '[')
-            .map(|(idx, _)| grid.idx_to_point(idx))
-            .map(|point| point.y * 100 + point.x)
-            .sum::<i32>()
+        Ok(result)
+    }
+
+    /// The sum of 100 times the y position plus the x position of each box
+    /// (measured to the left side of wide boxes)
+    #[must_use]
+    pub fn score(&self) -> i32 {
+        self.grid.iter_with_points()
+            .filter(|&(_, &c)| c == 'O' || c == '[')
+            .map(|(point, _)| point.y * 100 + point.x)
+            .sum()
     }
 }
 
+/// The warehouse for part 2, where everything except the robot is twice as wide
+fn widen(grid: &Vec2d<char>) -> Result<Vec2d<char>> {
+    let updated_grid = grid.grid.iter()
+        .map(|&c| match c {
+            '#' => Ok(['#', '#']),
+            'O' => Ok(['[', ']']),
+            '.' => Ok(['.', '.']),
+            '@' => Ok(['@', '.']),
+            _ => Err(AocError::parse(format!("invalid grid character {c}"))),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Vec2d {
+        grid: updated_grid.concat(),
+        line_len: grid.line_len * 2,
+    })
+}
+
+/// Frames of the robot pushing boxes around the wide warehouse from part 2, about `max_frames` of them.
+/// Walls are gray, boxes are yellow, and the robot is red.
+///
+/// The frames stop early if a move fails (see [`Warehouse::step`]).
+///
+/// # Errors
+/// If there is no robot in the warehouse, or anything other than walls, boxes, and empty space
+pub fn warehouse_frames(input: &Input, max_frames: usize) -> Result<impl Iterator<Item = String> + '_> {
+    let (grid, moves) = input;
+    let mut warehouse = Warehouse::wide(grid)?;
+    let every = frame_interval(moves.len(), max_frames);
+    let first = render_warehouse(warehouse.grid());
+    let frames = moves.iter().enumerate().map_while(move |(idx, &movement)| {
+        warehouse.step(movement).ok()?;
+        Some(((idx + 1) % every == 0 || idx + 1 == moves.len()).then(|| render_warehouse(warehouse.grid())))
+    });
+    let frames = frames.flatten();
+    Ok(std::iter::once(first).chain(frames))
+}
+
+fn render_warehouse(grid: &Vec2d<char>) -> String {
+    render(grid.line_len, grid.height(), |point| match grid[point] {
+        '#' => ('#', Color::Gray),
+        c @ ('[' | ']' | 'O') => (c, Color::Yellow),
+        '@' => ('@', Color::Red),
+        c => (c, Color::Default),
+    })
+}
+
+/// Move the box (or half of a wide box) at `from` to the empty tile `to`, counting the move
+fn shift_box(from: Point, to: Point, grid: &mut Vec2d<char>, box_moves: &mut Vec2d<u32>) {
+    grid[to] = grid[from];
+    grid[from] = '.';
+    box_moves[to] = box_moves[from] + 1;
+    box_moves[from] = 0;
+}
+
 // This can be done recursively by greedily moving boxes that can be moved in the path
-fn move_box(from: Point, grid: &mut Vec2d<char>, direction: Directions) -> bool {
+fn move_box(from: Point, grid: &mut Vec2d<char>, box_moves: &mut Vec2d<u32>, direction: Directions) -> bool {
     let Some(next) = grid.next_point(from, direction) else {
         return false;
     };
     if grid[next] == '.' {
-        grid[next] = grid[from];
-        grid[from] = '.';
+        shift_box(from, next, grid, box_moves);
         return true;
     } else if grid[next] == '#' {
         return false;
-    } else if move_box(next, grid, direction) { // 'O'
-        grid[next] = grid[from];
-        grid[from] = '.';
+    } else if move_box(next, grid, box_moves, direction) { // 'O'
+        shift_box(from, next, grid, box_moves);
         return true;
     }
     false
@@ -162,97 +273,128 @@ fn can_move_large_box(from: Point, grid: &Vec2d<char>, direction: Directions) ->
 
 // Left and right will work the same as before
 // but we need additional checks for up and down pushing due to the box size
-fn move_large_box(from: Point, grid: &mut Vec2d<char>, direction: Directions) -> bool {
+fn move_large_box(from: Point, grid: &mut Vec2d<char>, box_moves: &mut Vec2d<u32>, direction: Directions) -> Result<bool> {
     if direction == Directions::Left || direction == Directions::Right {
-        return move_box(from, grid, direction);
+        return Ok(move_box(from, grid, box_moves, direction));
     }
     let other_from = match grid[from] {
         '[' => grid.next_unbounded(from, Directions::Right),
         ']' => grid.next_unbounded(from, Directions::Left),
-        _ => panic!("Trying to move something that is not a box"),
+        c => return Err(AocError::parse(format!("{c} at {},{} is not half of a wide box", from.x, from.y))),
     };
-    if can_move_large_box(from, grid, direction) {
-        let next = grid.next_point(from, direction).unwrap();
-        let other_next = grid.next_point(other_from, direction).unwrap();
-        if grid[next] != '.' {
-            move_large_box(next, grid, direction);
-        }
-        if grid[other_next] != '.' {
-            move_large_box(other_next, grid, direction);
-        }
-        grid[next] = grid[from];
-        grid[other_next] = grid[other_from];
-        grid[from] = '.';
-        grid[other_from] = '.';
-        true
-    } else {
-        false
+    if !can_move_large_box(from, grid, direction) {
+        return Ok(false);
     }
+    let half_box = |point| grid.next_point(point, direction)
+        .ok_or_else(|| AocError::parse(format!("the box at {},{} is pushed off the edge of the warehouse", from.x, from.y)));
+    let (next, other_next) = (half_box(from)?, half_box(other_from)?);
+    if grid[next] != '.' {
+        move_large_box(next, grid, box_moves, direction)?;
+    }
+    if grid[other_next] != '.' {
+        move_large_box(other_next, grid, box_moves, direction)?;
+    }
+    shift_box(from, next, grid, box_moves);
+    shift_box(other_from, other_next, grid, box_moves);
+    Ok(true)
 }
 
-fn parse_input(str: &str) -> Warehouse {
-    let parts = str.split("\n\n").collect::<Vec<_>>();
+fn parse_input(str: &str) -> Result<Input> {
+    let (grid_section, moves_section) = two_sections(str, "the warehouse and moves")?;
     
-    let chars = parts[0].lines()
-        .flat_map(|line| line.trim().chars().collect::<Vec<_>>())
-        .collect();
-    let line_len = parts[0].lines().next().unwrap().len();
-    let grid = Vec2d {
-        grid: chars,
-        line_len: line_len as i32,
-    };
+    let grid: Vec2d<char> = grid_section.parse()?;
+    if let Some(c) = grid.grid.iter().find(|c| !matches!(c, '#' | '.' | 'O' | '@')) {
+        return Err(AocError::parse(format!("invalid grid character {c}")));
+    }
 
-    let moves = parts[1].lines()
+    let moves = moves_section.lines()
         .flat_map(|line| line.chars().collect::<Vec<_>>())
-        .map(|c| match c {
-            '^' => Directions::Up,
-            'v' => Directions::Down,
-            '>' => Directions::Right,
-            '<' => Directions::Left,
-            _ => panic!("invalid direction character {c}"),
-        })
-        .collect();
-    (grid, moves)
+        .map(|c| Directions::from_char(c).ok_or_else(|| AocError::parse(format!("invalid direction character {c}"))))
+        .collect::<Result<_>>()?;
+    Ok((grid, moves))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
 
-    const TEST: &str = "##########
-#..O..O.O#
-#......O.#
-#.OO..O.O#
-#..O@..O.#
-#O#..O...#
-#O..O..O.#
-#.OO.O.OO#
-#....O...#
-##########
-
-<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
-vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
-><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
-<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
-^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
-^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
->^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
-<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
-^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
-v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+    aoc_test!(day15, part1, example 1, "10092");
+    aoc_test!(day15, part2, example 1, "9021");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day15::part1(&input);
-        assert_eq!("10092", result.to_string())
+    fn test_push_row() {
+        let mut warehouse = Warehouse::new("######\n#@OO.#\n######".parse().unwrap()).unwrap();
+        assert_eq!(MoveResult::Pushed, warehouse.step(Directions::Right).unwrap());
+        assert_eq!("######\n#.@OO#\n######", warehouse.grid().to_string());
+        assert_eq!(MoveResult::Blocked, warehouse.step(Directions::Right).unwrap());
+        assert_eq!(MoveResult::Moved, warehouse.step(Directions::Left).unwrap());
+        assert_eq!(Point::new(1, 1), warehouse.robot());
+        assert_eq!(103 + 104, warehouse.score());
+        assert_eq!(vec![(Point::new(3, 1), 1), (Point::new(4, 1), 1)], warehouse.box_moves().collect::<Vec<_>>());
+        // the robot started on (1, 1), went to (2, 1), then came back
+        assert_eq!([2, 1, 0], [1, 2, 3].map(|x| warehouse.visits()[Point::new(x, 1)]));
     }
 
     #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result =  Day15::part2(&input);
-        assert_eq!("9021", result.to_string())
+    fn test_push_wide_boxes_up() {
+        // the robot pushes one box, which pushes the two boxes resting on it
+        let grid = "##############
+##......##..##
+##..........##
+##...[][]...##
+##....[]....##
+##.....@....##
+##############";
+        let mut warehouse = Warehouse::new(grid.parse().unwrap()).unwrap();
+        assert_eq!(MoveResult::Pushed, warehouse.step(Directions::Up).unwrap());
+        let expected = "##############
+##......##..##
+##...[][]...##
+##....[]....##
+##.....@....##
+##..........##
+##############";
+        assert_eq!(expected, warehouse.grid().to_string());
+        // the box on the right now hits the wall, so nothing moves
+        assert_eq!(MoveResult::Blocked, warehouse.step(Directions::Up).unwrap());
+        assert_eq!(expected, warehouse.grid().to_string());
+        assert_eq!(205 + 207 + 306, warehouse.score());
+        assert!(warehouse.box_moves().all(|(_, moves)| moves == 1));
+
+        // go around and push the lower box to the side, which moves each half once
+        assert_eq!(MoveResult::Moved, warehouse.step(Directions::Left).unwrap());
+        assert_eq!(MoveResult::Moved, warehouse.step(Directions::Left).unwrap());
+        assert_eq!(MoveResult::Moved, warehouse.step(Directions::Up).unwrap());
+        assert_eq!(MoveResult::Pushed, warehouse.step(Directions::Right).unwrap());
+        let moves = warehouse.box_moves().collect::<Vec<_>>();
+        assert_eq!(vec![(Point::new(5, 2), 1), (Point::new(7, 2), 1), (Point::new(7, 3), 2)], moves);
     }
 
+    #[test]
+    fn test_push_wide_boxes_blocked_on_one_side() {
+        // the lower box could move, but the upper box it pushes is under a wall on one side
+        let grid = "#######
+#..#..#
+#.[]..#
+#..[].#
+#...@.#
+#######";
+        let mut warehouse = Warehouse::new(grid.parse().unwrap()).unwrap();
+        assert_eq!(MoveResult::Blocked, warehouse.step(Directions::Up).unwrap());
+        assert_eq!(grid, warehouse.grid().to_string());
+        // pushing from under the left half of the lower box is blocked the same way
+        assert_eq!(MoveResult::Moved, warehouse.step(Directions::Left).unwrap());
+        assert_eq!(MoveResult::Blocked, warehouse.step(Directions::Up).unwrap());
+        assert_eq!(Point::new(3, 4), warehouse.robot());
+    }
+
+    #[test]
+    fn test_invalid_warehouse() {
+        assert!(matches!(parse_input("#####\n#@x.#\n#####\n\n>"), Err(AocError::Parse(_))));
+        assert!(matches!(Warehouse::wide(&"#####\n#@[]#\n#####".parse().unwrap()), Err(AocError::Parse(_))));
+        // a wide box pushing up into a box from part 1
+        let mut warehouse = Warehouse::new("######\n#.O..#\n#.[].#\n#.@..#\n######".parse().unwrap()).unwrap();
+        assert!(matches!(warehouse.step(Directions::Up), Err(AocError::Parse(_))));
+    }
 }
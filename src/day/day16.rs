@@ -1,8 +1,10 @@
-use super::Day;
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
 use crate::util::grid::prelude::*;
-use std::fs;
-use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::cmp::Ordering;
+use crate::util::hash::FxHashSet;
+use crate::util::pathfinding::{dijkstra, dijkstra_multi, Dijkstra};
+use crate::util::viz::Svg;
+use tracing::debug;
 
 /// Day 16: Reindeer Maze
 /// 
@@ -15,201 +17,152 @@ use std::cmp::Ordering;
 /// traversed by all the possible lowest cost path solutions?
 pub struct Day16;
 
-impl Day<Vec2d<char>> for Day16 {
-    fn read_input() -> Vec2d<char> {
-        let input = fs::read_to_string("resources/day16.txt").expect("file day16.txt not found");
-        parse_input(&input)
+impl Day<Vec2d<u8>> for Day16 {
+    fn parse(input: &str) -> Result<Vec2d<u8>> {
+        parse_input(input)
     }
 
-    // Simple implementation of Dijkstra's algorithm to quickly find the best path through the maze
+    // Dijkstra's algorithm to quickly find the best path through the maze
     // Note that we must track both position and direction as the same position might be crossed
     // from a separate direction with a very different cost score.
-    fn part1(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let start = input.find(&'S').unwrap();
-        let start_direction = Directions::Right;
-
-        let mut distances:HashMap<(Point, Directions), i32> = HashMap::new();
-        let mut queue = BinaryHeap::new();
-        queue.push(Node { cost: 0, position: start, direction: start_direction, parent: None });
-        distances.insert((start, start_direction), 0);
-
-        while let Some(current) = queue.pop() {
-            if input[current.position] == 'E' {
-                return current.cost;
-            }
-
-            let current_cost = *distances.get(&(current.position, current.direction)).unwrap_or(&i32::MAX);
-            if current.cost > current_cost {
-                continue;
-            }
-
-            for next_direction in possible_directions(current.direction) {
-                let Some(next_point) = input.next_point(current.position, next_direction) else {
-                    continue;
-                };
-                if input[next_point] == '#' {
-                    continue;
-                }
-                let next_cost = current.cost + 1 + if next_direction == current.direction { 0 } else { 1000 };
-                if next_cost < *distances.get(&(next_point, next_direction)).unwrap_or(&i32::MAX) {
-                    distances.insert((next_point, next_direction), next_cost);
-                    queue.push(Node { cost: next_cost, position: next_point, direction: next_direction, parent: None });
-                }
-            }
-        }
-        0 // Did not find a path
+    fn part1(input: &Vec2d<u8>) -> Result<impl IntoAnswer> {
+        search(input)?.goal_cost().ok_or_else(|| AocError::no_solution("no path from S to E"))
     }
 
-    fn part2(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let paths = best_paths(input);
-        paths.into_iter()
-            .flatten()
-            .collect::<HashSet<_>>()
-            .len()
+    fn part2(input: &Vec2d<u8>) -> Result<impl IntoAnswer> {
+        Ok(best_paths(input)?.tiles.len())
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct Node {
-    cost: i32,
-    position: Point,
-    direction: Directions,
-    parent: Option<Box<Node>>, // used for part 2
+/// The lowest cost ways through the maze
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BestPaths {
+    /// The lowest cost to get from S to E
+    pub cost: i32,
+    /// Every tile on any of the lowest cost paths
+    pub tiles: FxHashSet<Point>,
+    /// One of the lowest cost paths, from S to E
+    pub path: Vec<Point>,
 }
 
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
-    }
+/// Find the lowest cost, every tile on a lowest cost path, and one of those paths.
+///
+/// Search forwards from the start, and backwards from the end (facing any direction).
+/// A state is on one of the best paths exactly when the cheapest way to reach it
+/// plus the cheapest way from it to the end adds up to the best cost.
+///
+/// # Errors
+/// If the maze has no start or end, or no path between them
+pub fn best_paths(input: &Vec2d<u8>) -> Result<BestPaths> {
+    let from_start = search(input)?;
+    let goal = from_start.goals.first().ok_or_else(|| AocError::no_solution("no path from S to E"))?;
+    let cost = from_start.cost(goal).ok_or_else(|| AocError::no_solution("no path from S to E"))?;
+    let path = from_start.path_to(goal).into_iter().map(|(point, _)| point).collect();
+    let to_end = search_back(input)?;
+    let tiles = from_start.costs()
+        .filter(|&(state, state_cost)| to_end.cost(state).is_some_and(|remaining| state_cost + remaining == cost))
+        .map(|(&(point, _), _)| point)
+        .collect::<FxHashSet<_>>();
+    debug!(cost, tiles = tiles.len(), "found the best paths");
+    Ok(BestPaths { cost, tiles, path })
 }
 
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+/// An SVG picture of the maze, with every tile on any of the lowest cost paths filled in green,
+/// and one of those paths drawn through them
+///
+/// # Errors
+/// If the maze has no start or end, or no path between them
+pub fn to_svg(input: &Vec2d<u8>) -> Result<String> {
+    let best = best_paths(input)?;
+    let mut svg = Svg::new(input.line_len, input.height());
+    svg.region(input.find_all(&b'#'), "#333");
+    svg.region(best.tiles, "seagreen");
+    svg.path(&best.path, "gold");
+    svg.region(input.find(&b'S'), "royalblue");
+    svg.region(input.find(&b'E'), "crimson");
+    Ok(svg.to_string())
 }
 
-// Modify the Dijkstra's algorithm from part 1. Now it does not exit when reaching the end point.
-// Instead in continues to create map out paths and costs, but it does not allow a new path
-// to exceed the least cost path (which we find first because dijkstra's algorithm is greedy).
-// 
-// Also changes our nodes to keep track of their parent so we can re-build the exact path taken.
-fn best_paths(input: &Vec2d<char>) -> Vec<Vec<Point>> {
-    let start = input.find(&'S').unwrap();
-    let start_direction = Directions::Right;
-
-    let mut distances:HashMap<(Point, Directions), i32> = HashMap::new();
-    let mut queue = BinaryHeap::new();
-    let mut best_paths = Vec::new();
-    let mut best_cost: i32 = i32::MAX;
-    queue.push(Node { cost: 0, position: start, direction: start_direction, parent: None });
-    distances.insert((start, start_direction), 0);
-
-    while let Some(current) = queue.pop() {
-        if current.cost > best_cost {
-            continue;
-        }
-
-        if input[current.position] == 'E' {
-            best_cost = current.cost;
-            let path = determine_path(current);
-            best_paths.push(path);
-            continue;
-        }
-
-        let current_cost = *distances.get(&(current.position, current.direction)).unwrap_or(&i32::MAX);
-        // There is a better path, so this node cannot be on the best path
-        if current.cost > current_cost {
-            continue;
-        }
-
-        for next_direction in possible_directions(current.direction) {
-            let Some(next_point) = input.next_point(current.position, next_direction) else {
-                continue;
-            };
-            if input[next_point] == '#' {
-                continue;
-            }
-            let next_cost = current.cost + 1 + if next_direction == current.direction { 0 } else { 1000 };
-            if next_cost <= *distances.get(&(next_point, next_direction)).unwrap_or(&i32::MAX) {
-                distances.insert((next_point, next_direction), next_cost);
-                let next_node = Node { 
-                    cost: next_cost, 
-                    position: next_point, 
-                    direction: next_direction,
-                    parent: Some(Box::new(current.clone()))
-                };
-                queue.push(next_node.clone());
-            }
-        }
-    }
-    best_paths
-}
-
-fn possible_directions(direction: Directions) -> Vec<Directions> {
-    match direction {
-        Directions::Down => vec![Directions::Down, Directions::Left, Directions::Right],
-        Directions::Left => vec![Directions::Left, Directions::Up, Directions::Down],
-        Directions::Up => vec![Directions::Up, Directions::Left, Directions::Right],
-        Directions::Right => vec![Directions::Right, Directions::Up, Directions::Down],
-        _ => panic!("Unsupported direction: {direction:?}"),
-    }
+/// Search from the start (facing right) until every lowest cost way of reaching the end is found
+fn search(input: &Vec2d<u8>) -> Result<Dijkstra<(Point, Directions), i32>> {
+    let start = input.find(&b'S').ok_or_else(|| AocError::parse("no start S in the maze"))?;
+    let neighbors = |&(position, direction): &(Point, Directions)| {
+        [direction, direction.turn_left(), direction.turn_right()].into_iter()
+            .filter_map(|next_direction| {
+                let next_point = input.next_point(position, next_direction)?;
+                if input[next_point] == b'#' {
+                    return None;
+                }
+                let cost = 1 + if next_direction == direction { 0 } else { 1000 };
+                Some(((next_point, next_direction), cost))
+            })
+            .collect::<Vec<_>>()
+    };
+    let result = dijkstra((start, Directions::Right), neighbors, |&(position, _)| input[position] == b'E');
+    debug!(cost = result.goal_cost(), explored = result.explored(), "reached the end");
+    Ok(result)
 }
 
-fn determine_path(end: Node) -> Vec<Point> {
-    let mut path = vec![end.position];
-    let mut current = end;
-    while let Some(next) = current.parent {
-        path.push(next.position);
-        current = *next;
-    }
-    path.reverse();
-    path
+/// Search backwards from the end, facing any direction, until the start is reached.
+/// Each move is reversed: a state is reached from the point behind it, facing the same way or turned.
+fn search_back(input: &Vec2d<u8>) -> Result<Dijkstra<(Point, Directions), i32>> {
+    let start = input.find(&b'S').ok_or_else(|| AocError::parse("no start S in the maze"))?;
+    let end = input.find(&b'E').ok_or_else(|| AocError::parse("no end E in the maze"))?;
+    let neighbors = |&(position, direction): &(Point, Directions)| {
+        let Some(previous_point) = input.next_point(position, direction.opposite()) else {
+            return Vec::new();
+        };
+        if input[previous_point] == b'#' {
+            return Vec::new();
+        }
+        [direction, direction.turn_left(), direction.turn_right()].into_iter()
+            .map(|previous_direction| {
+                let cost = 1 + if previous_direction == direction { 0 } else { 1000 };
+                ((previous_point, previous_direction), cost)
+            })
+            .collect()
+    };
+    let ends = Directions::cardinal().map(|direction| (end, direction));
+    Ok(dijkstra_multi(ends, neighbors, |&state| state == (start, Directions::Right)))
 }
 
-fn parse_input(input: &str) -> Vec2d<char> {
-    let chars = input.lines()
-        .flat_map(|line| line.trim().chars().collect::<Vec<_>>())
-        .collect();
-    let line_len = input.lines().next().unwrap().len();
-    Vec2d {
-        grid: chars,
-        line_len: line_len as i32,
-    }
+fn parse_input(input: &str) -> Result<Vec2d<u8>> {
+    Vec2d::from_bytes(input)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
 
-    const TEST: &str = "###############
-#.......#....E#
-#.#.###.#.###.#
-#.....#.#...#.#
-#.###.#####.#.#
-#.#.#.......#.#
-#.#.#####.###.#
-#...........#.#
-###.#.#####.#.#
-#...#.....#.#.#
-#.#.#.###.#.#.#
-#.....#...#.#.#
-#.###.#.#.#.#.#
-#S..#.....#...#
-###############";
+    aoc_test!(day16, part1, example 1, "7036"; example 2, "11048");
+    aoc_test!(day16, part2, example 1, "45"; example 2, "64");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day16::part1(&input);
-        assert_eq!("7036", result.to_string())
+    fn test_to_svg() {
+        let input = parse_input(&example(16, 1)).unwrap();
+        let svg = to_svg(&input).unwrap();
+        assert!(svg.contains("viewBox=\"0 0 150 150\""));
+        let best = svg.lines().find(|line| line.contains("seagreen")).unwrap();
+        assert_eq!(45, best.matches('M').count());
+        assert_eq!(1, svg.matches("<polyline").count());
     }
 
     #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result =  Day16::part2(&input);
-        assert_eq!("45", result.to_string())
+    fn test_best_paths() {
+        let input = parse_input(&example(16, 1)).unwrap();
+        let best = best_paths(&input).unwrap();
+        assert_eq!(7036, best.cost);
+        assert_eq!(45, best.tiles.len());
+        assert_eq!(Some(&Point::new(1, 13)), best.path.first());
+        assert_eq!(Some(&Point::new(13, 1)), best.path.last());
+        assert!(best.path.iter().all(|point| best.tiles.contains(point)));
+        // every step moves to a neighbor, and every turn (including from facing right at the start) costs 1000
+        assert!(best.path.windows(2).all(|pair| pair[1].neighbors4().any(|next| next == pair[0])));
+        let facing = [best.path[0] - Point::new(1, 0)];
+        let steps = facing.iter().chain(&best.path).copied().collect::<Vec<_>>();
+        let turns = steps.windows(3).filter(|w| w[2] - w[1] != w[1] - w[0]).count();
+        assert_eq!(7036, best.path.len() - 1 + 1000 * turns);
     }
-
 }
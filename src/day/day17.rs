@@ -1,5 +1,7 @@
-use super::Day;
-use std::fs;
+use super::{Day, IntoAnswer};
+use std::fmt::Write;
+use crate::parse;
+use crate::util::error::{AocError, Result};
 
 /// Day 17: Chronospatial Computer
 /// 
@@ -17,32 +19,39 @@ use std::fs;
 /// in register a that would cause the program to output itself?
 pub struct Day17;
 
+/// The state of the 3 bit computer. Run a program one instruction at a time with [`Computer::step`],
+/// or until it halts with [`Computer::run`].
 #[derive(Debug, Clone)]
 pub struct Computer {
     register_a: u64,
     register_b: u64,
     register_c: u64,
+    instruction_pointer: usize,
     output: Vec<u64>,
 }
 
-type Debugger = (Computer, Vec<u64>);
+/// The result of running a single instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Ran,
+    Output(u64),
+    /// The instruction pointer is past the end of the program. Nothing was run
+    Halted,
+}
+
+pub type Debugger = (Computer, Vec<u64>);
 
 impl Day<Debugger> for Day17 {
-    fn read_input() -> Debugger {
-        let input = fs::read_to_string("resources/day17.txt").expect("file day17.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Debugger> {
+        parse_input(input)
     }
 
     // Straightforware implementation of the program logic and running it.
-    fn part1(input: &Debugger) -> impl std::fmt::Display {
+    fn part1(input: &Debugger) -> Result<impl IntoAnswer> {
         let (computer, program) = input;
         let mut computer = computer.clone();
-        run_program(&mut computer, program);
-
-        // Rust has a `join()` but it only works on strings, not u64
-        let output = computer.output.iter()
-            .fold(String::new(), |acc, next| format!("{acc},{next}" ));
-        output[1..].to_string()
+        run_program(&mut computer, program)?;
+        Ok(computer.output.iter().map(ToString::to_string).collect::<Vec<_>>().join(","))
     }
 
     /// This requires some explanation.
@@ -57,104 +66,329 @@ impl Day<Debugger> for Day17 {
     /// 4. So take [success * 8, success * 8 + 8). This range represents all possible states that end in success.
     /// 5. Now run the program and compare the output (now 2 digits) to the last 2 digits of the program.
     /// 6. Repeat this process until we solve for the full length of the program
-    fn part2(input: &Debugger) -> impl std::fmt::Display {
+    ///
+    /// Other programs can shift a by a different number of bits each pass, see [`loop_shift`].
+    /// If the program doesn't have that shape, fall back to trying every value of a in order.
+    fn part2(input: &Debugger) -> Result<impl IntoAnswer> {
         let (computer, program) = input;
-        let mut possible_values = vec![0];
-        let mut from_end = program.len();
-        while from_end > 0 {
-            from_end -= 1;
-            possible_values = possible_values.into_iter()
-                .flat_map(|a| a * 8 .. a * 8 + 8)
-                .map(|a| {
-                    let mut test_computer = computer.clone();
-                    test_computer.register_a = a;
-                    run_program(&mut test_computer, program);
-                    (a, test_computer.output.clone())
-                })
-                .filter(|(_, output)| output[..] == program[from_end..])
-                .map(|(a, _)| a)
-                .collect();
-        }
-        possible_values.into_iter().min().unwrap()
+        let a = match loop_shift(program) {
+            Some(shift) => quine_search(computer, program, shift, program.len(), 0),
+            None => brute_force_quine(computer, program, BRUTE_FORCE_LIMIT),
+        };
+        a.ok_or_else(|| AocError::no_solution("no value of register a outputs the program"))
     }
 }
 
-fn run_program(computer: &mut Computer, program: &[u64]) {
-    let mut instruction_pointer = 0;
-    while let Some(&operator) = program.get(instruction_pointer) {
-        let operand = program[instruction_pointer + 1];
-        match operator {
-            0 => computer.register_a /= u64::pow(2, computer.combo_operand(operand).try_into().unwrap()),
-            1 => computer.register_b ^= operand,
-            2 => computer.register_b = computer.combo_operand(operand) % 8,
-            3 => if computer.register_a != 0 { instruction_pointer = usize::try_from(operand).unwrap() },
-            4 => computer.register_b ^= computer.register_c,
-            5 => computer.output.push(computer.combo_operand(operand) % 8),
-            // the rust exponential methods for u64 take a u64 and a u32. Some lossy casting must be performed
-            6 => computer.register_b = computer.register_a / u64::pow(2, computer.combo_operand(operand).try_into().unwrap()),
-            7 => computer.register_c = computer.register_a / u64::pow(2, computer.combo_operand(operand).try_into().unwrap()),
-            _ => println!("Invalid operand {operand}"),
+/// How many values of register a to try when the program isn't a simple loop
+const BRUTE_FORCE_LIMIT: u64 = 1 << 24;
+
+/// Stop a program that hasn't halted after this many steps, in case it never does
+const MAX_STEPS: usize = 100_000;
+
+/// The number of bits of register a that each pass through the program consumes,
+/// if the program is a single loop (`jnz 0` as the last instruction, and no other jumps)
+/// that outputs once per pass, and only changes a by shifting it a literal number of bits.
+/// For the usual input that is `a = a >> 3`, or dividing by 8.
+fn loop_shift(program: &[u64]) -> Option<u32> {
+    let instructions = program.chunks(2).collect::<Vec<_>>();
+    let (&last, body) = instructions.split_last()?;
+    if last != [3, 0] || body.iter().any(|instruction| instruction[0] == 3) {
+        return None;
+    }
+    if body.iter().filter(|instruction| instruction[0] == 5).count() != 1 {
+        return None;
+    }
+    let mut shift = 0;
+    for instruction in body {
+        match *instruction {
+            [0, bits @ 0 ..= 3] => shift += bits,
+            [0, _] | [_] => return None,
+            _ => {}
         }
+    }
+    u32::try_from(shift).ok().filter(|&shift| shift > 0)
+}
+
+/// Work backwards from the end of the program, `shift` bits of register a at a time.
+/// Each value of a that outputs the last `from_end` digits of the program can be extended
+/// by `shift` more low bits to output one more digit.
+/// Try the smallest bits first, so the first value found is the lowest.
+fn quine_search(computer: &Computer, program: &[u64], shift: u32, from_end: usize, a: u64) -> Option<u64> {
+    if from_end == 0 {
+        return Some(a);
+    }
+    let from_end = from_end - 1;
+    (a << shift .. (a << shift) + (1 << shift))
+        .filter(|&next| run_with_a(computer, program, next, program.len()).is_ok_and(|output| output == program[from_end ..]))
+        .find_map(|next| quine_search(computer, program, shift, from_end, next))
+}
+
+/// Try every value of register a below `limit` in order
+fn brute_force_quine(computer: &Computer, program: &[u64], limit: u64) -> Option<u64> {
+    (0 .. limit).find(|&a| run_with_a(computer, program, a, program.len()).is_ok_and(|output| output == program))
+}
 
-        if operator != 3 || computer.register_a == 0 {
-            instruction_pointer += 2;
+/// The output of the program with `a` in register a, stopping once it outputs more than `max_len` values.
+/// A program that fails can't output itself, so the searches treat an error as a mismatch.
+fn run_with_a(computer: &Computer, program: &[u64], a: u64, max_len: usize) -> Result<Vec<u64>> {
+    let mut computer = computer.clone();
+    computer.register_a = a;
+    for _ in 0 .. MAX_STEPS {
+        if computer.output.len() > max_len || computer.step(program)? == Step::Halted {
+            break;
         }
-    } 
+    }
+    Ok(computer.output)
+}
+
+fn run_program(computer: &mut Computer, program: &[u64]) -> Result<()> {
+    computer.run(program)
+}
+
+/// Render the program as readable pseudo-code, one instruction per line
+/// prefixed with its instruction pointer, such as `0: b = a % 8`.
+/// Divisions by a power of 2 are written as shifts.
+#[must_use]
+pub fn disassemble(program: &[u64]) -> String {
+    let mut code = String::new();
+    for (idx, instruction) in program.chunks(2).enumerate() {
+        writeln!(code, "{}: {}", idx * 2, disassemble_instruction(instruction)).unwrap();
+    }
+    code
+}
+
+/// Render a single instruction (the operator and operand) as pseudo-code, see [`disassemble`]
+#[must_use]
+pub fn disassemble_instruction(instruction: &[u64]) -> String {
+    let combo = |operand: u64| match operand {
+        0 ..= 3 => operand.to_string(),
+        4 => "a".to_string(),
+        5 => "b".to_string(),
+        6 => "c".to_string(),
+        _ => format!("<reserved {operand}>"),
+    };
+    match *instruction {
+        [0, operand] => format!("a = a >> {}", combo(operand)),
+        [1, operand] => format!("b = b ^ {operand}"),
+        [2, operand] => format!("b = {} % 8", combo(operand)),
+        [3, operand] => format!("jnz {operand}"),
+        [4, _] => "b = b ^ c".to_string(),
+        [5, operand] => format!("out({} % 8)", combo(operand)),
+        [6, operand] => format!("b = a >> {}", combo(operand)),
+        [7, operand] => format!("c = a >> {}", combo(operand)),
+        [operator, operand] => format!("invalid {operator},{operand}"),
+        [operator] => format!("{operator} with no operand"),
+        _ => "invalid instruction".to_string(),
+    }
 }
 
 impl Computer {
-    fn combo_operand(&self, operand: u64) -> u64 {
+    #[must_use]
+    pub fn new(register_a: u64, register_b: u64, register_c: u64) -> Self {
+        Self { register_a, register_b, register_c, instruction_pointer: 0, output: Vec::new() }
+    }
+
+    /// The a, b, and c registers
+    #[must_use]
+    pub fn registers(&self) -> (u64, u64, u64) {
+        (self.register_a, self.register_b, self.register_c)
+    }
+
+    pub fn set_register_a(&mut self, value: u64) {
+        self.register_a = value;
+    }
+
+    #[must_use]
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    /// Every value output so far
+    #[must_use]
+    pub fn output(&self) -> &[u64] {
+        &self.output
+    }
+
+    /// Run the instruction at the instruction pointer
+    ///
+    /// # Errors
+    /// If the operator isn't one of the 8 instructions, or the instruction uses the reserved combo operand 7
+    pub fn step(&mut self, program: &[u64]) -> Result<Step> {
+        let (Some(&operator), Some(&operand)) = (program.get(self.instruction_pointer), program.get(self.instruction_pointer + 1)) else {
+            return Ok(Step::Halted);
+        };
+        let mut step = Step::Ran;
+        match operator {
+            0 => self.register_a = self.shifted_a(operand)?,
+            1 => self.register_b ^= operand,
+            2 => self.register_b = self.combo_operand(operand)? % 8,
+            3 => if self.register_a != 0 {
+                self.instruction_pointer = usize::try_from(operand)
+                    .map_err(|_| AocError::parse(format!("jnz {operand} is out of range")))?;
+            },
+            4 => self.register_b ^= self.register_c,
+            5 => {
+                let value = self.combo_operand(operand)? % 8;
+                self.output.push(value);
+                step = Step::Output(value);
+            }
+            6 => self.register_b = self.shifted_a(operand)?,
+            7 => self.register_c = self.shifted_a(operand)?,
+            _ => return Err(AocError::parse(format!("invalid instruction {operator},{operand}"))),
+        }
+
+        if operator != 3 || self.register_a == 0 {
+            self.instruction_pointer += 2;
+        }
+        Ok(step)
+    }
+
+    /// Run until the next output (a breakpoint on output), returning the output value,
+    /// or `None` if the program halts first
+    ///
+    /// # Errors
+    /// If an instruction is invalid (see [`Computer::step`]), or the program runs for `MAX_STEPS` without output
+    pub fn run_to_output(&mut self, program: &[u64]) -> Result<Option<u64>> {
+        for _ in 0 .. MAX_STEPS {
+            match self.step(program)? {
+                Step::Ran => {}
+                Step::Output(value) => return Ok(Some(value)),
+                Step::Halted => return Ok(None),
+            }
+        }
+        Err(AocError::no_solution(format!("no output after {MAX_STEPS} steps")))
+    }
+
+    /// Run until the program halts
+    ///
+    /// # Errors
+    /// If an instruction is invalid (see [`Computer::step`]), or the program hasn't halted after `MAX_STEPS`
+    pub fn run(&mut self, program: &[u64]) -> Result<()> {
+        for _ in 0 .. MAX_STEPS {
+            if self.step(program)? == Step::Halted {
+                return Ok(());
+            }
+        }
+        Err(AocError::no_solution(format!("the program hasn't halted after {MAX_STEPS} steps")))
+    }
+
+    /// Register a divided by 2 to the power of the combo operand, which is a right shift.
+    /// Shifting by 64 or more bits leaves nothing.
+    fn shifted_a(&self, operand: u64) -> Result<u64> {
+        Ok(u32::try_from(self.combo_operand(operand)?).ok()
+            .and_then(|shift| self.register_a.checked_shr(shift))
+            .unwrap_or(0))
+    }
+
+    fn combo_operand(&self, operand: u64) -> Result<u64> {
         match operand {
-            0 ..= 3 => operand,
-            4 => self.register_a,
-            5 => self.register_b,
-            6 => self.register_c,
-            _ => panic!("operand {operand} is reserved and not implemented"),
+            0 ..= 3 => Ok(operand),
+            4 => Ok(self.register_a),
+            5 => Ok(self.register_b),
+            6 => Ok(self.register_c),
+            _ => Err(AocError::parse(format!("combo operand {operand} is reserved"))),
         }
     }
 }
 
-fn parse_input(input: &str) -> Debugger {
-    let lines = input.lines().collect::<Vec<_>>();
-    let parse_register = |line: &str| line.split(": ").last().unwrap().trim().parse::<u64>().unwrap();
-    let register_a = parse_register(lines[0]);
-    let register_b = parse_register(lines[1]);
-    let register_c = parse_register(lines[2]);
-
-    let program = lines[4].split(": ").last().unwrap().trim()
-        .split(',')
-        .map(|s| s.parse::<u64>().unwrap())
-        .collect::<Vec<_>>();
-    let computer = Computer { register_a, register_b, register_c, output: Vec::new() };
-    (computer, program)
+fn parse_input(input: &str) -> Result<Debugger> {
+    let (register_a, register_b, register_c, program) = parse!(
+        input,
+        "Register A: {}\nRegister B: {}\nRegister C: {}\n\nProgram: {}" => u64, u64, u64, String
+    )?;
+    let program = program.split(',')
+        .map(str::parse::<u64>)
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some(value) = program.iter().find(|&&value| value > 7) {
+        return Err(AocError::parse(format!("{value} is not a 3 bit number")));
+    }
+    // adv, bst, out, bdv, and cdv take a combo operand, where 7 is reserved
+    if let Some(&[operator, _]) = program.chunks(2).find(|instruction| matches!(instruction, [0 | 2 | 5 | 6 | 7, 7])) {
+        return Err(AocError::parse(format!("instruction {operator},7 uses the reserved combo operand 7")));
+    }
+    let computer = Computer::new(register_a, register_b, register_c);
+    Ok((computer, program))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
+
+    aoc_test!(day17, part1, example 1, "4,6,3,5,6,3,5,2,1,0");
+    aoc_test!(day17, part2, example 2, "117440");
+
+    #[test]
+    fn test_invalid_program() {
+        let program = |program: &str| format!("Register A: 1\nRegister B: 64\nRegister C: 0\n\nProgram: {program}");
+        assert!(matches!(parse_input(&program("2,7")), Err(AocError::Parse(_))));
+        assert!(matches!(parse_input(&program("8,1")), Err(AocError::Parse(_))));
+        assert!(parse_input(&program("1,7")).is_ok());
+        // shifting a by b = 64 bits leaves nothing
+        let input = parse_input(&program("0,5,5,4")).unwrap();
+        assert_eq!("0", Day17::part1(&input).unwrap().to_string());
+        // a program that never outputs
+        let input = parse_input(&program("1,1")).unwrap();
+        assert_eq!("", Day17::part1(&input).unwrap().to_string());
+    }
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!("0: a = a >> 3\n2: out(a % 8)\n4: jnz 0\n", disassemble(&[0, 3, 5, 4, 3, 0]));
+        assert_eq!("0: b = a % 8\n2: c = a >> b\n4: b = b ^ c\n6: 7 with no operand\n", disassemble(&[2, 4, 7, 5, 4, 1, 7]));
+    }
 
     #[test]
-    fn test_part_1() {
-        let test_input = "Register A: 729
-            Register B: 0
-            Register C: 0
+    fn test_step() {
+        let program = [0, 1, 5, 4, 3, 0];
+        let mut computer = Computer::new(4, 0, 0);
+        assert_eq!(Step::Ran, computer.step(&program).unwrap());
+        assert_eq!((2, 0, 0), computer.registers());
+        assert_eq!(Step::Output(2), computer.step(&program).unwrap());
+        assert_eq!(Step::Ran, computer.step(&program).unwrap());
+        assert_eq!(0, computer.instruction_pointer());
+        assert_eq!(Some(1), computer.run_to_output(&program).unwrap());
+        assert_eq!(Some(0), computer.run_to_output(&program).unwrap());
+        assert_eq!(None, computer.run_to_output(&program).unwrap());
+        assert_eq!(&[2, 1, 0], computer.output());
+        assert_eq!(Step::Halted, computer.step(&program).unwrap());
+    }
 
-            Program: 0,1,5,4,3,0";
-        let input = parse_input(test_input);
-        let result =  Day17::part1(&input);
-        assert_eq!("4,6,3,5,6,3,5,2,1,0", result.to_string())
+    #[test]
+    fn test_step_invalid() {
+        let mut computer = Computer::new(4, 0, 0);
+        assert!(matches!(computer.step(&[5, 7]), Err(AocError::Parse(_))));
+        assert!(matches!(computer.step(&[8, 0]), Err(AocError::Parse(_))));
+        assert!(computer.run(&[1, 1, 9, 0]).is_err());
+        // jumps back to the start forever
+        assert!(matches!(Computer::new(4, 0, 0).run(&[3, 0]), Err(AocError::NoSolution(_))));
+        assert!(matches!(Computer::new(4, 0, 0).run_to_output(&[3, 0]), Err(AocError::NoSolution(_))));
+        assert_eq!((4, 1, 0), computer.registers());
     }
 
     #[test]
-    fn test_part_2() {
-        let test_input = "Register A: 2024
-            Register B: 0
-            Register C: 0
+    fn test_brute_force() {
+        let input = parse_input(&example(17, 2)).unwrap();
+        let (computer, program) = &input;
+        assert_eq!(Some(117_440), brute_force_quine(computer, program, 200_000));
+    }
 
-            Program: 0,3,5,4,3,0";
-        let input = parse_input(test_input);
-        let result =  Day17::part2(&input);
-        assert_eq!("117440", result.to_string())
+    #[test]
+    fn test_loop_shift() {
+        assert_eq!(Some(3), loop_shift(&[0, 3, 5, 4, 3, 0]));
+        assert_eq!(Some(3), loop_shift(&[2, 4, 1, 1, 7, 5, 0, 3, 1, 4, 4, 5, 5, 5, 3, 0]));
+        assert_eq!(Some(2), loop_shift(&[0, 1, 5, 4, 0, 1, 3, 0]));
+        // shifting by a register, outputting twice, or jumping anywhere else
+        assert_eq!(None, loop_shift(&[0, 5, 5, 4, 3, 0]));
+        assert_eq!(None, loop_shift(&[0, 3, 5, 4, 5, 4, 3, 0]));
+        assert_eq!(None, loop_shift(&[0, 3, 5, 4, 3, 2]));
     }
 
+    #[test]
+    fn test_split_shift() {
+        // a is shifted 3 bits per pass, 1 and 2 bits at a time
+        let input = parse_input("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,0,2,5,4,3,0").unwrap();
+        assert_eq!("7512128", Day17::part2(&input).unwrap().to_string());
+    }
 }
@@ -1,13 +1,16 @@
-use super::Day;
+use super::{Answer, Context, Day, IntoAnswer};
+use crate::util::dsu::DisjointSet;
+use crate::util::error::{AocError, Result};
 use crate::util::grid::prelude::*;
-use std::cmp::Ordering;
-use std::fs;
-use std::collections::BinaryHeap;
+use crate::util::parse::key_value;
+use crate::util::pathfinding::dijkstra_grid;
+use crate::util::viz::{frame_interval, render, Color};
 
 /// Day 18: RAM Run
 /// 
 /// The puzzle input is a list of (x,y) coordinates. These are obstacles that are dropped sequentially
-/// onto a 71 x 71 unit 2d grid (valid values are 0-70 inclusive).
+/// onto a 71 x 71 unit 2d grid (valid values are 0-70 inclusive). The example is on a smaller grid,
+/// so the size and how many bytes fall in part 1 are the `size` and `fallen` parameters.
 /// 
 /// You start in the top left (0,0) at attempt to get to the bottom right (70,70).
 /// 
@@ -16,101 +19,263 @@ use std::collections::BinaryHeap;
 /// Part 2: Find the first point where there is no longer a valid path from start to end.
 pub struct Day18;
 
+/// The width and height of the memory space, unless the `size` parameter is set
+const SIZE: i32 = 71;
+/// How many bytes have fallen in part 1, unless the `fallen` parameter is set
+const FALLEN: usize = 1024;
+
 impl Day<Vec<Point>> for Day18 {
-    fn read_input() -> Vec<Point> {
-        let input = fs::read_to_string("resources/day18.txt").expect("file day18.txt not found");
-        input.lines()
-            .map(|line| {
-                let pts = line.split(',')
-                    .map(|s| s.parse::<i32>().unwrap())
-                    .collect::<Vec<_>>();
-                Point::new(pts[0], pts[1])
-            })
-            .collect()
-    }
-
-    fn part1(input: &Vec<Point>) -> impl std::fmt::Display {
-        let mut grid = Vec2d {
-            grid: vec![true; 71 * 71],
-            line_len: 71
+    fn parse(input: &str) -> Result<Vec<Point>> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Vec<Point>) -> Result<impl IntoAnswer> {
+        shortest_path(input, SIZE, FALLEN)
+    }
+
+    fn part2(input: &Vec<Point>) -> Result<impl IntoAnswer> {
+        Self::part2_with(input, &Context::default())
+    }
+
+    const ALGORITHMS: &'static [&'static str] = &["reverse-union", "binary-search"];
+    const PARAMS: &'static [&'static str] = &["size", "fallen"];
+
+    fn part1_with(input: &Vec<Point>, context: &Context) -> Result<Answer> {
+        Ok(shortest_path(input, context.param("size", SIZE)?, context.param("fallen", FALLEN)?)?.into())
+    }
+
+    fn part2_with(input: &Vec<Point>, context: &Context) -> Result<Answer> {
+        let search = match context.algorithm() {
+            Some("binary-search") => BlockingSearch::BinarySearch,
+            _ => BlockingSearch::ReverseUnion,
         };
-        for &point in &input[..1024] {
-            grid[point] = false;
-        }
-        find_path(&grid).unwrap()
-    }
-
-    // Solve using a binary search. The binary search finishes at the first impassible grid
-    fn part2(input: &Vec<Point>) -> impl std::fmt::Display {
-        let mut valid_index = 1023;
-        let mut invalid_index = input.len() - 1;
-        while invalid_index - valid_index > 1 {
-            let attempt_index = (valid_index + invalid_index) / 2;
-            let mut grid = Vec2d {
-                grid: vec![true; 71 * 71],
-                line_len: 71
-            };
-            for &point in &input[..=attempt_index] {
-                grid[point] = false;
-            }
-            let path = find_path(&grid);
-            if path.is_some() {
-                valid_index = attempt_index;
-            } else {
-                invalid_index = attempt_index;
-            }
-        }
-        let first_bad_point = input[invalid_index];
-        format!("{},{}", first_bad_point.x, first_bad_point.y)
+        let point = first_blocking_byte(input, context.param("size", SIZE)?, search)?;
+        Ok(format!("{},{}", point.x, point.y).into())
+    }
+}
+
+/// How to find the first byte that blocks the path to the exit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockingSearch {
+    /// Drop every byte, then take them away in reverse order, joining open spaces with a disjoint set
+    /// until the start and exit are connected. One pass over the bytes, with no path finding.
+    ReverseUnion,
+    /// Binary search over how many bytes have fallen, finding a path for each guess
+    BinarySearch,
+}
+
+/// The fewest moves from the top left to the bottom right of a `size` by `size` memory space,
+/// after the first `count` bytes have fallen
+fn shortest_path(bytes: &[Point], size: i32, count: usize) -> Result<i32> {
+    check_memory(bytes, size)?;
+    find_path(&fallen_walls(bytes, size, count), size)
+        .map(|(moves, _)| moves)
+        .ok_or_else(|| AocError::no_solution("no path to the exit"))
+}
+
+/// The size has to be positive and small enough to number every point in the memory space,
+/// and every byte has to fall inside it
+fn check_memory(bytes: &[Point], size: i32) -> Result<()> {
+    if size <= 0 || size.checked_mul(size).is_none() {
+        return Err(AocError::Config(format!("size = {size} is out of range")));
+    }
+    match bytes.iter().find(|point| !(0 .. size).contains(&point.x) || !(0 .. size).contains(&point.y)) {
+        Some(point) => Err(AocError::parse(format!("byte {},{} is outside the memory space", point.x, point.y))),
+        None => Ok(()),
+    }
+}
+
+/// The memory space after the first `count` bytes have fallen
+fn fallen_walls(bytes: &[Point], size: i32, count: usize) -> BitGrid {
+    let mut walls = BitGrid::new(size, size);
+    for &point in bytes.iter().take(count) {
+        walls.insert(point);
+    }
+    walls
+}
+
+/// The first byte to fall that leaves no path from the top left to the bottom right
+/// of a `size` by `size` memory space
+///
+/// # Errors
+/// If the size is out of range, a byte falls outside the memory space,
+/// or the path to the exit is never blocked or is blocked before any byte falls
+pub fn first_blocking_byte(bytes: &[Point], size: i32, search: BlockingSearch) -> Result<Point> {
+    check_memory(bytes, size)?;
+    match search {
+        BlockingSearch::ReverseUnion => blocking_by_reverse_union(bytes, size),
+        BlockingSearch::BinarySearch => blocking_by_binary_search(bytes, size),
     }
 }
 
-/// Use Dijkstra's algorithm to find the shortest path from start to end
-fn find_path(grid: &Vec2d<bool>) -> Option<i32> {
-    let start = Point::new(0, 0);
-    let end = Point::new(70, 70);
-    let mut distances = vec![i32::MAX; grid.grid.len()];
-    let mut queue = BinaryHeap::new();
-    queue.push(Node { position: start, cost: 0 });
-    distances[0] = 0;
-
-    while let Some(current) = queue.pop() {
-        if current.position == end {
-            return Some(current.cost);
+// Work backwards: drop every obstacle, then remove them in reverse order, joining each freed
+// space to its open neighbors. The first obstacle whose removal connects start and end
+// is the first obstacle that blocked the path.
+fn blocking_by_reverse_union(bytes: &[Point], size: i32) -> Result<Point> {
+    let mut walls = fallen_walls(bytes, size, bytes.len());
+    let mut open_spaces = DisjointSet::new((size * size).unsigned_abs() as usize);
+    for point in walls.iter_points().filter(|&point| !walls.contains(point)).collect::<Vec<_>>() {
+        join_open_neighbors(&walls, &mut open_spaces, point);
+    }
+    let start = walls.point_to_idx(Point::new(0, 0));
+    let end = walls.point_to_idx(Point::new(size - 1, size - 1));
+    if open_spaces.connected(start, end) {
+        return Err(AocError::no_solution("the path to the exit is never blocked"));
+    }
+    for &point in bytes.iter().rev() {
+        walls.remove(point);
+        join_open_neighbors(&walls, &mut open_spaces, point);
+        if open_spaces.connected(start, end) {
+            return Ok(point);
         }
-        let current_idx = grid.point_to_idx(current.position);
-        if current.cost > distances[current_idx] {
-            continue;
+    }
+    Err(AocError::no_solution("the exit is blocked before any obstacle falls"))
+}
+
+// The path is open after `open` bytes have fallen and blocked after `closed` bytes,
+// so the byte that blocks it is somewhere in between. Halve the gap until they are next to each other.
+fn blocking_by_binary_search(bytes: &[Point], size: i32) -> Result<Point> {
+    let blocked = |count: usize| find_path(&fallen_walls(bytes, size, count), size).is_none();
+    if !blocked(bytes.len()) {
+        return Err(AocError::no_solution("the path to the exit is never blocked"));
+    }
+    if blocked(0) {
+        return Err(AocError::no_solution("the exit is blocked before any obstacle falls"));
+    }
+    let (mut open, mut closed) = (0, bytes.len());
+    while closed - open > 1 {
+        let count = open + (closed - open) / 2;
+        if blocked(count) {
+            closed = count;
+        } else {
+            open = count;
         }
-        [Directions::Up, Directions::Down, Directions::Left, Directions::Right].into_iter()
-            .filter_map(|d| grid.next_point(current.position, d))
-            .filter(|&point| grid[point])
-            .for_each(|next_pos| {
-                let next_idx = grid.point_to_idx(next_pos);
-                let next_cost = current.cost + 1;
-                if next_cost < distances[next_idx] {
-                    queue.push(Node { position: next_pos, cost: next_cost });
-                    distances[next_idx] = next_cost;
-                }
-            });
-    }
-    None
+    }
+    Ok(bytes[closed - 1])
+}
+
+fn join_open_neighbors(walls: &BitGrid, open_spaces: &mut DisjointSet, point: Point) {
+    let idx = walls.point_to_idx(point);
+    for next in point.neighbors4().filter(|&next| walls.in_bounds(next) && !walls.contains(next)) {
+        open_spaces.union(idx, walls.point_to_idx(next));
+    }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct Node {
-    position: Point,
-    cost: i32,
+/// Use Dijkstra's algorithm to find the shortest path from the top left to the bottom right
+/// of a `size` by `size` memory space. Returns the number of moves, and the points along the path
+/// including both ends.
+fn find_path(walls: &BitGrid, size: i32) -> Option<(i32, Vec<Point>)> {
+    let end = Point::new(size - 1, size - 1);
+    let search = dijkstra_grid(walls, Point::new(0, 0), |&wall| !wall, |&point| point == end);
+    let moves = search.goal_cost()?;
+    Some((moves, search.path_to(&end)))
 }
 
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
+/// Frames of the bytes falling into memory one by one, about `max_frames` of them,
+/// in a memory space the size of the `size` parameter.
+/// Fallen bytes are gray, the latest byte to fall is red, and the start and exit are green.
+///
+/// # Errors
+/// If the size is out of range, or a byte falls outside the memory space
+pub fn falling_frames<'a>(
+    bytes: &'a [Point],
+    context: &Context,
+    max_frames: usize,
+) -> Result<impl Iterator<Item = String> + 'a> {
+    let size = context.param("size", SIZE)?;
+    check_memory(bytes, size)?;
+    // when each point is covered by a fallen byte
+    let mut fallen = Vec2d { grid: vec![usize::MAX; (size * size).unsigned_abs() as usize], line_len: size };
+    for (idx, &point) in bytes.iter().enumerate().rev() {
+        fallen[point] = idx;
     }
+    let every = frame_interval(bytes.len(), max_frames);
+    let counts = (0 .. bytes.len()).step_by(every).chain([bytes.len()]);
+    Ok(counts.map(move |count| {
+        render(size, size, |point| match fallen[point] {
+            idx if idx < count => ('#', if idx + 1 == count { Color::Red } else { Color::Gray }),
+            _ if point == Point::new(0, 0) => ('S', Color::Green),
+            _ if point == Point::new(size - 1, size - 1) => ('E', Color::Green),
+            _ => ('.', Color::Default),
+        })
+    }))
 }
 
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+fn parse_input(input: &str) -> Result<Vec<Point>> {
+    input.lines()
+        .map(|line| {
+            let (x, y) = key_value(line, ",")?;
+            Ok(Point::new(x.parse()?, y.parse()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::example;
+
+    #[test]
+    fn test_part_1() {
+        let input = parse_input(&example(18, 1)).unwrap();
+        assert_eq!(22, shortest_path(&input, 7, 12).unwrap());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_part_2() {
+        let input = parse_input(&example(18, 1)).unwrap();
+        for search in [BlockingSearch::ReverseUnion, BlockingSearch::BinarySearch] {
+            assert_eq!(Point::new(6, 1), first_blocking_byte(&input, 7, search).unwrap());
+            assert!(first_blocking_byte(&input[.. 20], 7, search).is_err());
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let input = parse_input(&example(18, 1)).unwrap();
+        let example = Context::default().with_param("size", 7).with_param("fallen", 12);
+        assert_eq!(Answer::Int(22), Day18::part1_with(&input, &example).unwrap());
+        assert_eq!(Answer::from("6,1"), Day18::part2_with(&input, &example).unwrap());
+        assert!(Day18::part1_with(&input, &example.with_param("size", 1 << 40)).is_err());
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let runner = crate::day::find_day(18).unwrap();
+        let small = Context::default().with_param("size", 3).with_param("fallen", 1);
+        assert!(matches!(runner.answers("-1,0\n2,2", &Context::default()), Err(AocError::Parse(_))));
+        assert!(matches!(runner.answers("5,5", &small), Err(AocError::Parse(_))));
+        for size in [0, -1, 46_341] {
+            let context = Context::default().with_param("size", size);
+            assert!(matches!(runner.answers("1,1", &context), Err(AocError::Config(_))), "{size}");
+        }
+        assert!(falling_frames(&[Point::new(5, 5)], &small, 10).is_err());
+        assert_eq!(2, falling_frames(&[Point::new(1, 1)], &small, 10).unwrap().count());
+    }
+
+    #[test]
+    fn test_find_path() {
+        let input = parse_input(&example(18, 1)).unwrap();
+        let mut walls = fallen_walls(&input, 7, 12);
+        let (moves, path) = find_path(&walls, 7).unwrap();
+        assert_eq!(22, moves);
+        assert_eq!(23, path.len());
+        assert_eq!(Some(&Point::new(0, 0)), path.first());
+        assert_eq!(Some(&Point::new(6, 6)), path.last());
+        assert!(path.iter().all(|&point| !walls.contains(point)));
+
+        // only re-search when a byte lands on the current path, to check part 2 another way
+        let mut path = path;
+        let blocking = input[12 ..].iter().find(|&&point| {
+            walls.insert(point);
+            if !path.contains(&point) {
+                return false;
+            }
+            match find_path(&walls, 7) {
+                Some((_, next)) => { path = next; false }
+                None => true,
+            }
+        });
+        assert_eq!(Some(&Point::new(6, 1)), blocking);
+    }
+}
@@ -1,5 +1,6 @@
-use super::Day;
-use std::{collections::HashMap, fs};
+use super::{Day, IntoAnswer};
+use crate::util::error::Result;
+use crate::util::parse::two_sections;
 
 /// Day 19: Linen Layout
 /// 
@@ -18,90 +19,127 @@ pub struct Day19;
 pub type Towels = (Vec<String>, Vec<String>);
 
 impl Day<Towels> for Day19 {
-    fn read_input() -> Towels {
-        let input = fs::read_to_string("resources/day19.txt").expect("file day19.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Towels> {
+        parse_input(input)
     }
 
-    // Solved in the same way as part 2.
-    // This could be done A LOT faster, but I solved the hard part for part 2 first,
-    // and it ended up being speedy enough that it wasn't worth doing short circuit implementation for part 1
-    fn part1(input: &Towels) -> impl std::fmt::Display {
+    fn part1(input: &Towels) -> Result<impl IntoAnswer> {
         let (supply, patterns) = input;
-        let mut memo = HashMap::new();
-        patterns.iter()
-            .map(|pattern| count_patterns(supply, pattern, &mut memo))
-            .filter(|&count| count > 0)
-            .count()
+        let trie = TowelTrie::new(supply);
+        Ok(patterns.iter()
+            .filter(|pattern| can_make(&trie, pattern))
+            .count())
     }
 
-    fn part2(input: &Towels) -> impl std::fmt::Display {
+    fn part2(input: &Towels) -> Result<impl IntoAnswer> {
         let (supply, patterns) = input;
-        let mut memo = HashMap::new();
-        patterns.iter()
-            .map(|pattern| count_patterns(supply, pattern, &mut memo))
-            .sum::<usize>()
+        let trie = TowelTrie::new(supply);
+        Ok(patterns.iter()
+            .map(|pattern| count_patterns(&trie, pattern))
+            .sum::<usize>())
     }
 }
 
-/// Sove via recursive depth first search with memoization.
-/// The memoization is absolutely essential to eliminate expensive repeating recursive calls
-fn count_patterns<'a>(supply: &Vec<String>, pattern: &'a str, memo: &mut HashMap<&'a str, usize>) -> usize {
-    // we've reached the end of the pattern. That means we have a success
-    if pattern.is_empty() {
-        return 1;
+/// A prefix tree of every towel in the supply, so all towels that fit at a position
+/// in a pattern are found with a single walk down the tree.
+struct TowelTrie {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: [Option<usize>; 26],
+    // a towel ends at this node
+    towel: bool,
+}
+
+impl TowelTrie {
+    fn new(supply: &[String]) -> Self {
+        let mut nodes = vec![TrieNode::default()];
+        for towel in supply {
+            // a towel with a stripe that isn't a letter can never match a pattern
+            let Some(stripes) = towel.bytes().map(stripe_idx).collect::<Option<Vec<_>>>() else {
+                continue;
+            };
+            let mut node = 0;
+            for child in stripes {
+                node = if let Some(next) = nodes[node].children[child] {
+                    next
+                } else {
+                    nodes.push(TrieNode::default());
+                    nodes[node].children[child] = Some(nodes.len() - 1);
+                    nodes.len() - 1
+                };
+            }
+            nodes[node].towel = true;
+        }
+        Self { nodes }
     }
-    if let Some(val) = memo.get(pattern) {
-        return *val;
+
+    /// The length of every towel that matches the pattern starting at `start`
+    fn matches<'a>(&'a self, pattern: &'a [u8], start: usize) -> impl Iterator<Item = usize> + 'a {
+        pattern[start ..].iter()
+            .scan(0, |node, &stripe| {
+                *node = self.nodes[*node].children[stripe_idx(stripe)?]?;
+                Some(*node)
+            })
+            .zip(1 ..)
+            .filter(|&(node, _)| self.nodes[node].towel)
+            .map(|(_, len)| len)
     }
+}
 
-    // loop through each towel type in the supply. If the pattern starts with this towel,
-    // create a recursive branch to find all possible combos of that towel + the rest of the pattern.
-    let mut count = 0;
-    for towel in supply {
-        if pattern.starts_with(towel) {
-            let valid_count = count_patterns(supply, &pattern[towel.len()..], memo);
-            memo.insert(&pattern[towel.len()..], valid_count);
-            count += valid_count;
+fn stripe_idx(stripe: u8) -> Option<usize> {
+    stripe.checked_sub(b'a').map(usize::from).filter(|&idx| idx < 26)
+}
+
+/// Mark every position in the pattern that a sequence of towels can reach,
+/// stopping as soon as one reaches the end of the pattern
+fn can_make(trie: &TowelTrie, pattern: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let mut reachable = vec![false; pattern.len() + 1];
+    reachable[0] = true;
+    for start in 0 .. pattern.len() {
+        if !reachable[start] {
+            continue;
+        }
+        for len in trie.matches(pattern, start) {
+            if start + len == pattern.len() {
+                return true;
+            }
+            reachable[start + len] = true;
         }
     }
-    count
+    pattern.is_empty()
 }
 
-fn parse_input(input: &str) -> Towels {
-    let parts = input.split("\n\n").collect::<Vec<_>>();
-    let supply = parts[0].split(", ").map(ToString::to_string).collect::<Vec<_>>();
-    let patterns = parts[1].lines().map(ToString::to_string).collect::<Vec<_>>();
-    (supply, patterns)
+/// Dynamic programming from the end of the pattern back to the start.
+/// `ways[i]` is how many combinations of towels make the rest of the pattern from position `i`,
+/// which is the sum of `ways` just past each towel that matches at `i`.
+fn count_patterns(trie: &TowelTrie, pattern: &str) -> usize {
+    let pattern = pattern.as_bytes();
+    let mut ways = vec![0; pattern.len() + 1];
+    // we've reached the end of the pattern. That means we have a success
+    ways[pattern.len()] = 1;
+    for start in (0 .. pattern.len()).rev() {
+        ways[start] = trie.matches(pattern, start)
+            .map(|len| ways[start + len])
+            .sum();
+    }
+    ways[0]
+}
+
+fn parse_input(input: &str) -> Result<Towels> {
+    let (supply, patterns) = two_sections(input, "towels and patterns")?;
+    let supply = supply.split(", ").map(ToString::to_string).collect::<Vec<_>>();
+    let patterns = patterns.lines().map(ToString::to_string).collect::<Vec<_>>();
+    Ok((supply, patterns))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    const TEST: &str = "r, wr, b, g, bwu, rb, gb, br
-
-brwrr
-bggr
-gbbr
-rrbgbr
-ubwu
-bwurrg
-brgr
-bbrgwb";
-
-    #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day19::part1(&input);
-        assert_eq!("6", result.to_string())
-    }
-
-    #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result = Day19::part2(&input);
-        assert_eq!("16", result.to_string())
-    }
+    use crate::aoc_test;
 
+    aoc_test!(day19, part1, example 1, "6");
+    aoc_test!(day19, part2, example 1, "16");
 }
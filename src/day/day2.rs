@@ -1,5 +1,6 @@
-use super::Day;
-use std::fs;
+use super::{Day, IntoAnswer};
+use crate::util::error::Result;
+use crate::util::sequences::{Direction, StepRule};
 
 /// Day 2: Red-Nosed Reports
 /// 
@@ -15,90 +16,43 @@ use std::fs;
 /// How many reports are safe if one number can be removed from the report?
 pub struct Day2;
 
-impl Day<Vec<Vec<i32>>> for Day2 {
-    fn read_input() ->  Vec<Vec<i32>> {
-        let input = fs::read_to_string("resources/day2.txt").expect("file day2.txt not found");
-        parse_input(&input)
-    }
-
-    fn part1(input: &Vec<Vec<i32>>) -> impl std::fmt::Display {
-        input.iter()
-            .filter(|report| Self::is_safe(report))
-            .count()
-    }
+/// Levels all increase or all decrease, by between 1 and 3 at each step
+pub const SAFE_REPORT: StepRule = StepRule {
+    min_step: 1,
+    max_step: 3,
+    direction: Direction::Either,
+};
 
-    fn part2(input: &Vec<Vec<i32>>) -> impl std::fmt::Display {
-        input.iter()
-            .filter(|report| Self::problem_dampener(report))
-            .count()
+impl Day<Vec<Vec<i32>>> for Day2 {
+    fn parse(input: &str) -> Result<Vec<Vec<i32>>> {
+        parse_input(input)
     }
-}
 
-impl Day2 {
-    fn is_safe(report: &[i32]) -> bool {
-        let increasing = report[0] < report[1];
-        for i in 1 .. report.len(){
-            if report[i - 1] < report[i] && !increasing {
-                return false;
-            } else if report[i - 1] > report[i] && increasing {
-                return false;
-            } else if report[i - 1] == report[i] {
-                return false;
-            }
-            
-            if i32::abs(report[i] - report[i-1]) > 3 {
-                return false;
-            }
-        }
-        true
+    fn part1(input: &Vec<Vec<i32>>) -> Result<impl IntoAnswer> {
+        Ok(input.iter()
+            .filter(|report| SAFE_REPORT.is_valid(report))
+            .count())
     }
 
-    fn problem_dampener(report: &[i32]) -> bool {
-        if Self::is_safe(report) {
-            return true;
-        }
-        for i in 0..report.len() {
-            let mut r = report.to_owned();
-            r.remove(i);
-            if Self::is_safe(&r) {
-                return true;
-            }
-        }
-        false
+    fn part2(input: &Vec<Vec<i32>>) -> Result<impl IntoAnswer> {
+        Ok(input.iter()
+            .filter(|report| SAFE_REPORT.is_valid_with_removal(report))
+            .count())
     }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<i32>> {
-    input.lines().map(|line| 
+fn parse_input(input: &str) -> Result<Vec<Vec<i32>>> {
+    Ok(input.lines().map(|line| 
         line.split_whitespace()
-            .map(|s| s.parse().expect("invalid int"))
+            .map(str::parse)
             .collect()
-    ).collect()
+    ).collect::<Result<_, _>>()?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "7 6 4 2 1
-        1 2 7 8 9
-        9 7 6 2 1
-        1 3 2 4 5
-        8 6 4 4 1
-        1 3 6 7 9";
-
-    #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST_INPUT);
-        let result =  Day2::part1(&input);
-        assert_eq!("2", result.to_string())
-    }
-
-    #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST_INPUT);
-        let result =  Day2::part2(&input);
-        assert_eq!("4", result.to_string())
-    }
+    use crate::aoc_test;
 
+    aoc_test!(day2, part1, example 1, "2");
+    aoc_test!(day2, part2, example 1, "4");
 }
\ No newline at end of file
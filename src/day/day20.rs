@@ -1,8 +1,10 @@
-use super::Day;
+use std::collections::BTreeMap;
+
+use super::{Answer, Context, Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
 use crate::util::grid::prelude::*;
-use std::cmp::Ordering;
-use std::fs;
-use std::collections::BinaryHeap;
+use crate::util::pathfinding::bfs;
+use crate::util::viz::Svg;
 
 /// Day 20: Race Condition
 /// 
@@ -20,186 +22,190 @@ use std::collections::BinaryHeap;
 /// it still only counts once). Now how many solutions finish the maze at least 100 moves faster?
 pub struct Day20;
 
-const DIRECTIONS: [Directions; 4] = [Directions::Down, Directions::Up, Directions::Left, Directions::Right];
+/// The fewest moves a cheat has to save to be counted, unless the `min_saving` parameter is set
+/// (the examples count much smaller savings)
+const MIN_SAVING: i32 = 100;
 
-impl Day<Vec2d<char>> for Day20 {
-    fn read_input() -> Vec2d<char> {
-        let input = fs::read_to_string("resources/day20.txt").expect("file day20.txt not found");
-        parse_input(&input)
+impl Day<Vec2d<u8>> for Day20 {
+    fn parse(input: &str) -> Result<Vec2d<u8>> {
+        parse_input(input)
     }
 
-    // Solved using lots and lots of dijkstra. But it's pretty speedy.
-    fn part1(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let start = input.find(&'S').unwrap();
-        let end = input.find(&'E').unwrap();
-         // Full dijkstra distance map from END to all points.
-        let dijkstra_map = dijkstra_map(end, input);
-        let max_time = dijkstra_map[input.point_to_idx(start)] - 100;
-
-        // Now we'll traverse the maze using dijstra staring at the start point
-        let mut distances = vec![i32::MAX; input.grid.len()];
-        distances[input.point_to_idx(start)] = 0;
-        let mut queue = BinaryHeap::new();
-        queue.push(Node { cost: distances[input.point_to_idx(start)], position: start });
-
-        let mut total_solutions = 0;
-        while let Some(current) = queue.pop() {
-
-            // Short circuit stop once we've exceeded our max time
-            if current.cost > max_time {
-                continue;
-            }
-            if current.cost > distances[input.point_to_idx(current.position)] {
-                continue;
-            }
+    // A cheat of 2 moves can only pass through a single wall
+    fn part1(input: &Vec2d<u8>) -> Result<impl IntoAnswer> {
+        count_cheats(input, 2, MIN_SAVING)
+    }
 
-            for direction in DIRECTIONS {
-                let Some(next_pos) = input.next_point(current.position, direction) else {
-                    continue;
-                };
-                if input[next_pos] == '#' {
-                    // For walls, attempt to cheat. If cheating is possible,
-                    // look up the path cost from the new post-cheat position
-                    let Some(cheat_pos) = input.next_point(next_pos, direction) else {
-                        continue;
-                    };
-                    let cheat_idx= input.point_to_idx(cheat_pos);
-                    if input[cheat_pos] != '#' && distances[cheat_idx] > current.cost + 2 {
-                        let cheat_cost = current.cost + 2 + dijkstra_map[cheat_idx];
-                        if cheat_cost <= max_time {
-                            // If cheating gets us to the finish in under the upper time limit, count it
-                            total_solutions += 1;
-                        }
-                    }
-                } else {
-                    // For open spaces, use the standard dijkstra algorithm
-                    let next_idx = input.point_to_idx(next_pos);
-                    if current.cost + 1 < distances[next_idx] {
-                        let next = Node { cost: current.cost + 1, position: next_pos };
-                        distances[input.point_to_idx(next_pos)] = next.cost;
-                        queue.push(next);
-                    }
-                }
-            }
-        }
-        total_solutions
+    fn part2(input: &Vec2d<u8>) -> Result<impl IntoAnswer> {
+        count_cheats(input, 20, MIN_SAVING)
     }
 
-    // Solved the same way as part 1, except we cheat in a different way
-    fn part2(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let start = input.find(&'S').unwrap();
-        let end = input.find(&'E').unwrap();
+    const PARAMS: &'static [&'static str] = &["min_saving"];
 
-        // Full dijkstra distance map from END to all points.
-        let dijkstra_map = dijkstra_map(end, input);
-        let max_time = dijkstra_map[input.point_to_idx(start)] - 100;
+    fn part1_with(input: &Vec2d<u8>, context: &Context) -> Result<Answer> {
+        Ok(count_cheats(input, 2, context.param("min_saving", MIN_SAVING)?)?.into())
+    }
 
-        let mut distances = vec![i32::MAX; input.grid.len()];
-        distances[input.point_to_idx(start)] = 0;
-        let mut queue = BinaryHeap::new();
-        queue.push(Node { position: start, cost: 0 });
+    fn part2_with(input: &Vec2d<u8>, context: &Context) -> Result<Answer> {
+        Ok(count_cheats(input, 20, context.param("min_saving", MIN_SAVING)?)?.into())
+    }
+}
 
-        let mut total_solutions = 0;
-        while let Some(current) = queue.pop() {
-            if current.cost > max_time {
-                continue;
-            }
-            if current.cost > distances[input.point_to_idx(current.position)] {
-                continue;
-            }
+/// There is only one path through the maze, so the distance from the start to each point on it
+/// is also how far along the path that point is.
+/// A cheat from one point to another later on the path skips everything between them,
+/// and the cheat itself costs the manhattan distance between the two points.
+///
+/// For every point on the path, look at every point within `cheat_len` of it (a diamond shape)
+/// and count the cheats that save at least `min_saving` moves.
+///
+/// # Errors
+/// If the maze has no start or end, or no path between them
+pub fn count_cheats(input: &Vec2d<u8>, cheat_len: i32, min_saving: i32) -> Result<usize> {
+    let distances = path_distances(input)?;
+    let mut total_solutions = 0;
+    find_cheats(&distances, cheat_len, min_saving, |_, _, _| total_solutions += 1);
+    Ok(total_solutions)
+}
 
-            for direction in DIRECTIONS {
-                let Some(next_pos) = input.next_point(current.position, direction) else {
+/// How many cheats of at most `cheat_len` moves save each number of moves, like the tables in the puzzle text.
+/// Only cheats that save time are counted.
+///
+/// # Errors
+/// If the maze has no start or end, or no path between them
+pub fn cheat_savings(input: &Vec2d<u8>, cheat_len: i32) -> Result<BTreeMap<i32, usize>> {
+    let distances = path_distances(input)?;
+    let mut savings = BTreeMap::new();
+    find_cheats(&distances, cheat_len, 1, |_, _, saving| *savings.entry(saving).or_default() += 1);
+    Ok(savings)
+}
+
+/// Call `found` with the start and end of every cheat of at most `cheat_len` moves
+/// that saves at least `min_saving` moves, along with how many moves it saves
+fn find_cheats(distances: &Vec2d<Option<i32>>, cheat_len: i32, min_saving: i32, mut found: impl FnMut(Point, Point, i32)) {
+    for (position, &cost) in distances.iter_with_points() {
+        let Some(cost) = cost else {
+            continue;
+        };
+        for dx in -cheat_len ..= cheat_len {
+            let dy_range = cheat_len - dx.abs();
+            for dy in -dy_range ..= dy_range {
+                let cheat_point = position + Point::new(dx, dy);
+                // our position after cheating should be on the path
+                let Some(&Some(cheat_cost)) = distances.get(cheat_point) else {
                     continue;
                 };
-                if input[next_pos] != '#' {
-                    // For open spaces, use the standard dijkstra algorithm
-                    let next_idx = input.point_to_idx(next_pos);
-                    if current.cost + 1 < distances[next_idx] {
-                        let next = Node { cost: current.cost + 1, position: next_pos };
-                        distances[input.point_to_idx(next_pos)] = next.cost;
-                        queue.push(next);
-                    }
-                }
-            }
-            // Always try to cheat from any point we traverse using our dijstra pathfinding algorithm
-            // First, examine all points that are within a manhattan distance of 20
-            for x in current.position.x - 20 ..= current.position.x + 20 {
-                let y_range = 20 - i32::abs(current.position.x - x);
-                for y in current.position.y - y_range ..= current.position.y + y_range {
-                    let cheat_point = Point::new(x, y);
-                    let manhattan = cheat_point.manhattan_distance(&current.position);
-                    // our position after cheating should be in bounds and not a wall
-                    if cheat_point != current.position 
-                        && input.in_bounds(cheat_point) 
-                        && input[cheat_point] != '#' 
-                        && manhattan <= 20 
-                    {
-                        // constant time lookup for how far away the end is from our cheat position
-                        let cheat_solve = current.cost + manhattan + dijkstra_map[input.point_to_idx(cheat_point)];
-                        if cheat_solve <= max_time {
-                            total_solutions += 1;
-                        }
-                    }
+                let saving = cheat_cost - cost - (dx.abs() + dy.abs());
+                if saving >= min_saving {
+                    found(position, cheat_point, saving);
                 }
             }
         }
-        total_solutions
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct Node {
-    cost: i32,
-    position: Point,
+/// An SVG picture of the race track, with the path from S to E drawn in blue,
+/// and each part 1 cheat drawn in red from where it starts to where it ends
+///
+/// # Errors
+/// If the maze has no start or end, or no path between them
+pub fn to_svg(input: &Vec2d<u8>) -> Result<String> {
+    let distances = path_distances(input)?;
+    let mut path = distances.iter_with_points()
+        .filter_map(|(point, &cost)| Some((cost?, point)))
+        .collect::<Vec<_>>();
+    path.sort_unstable_by_key(|&(cost, _)| cost);
+    let path = path.into_iter().map(|(_, point)| point).collect::<Vec<_>>();
+
+    let mut svg = Svg::new(input.line_len, input.height());
+    svg.region(input.find_all(&b'#'), "#333");
+    svg.path(&path, "royalblue");
+    find_cheats(&distances, 2, MIN_SAVING, |from, to, _| svg.path(&[from, to], "crimson"));
+    Ok(svg.to_string())
 }
 
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
+/// How far along the path from the start each point is, `None` for walls
+fn path_distances(input: &Vec2d<u8>) -> Result<Vec2d<Option<i32>>> {
+    let start = input.find(&b'S').ok_or_else(|| AocError::parse("no start S in the maze"))?;
+    let end = input.find(&b'E').ok_or_else(|| AocError::parse("no end E in the maze"))?;
+    let mut distances = Vec2d {
+        grid: vec![None; input.grid.len()],
+        line_len: input.line_len,
+    };
+    let open_neighbors = |&point: &Point| point.neighbors4().filter(|&next| input.get(next).is_some_and(|&c| c != b'#'));
+    for (point, depth) in bfs(start, open_neighbors) {
+        distances[point] = Some(i32::try_from(depth).map_err(|_| AocError::parse("the maze is too large"))?);
     }
+    if distances[end].is_none() {
+        return Err(AocError::no_solution("no path from S to E"));
+    }
+    Ok(distances)
 }
 
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+fn parse_input(input: &str) -> Result<Vec2d<u8>> {
+    Vec2d::from_bytes(input)
 }
 
-/// This function returns a dijkstra map of distances from the end point to all other maze points.
-/// This is a useful way to memoize the distances from any point in the maze to the end
-fn dijkstra_map(end: Point, grid: &Vec2d<char>) -> Vec<i32> {
-    let mut distances = vec![i32::MAX; grid.grid.len()];
-    distances[grid.point_to_idx(end)] = 0;
-    let mut queue = BinaryHeap::new();
-    queue.push(Node { position: end, cost: 0 });
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::example;
+
+    #[test]
+    fn test_part_1() {
+        // A single winding corridor, short enough to count the cheats by hand
+        let input = parse_input(&example(20, 2)).unwrap();
+        // Cheating through either wall in the middle row saves 2,
+        // and through the wall next to S or the wall in the bottom row saves 4
+        assert_eq!(4, count_cheats(&input, 2, 1).unwrap());
+        assert_eq!(2, count_cheats(&input, 2, 3).unwrap());
+        assert_eq!(0, count_cheats(&input, 2, 5).unwrap());
+    }
 
-    while let Some(current) = queue.pop() {
-        if current.cost > distances[grid.point_to_idx(current.position)] {
-            continue;
-        }
-        [Directions::Up, Directions::Down, Directions::Left, Directions::Right].into_iter()
-            .filter_map(|direction| grid.next_point(current.position, direction))
-            .filter(|&next_pos| grid[next_pos] != '#')
-            .for_each(|next_pos| {
-                let next_idx = grid.point_to_idx(next_pos);
-                if current.cost + 1 < distances[next_idx] {
-                    let next = Node { cost: current.cost + 1, position: next_pos };
-                    distances[grid.point_to_idx(next_pos)] = next.cost;
-                    queue.push(next);
-                }
-            });
+    #[test]
+    fn test_part_2() {
+        let input = parse_input(&example(20, 2)).unwrap();
+        assert_eq!(26, count_cheats(&input, 20, 1).unwrap());
+        assert_eq!(15, count_cheats(&input, 20, 4).unwrap());
+    }
+
+    #[test]
+    fn test_example() {
+        // The counts for each amount of time saved listed in the puzzle text
+        let input = parse_input(&example(20, 1)).unwrap();
+        assert_eq!(44, count_cheats(&input, 2, 1).unwrap());
+        assert_eq!(5, count_cheats(&input, 2, 20).unwrap());
+        assert_eq!(1, count_cheats(&input, 2, 64).unwrap());
+        assert_eq!(0, count_cheats(&input, 2, 65).unwrap());
+        assert_eq!(285, count_cheats(&input, 20, 50).unwrap());
+        assert_eq!(29, count_cheats(&input, 20, 72).unwrap());
+        assert_eq!(3, count_cheats(&input, 20, 76).unwrap());
+        let context = Context::default().with_param("min_saving", 50);
+        assert_eq!(Answer::Int(1), Day20::part1_with(&input, &context).unwrap());
+        assert_eq!(Answer::Int(285), Day20::part2_with(&input, &context).unwrap());
+        assert_eq!(0, count_cheats(&input, 20, 77).unwrap());
+    }
+
+    #[test]
+    fn test_cheat_savings() {
+        let input = parse_input(&example(20, 1)).unwrap();
+        let savings = cheat_savings(&input, 2).unwrap();
+        let expected = BTreeMap::from([(2, 14), (4, 14), (6, 2), (8, 4), (10, 2), (12, 3), (20, 1), (36, 1), (38, 1), (40, 1), (64, 1)]);
+        assert_eq!(expected, savings);
+
+        // The puzzle text only lists the cheats that save at least 50 moves for part 2
+        let savings = cheat_savings(&input, 20).unwrap();
+        let expected = [(50, 32), (52, 31), (54, 29), (56, 39), (58, 25), (60, 23), (62, 20), (64, 19), (66, 12), (68, 14), (70, 12), (72, 22), (74, 4), (76, 3)];
+        assert_eq!(expected.to_vec(), savings.range(50 ..).map(|(&saving, &count)| (saving, count)).collect::<Vec<_>>());
+        assert_eq!(count_cheats(&input, 20, 1).unwrap(), savings.values().sum());
     }
-    distances
-}
 
-fn parse_input(input: &str) -> Vec2d<char> {
-    let chars = input.lines()
-        .flat_map(|line| line.trim().chars().collect::<Vec<_>>())
-        .collect();
-    let line_len = input.lines().next().unwrap().len();
-    Vec2d {
-        grid: chars,
-        line_len: line_len as i32,
+    #[test]
+    fn test_to_svg() {
+        let input = parse_input(&example(20, 2)).unwrap();
+        let svg = to_svg(&input).unwrap();
+        // the cheats here save at most 4 moves, so only the path is drawn
+        assert_eq!(1, svg.matches("<polyline").count());
+        assert!(svg.contains("points=\"15,15 15,25 15,35 25,35 35,35 35,25 35,15 45,15 55,15 55,25 55,35 55,45\""));
     }
 }
@@ -1,12 +1,13 @@
-use std::collections::{BinaryHeap, HashMap};
-use std::fs;
-use super::Day;
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
 use crate::util::grid::prelude::*;
+use crate::util::hash::{FxHashMap, FxHashSet};
+use tracing::{debug, trace};
 
 /// Day 21: Keypad Conundrum
 /// 
 /// A keypad has 10 possible digits layed out as follows:
-/// ```
+/// ```text
 /// 7 8 9
 /// 4 5 6
 /// 1 2 3
@@ -14,7 +15,7 @@ use crate::util::grid::prelude::*;
 /// ```
 /// A robot is necessary to press the buttons. This robot has a control
 /// pad that moves its robotic arm. The control pad looks as follows:
-/// ```
+/// ```text
 ///   ^ A
 /// < v >
 /// ```
@@ -45,269 +46,253 @@ use crate::util::grid::prelude::*;
 pub struct Day21;
 
 impl Day<Vec<String>> for Day21 {
-    fn read_input() -> Vec<String> {
-        let input = fs::read_to_string("resources/day21.txt").expect("file day21.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec<String>> {
+        parse_input(input)
     }
 
     // We'll sovle part 1 and part 2 in the same general way.
-    fn part1(input: &Vec<String>) -> impl std::fmt::Display {
-        solve_for_robot_chain(2, input)
+    fn part1(input: &Vec<String>) -> Result<impl IntoAnswer> {
+        let (numeric, directional) = (Keypad::numeric(), Keypad::directional());
+        solve(input, &robot_chain(&numeric, &directional, 2))
     }
 
-    fn part2(input: &Vec<String>) -> impl std::fmt::Display {
-        solve_for_robot_chain(25, input)
+    fn part2(input: &Vec<String>) -> Result<impl IntoAnswer> {
+        let (numeric, directional) = (Keypad::numeric(), Keypad::directional());
+        solve(input, &robot_chain(&numeric, &directional, 25))
     }
 }
 
-// function to set up the robot chains and calculate the final result
-fn solve_for_robot_chain(length: i32, input: &[String]) -> i64 {
-    // There are actually only 2 "robot" objects that will be borrowed by all the robot chains
-    // the keypad robot, and the directional robot, are built here
-    let numeric_keypad = Vec2d {
-        grid: vec!['7','8','9','4','5','6', '1', '2', '3', 'X', '0', 'A'],
-        line_len: 3,
-    };
-    let direction_keypad = Vec2d {
-        grid: vec!['X','^','A','<','v','>'],
-        line_len: 3,
-    };
-    let mut key_robot = Robot::new(numeric_keypad);
-    let mut direction_robot = Robot::new(direction_keypad);
-    key_robot.load_all_keys();
-    direction_robot.load_all_keys();
+/// The numeric keypad on the door, see [`Keypad::from_layout`]
+pub const NUMERIC_LAYOUT: &str = "789\n456\n123\n.0A";
+/// The directional keypad used by every robot, and by you
+pub const DIRECTIONAL_LAYOUT: &str = ".^A\n<v>";
 
-    // Build the robot chain including [`length`] nested directional robots
-    let mut parent =  RobotState {
-        robot: &direction_robot,
-        current_pos: direction_robot.find_key_pos('A'),
-        level: 0,
-        parent: Box::new(None),
-    };
-    for level in 1 ..= length {
-        parent = RobotState {
-            robot: &direction_robot,
-            current_pos: direction_robot.find_key_pos('A'),
-            level,
-            parent: Box::new(Some(parent.clone())),
-        };
-    }
-    let key_state = RobotState {
-        robot: &key_robot,
-        current_pos: key_robot.find_key_pos('A'),
-        level: length + 1,
-        parent: Box::new(Some(parent)),
-    };
-
-    // loop through and calculate the button presses needed for each code
-    let mut memo = HashMap::new();
-    input.iter().map(|code| {
-        let mut state = key_state.clone();
-        let mut num_steps = 0;
-        for next_digit in code.chars() {
-            let (updated_state, cost) = path_cost(state, next_digit, &mut memo);
-            num_steps += cost;
-            state = updated_state;
-        }
-        let code_num = &code[..code.len() - 1].parse().unwrap();
-        num_steps * code_num
-    })
-    .sum::<i64>()
-}
+/// The cost (in presses on your own keypad) for the robot using a keypad
+/// to move its arm from one key to another and then press it
+pub type CostTable = FxHashMap<(char, char), i64>;
 
-/// The robot holds the basic behavior of our two types of robots.
-/// Use a [`Vec2d`] to represent the keypad. Create a map to remember
-/// the [`Point`] positions of each key, since we'll be looking those up frequently.
-/// Note: this is not intended to be cloned, and deliberately does not implement it.
-struct Robot {
-    keypad: Vec2d<char>,
-    key_positions: HashMap<char, Point>,
+/// A keypad, with `None` for the empty space the arm can never move over
+#[derive(Clone)]
+pub struct Keypad {
+    keys: Vec2d<Option<char>>,
 }
 
-impl Robot {
-    fn new(keypad: Vec2d<char>) -> Self {
-        Self {
-            keypad,
-            key_positions: HashMap::new(),
+impl Keypad {
+    /// Read a keypad with one row per line, and `.` for the empty space, ex: [`NUMERIC_LAYOUT`].
+    /// Every arm starts at `A`, so the keypad needs an `A` key.
+    ///
+    /// # Errors
+    /// If the rows are not all the same length, a key is on the keypad twice, or there is no `A` key
+    pub fn from_layout(layout: &str) -> Result<Self> {
+        let keys = Vec2d::parse_with(layout, |c| Ok((c != '.').then_some(c)))?;
+        let mut seen = FxHashSet::default();
+        if let Some(key) = keys.grid.iter().flatten().find(|&&key| !seen.insert(key)) {
+            return Err(AocError::parse(format!("key {key} is on the keypad twice")));
+        }
+        if !seen.contains(&'A') {
+            return Err(AocError::parse("the keypad has no A key"));
         }
+        Ok(Self { keys })
     }
 
-    fn find_key_pos(&self, key: char) -> Point {
-        if self.key_positions.contains_key(&key) {
-            return self.key_positions[&key];
-        }
-        self.keypad.find(&key).expect("Not able to find key in keypad")
+    /// The keypad from [`NUMERIC_LAYOUT`]
+    ///
+    /// # Panics
+    /// Never, the layout is valid
+    #[must_use]
+    pub fn numeric() -> Self {
+        Self::from_layout(NUMERIC_LAYOUT).expect("valid numeric keypad")
     }
 
-    fn load_all_keys(&mut self) {
-        for &key in &self.keypad.grid {
-            let pos = self.find_key_pos(key);
-            self.key_positions.insert(key, pos);
-        }
+    /// The keypad from [`DIRECTIONAL_LAYOUT`]
+    ///
+    /// # Panics
+    /// Never, the layout is valid
+    #[must_use]
+    pub fn directional() -> Self {
+        Self::from_layout(DIRECTIONAL_LAYOUT).expect("valid directional keypad")
     }
-}
 
-/// `RobotState` is a lightweight representation of where each robotic arm is at any given time.
-/// This class is designed to be cloned and duplicated without bloating memory by only
-/// holding onto a borrow of the [`Robot`]. So all N directional `RobotState` objects
-/// hold the borrow to the same underlying [`Robot`]. We must specify a lifetime for the borrow.
-#[derive(Clone)]
-struct RobotState<'a> {
-    robot: &'a Robot,
-    current_pos: Point,
-    level: i32,
-    parent: Box<Option<RobotState<'a>>>,
-}
+    /// Every key, along with where it is on the keypad
+    fn keys(&self) -> impl Iterator<Item = (Point, char)> + '_ {
+        self.keys.iter_with_points().filter_map(|(point, key)| key.map(|key| (point, key)))
+    }
 
-impl <'a> RobotState<'a> {
-    // Clone but replace the parent reference with a new reference
-    fn replace_parent(&self, new_parent: RobotState<'a>) -> Self {
-        Self {
-            robot: self.robot,
-            current_pos: self.current_pos,
-            level: self.level,
-            parent: Box::new(Some(new_parent)),
+    /// The cost table for a robot using this keypad, when its arrow keys are pressed at the cost of `controller`.
+    ///
+    /// The arm always starts at 'A' on the controlling keypad, and ends there after pressing 'A'.
+    /// That makes every move between two keys independent of the moves before it.
+    /// The best way to move is always all horizontal then all vertical, or the other way around:
+    /// repeating the same arrow is cheapest, as the controlling arm only has to press 'A' again.
+    /// The only catch is that neither way is allowed if the corner is the empty space.
+    ///
+    /// # Errors
+    /// If `controller` is missing an arrow key needed to move the arm, or two keys
+    /// can't be reached from each other with at most one turn
+    pub fn costs(&self, controller: &CostTable) -> Result<CostTable> {
+        let mut costs = FxHashMap::default();
+        for (from_pos, from) in self.keys() {
+            for (to_pos, to) in self.keys() {
+                let diff = to_pos - from_pos;
+                let horizontal = if diff.x < 0 { Directions::Left } else { Directions::Right };
+                let vertical = if diff.y < 0 { Directions::Up } else { Directions::Down };
+                let arrows = |direction: Directions, count: i32| {
+                    std::iter::repeat_n(direction.to_char().unwrap_or('A'), count.unsigned_abs() as usize)
+                };
+                let mut options = Vec::new();
+                if self.keys[Point::new(to_pos.x, from_pos.y)].is_some() {
+                    options.push(arrows(horizontal, diff.x).chain(arrows(vertical, diff.y)).collect::<Vec<_>>());
+                }
+                if self.keys[Point::new(from_pos.x, to_pos.y)].is_some() {
+                    options.push(arrows(vertical, diff.y).chain(arrows(horizontal, diff.x)).collect());
+                }
+                if options.is_empty() {
+                    return Err(AocError::no_solution(format!("both corners between {from} and {to} are empty")));
+                }
+                let best = options.into_iter()
+                    .map(|moves| sequence_cost(moves.into_iter().chain(['A']), controller))
+                    .min()
+                    .flatten()
+                    .ok_or_else(|| AocError::no_solution(format!("the controlling keypad can't move from {from} to {to}")))?;
+                trace!(%from, %to, cost = best, "move cost");
+                costs.insert((from, to), best);
+            }
         }
+        Ok(costs)
     }
 }
 
-/// We need equals and hash, but we don't want to be comparing [`Robot`] structs, which
-/// contain the keypad vector. Use level as a proxy for robot type.
-impl <'a> PartialEq for RobotState<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        self.current_pos == other.current_pos && self.level == other.level && self.parent == other.parent
-    }
+/// The puzzle's chain of keypads: the numeric keypad, operated by a robot using the first of `robots`
+/// directional keypads (each operated by the robot using the next one), with you on the last directional keypad
+#[must_use]
+pub fn robot_chain<'k>(numeric: &'k Keypad, directional: &'k Keypad, robots: usize) -> Vec<&'k Keypad> {
+    std::iter::once(numeric)
+        .chain(std::iter::repeat_n(directional, robots + 1))
+        .collect()
 }
 
-impl <'a> Eq for RobotState<'a> {}
-
-impl <'a> std::hash::Hash for RobotState<'a> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.current_pos.hash(state);
-        self.level.hash(state);
-        self.parent.hash(state);
+/// The cost table for the first keypad in `chain`. Each keypad is operated by a robot using the
+/// keypad after it, except the last one, where you press the keys yourself.
+///
+/// Builds the cost tables one level at a time, starting from your own keypad, where every press costs 1.
+///
+/// # Errors
+/// If the chain is empty, or a keypad can't control the one before it (see [`Keypad::costs`])
+pub fn chain_costs(chain: &[&Keypad]) -> Result<CostTable> {
+    let (yours, robots) = chain.split_last().ok_or_else(|| AocError::no_solution("no keypads"))?;
+    let mut costs: CostTable = yours.keys()
+        .flat_map(|(_, from)| yours.keys().map(move |(_, to)| ((from, to), 1)))
+        .collect();
+    for keypad in robots.iter().rev() {
+        costs = keypad.costs(&costs)?;
     }
+    Ok(costs)
 }
 
-/// Make a node class for the min priority queue needed for pathing
-#[derive(PartialEq, Eq)]
-struct Node<'a> {
-    state: RobotState<'a>,
-    cost: i64,
+/// The fewest presses on your keypad to type `code` on the first keypad in `chain` (see [`chain_costs`])
+///
+/// # Errors
+/// If the chain doesn't work, or the code has a key that isn't on the first keypad
+pub fn presses(code: &str, chain: &[&Keypad]) -> Result<i64> {
+    code_presses(code, &chain_costs(chain)?)
 }
 
-impl <'a> Ord for Node<'a> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.cost.cmp(&self.cost)
-    }
+/// Sum the complexity of each code: the fewest presses to type it through `chain`
+/// (see [`chain_costs`]), multiplied by the numeric part of the code
+///
+/// # Errors
+/// If the chain doesn't work, or a code has a key that isn't on the first keypad, or isn't a number followed by `A`
+pub fn solve(codes: &[String], chain: &[&Keypad]) -> Result<i64> {
+    let costs = chain_costs(chain)?;
+    codes.iter().map(|code| code_complexity(code, &costs)).sum()
 }
 
-impl <'a> PartialOrd for Node<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+/// The complexity of one code, typed on the numeric keypad through `robots` robots on directional keypads
+/// (see [`robot_chain`]). Part 1 has 2 robots, and part 2 has 25.
+///
+/// # Errors
+/// If the code has a key that isn't on the numeric keypad, or isn't a number followed by `A`
+pub fn complexity(code: &str, robots: usize) -> Result<i64> {
+    let (numeric, directional) = (Keypad::numeric(), Keypad::directional());
+    code_complexity(code, &chain_costs(&robot_chain(&numeric, &directional, robots))?)
 }
 
-type MemoKey<'a> = (RobotState<'a>, char);
-type MemoVal<'a> = (RobotState<'a>, i64);
-
-/// This is where all the logic lives. Use a depth first recursion with memoization
-/// dynamic programming algorithm to solve the N nested layers of robots.
-/// 
-/// When you have a Dijkstra's algorithm, everything looks like a pathing problem.
-/// There are probably better approaches, but this one came to mind for me.
-/// 
-/// * To find the path from one digit to the next, use A* pathing (with manhattan distance as `h()`).
-/// * The cost of moving to the next adjacent key is determined by the cost of the parent robot.
-/// * This is where the recursion comes in, until we reach the top most level where the cost is 1.
-/// * Memoization is essential to prevent duplicate subproblems. Robot states frequently repeat.
-fn path_cost<'a>(
-    robot_state: RobotState<'a>,
-    destination: char,
-    memo: &mut HashMap<MemoKey<'a>, MemoVal<'a>>
-) -> (RobotState<'a>, i64) {
-    if let Some(result) = memo.get(&(robot_state.clone(), destination)) {
-        return result.clone();
-    };
-    if robot_state.parent.is_none() {
-        // At the top level, it takes no additiona effort to press the desired button
-        return (robot_state, 1);
-    };
-
-    let end = robot_state.robot.find_key_pos(destination);
-    let mut queue = BinaryHeap::new();
-    queue.push(Node { state: robot_state.clone(), cost: 0 });
+fn code_complexity(code: &str, costs: &CostTable) -> Result<i64> {
+    let num_steps = code_presses(code, costs)?;
+    let code_num = code.strip_suffix('A')
+        .ok_or_else(|| AocError::parse(format!("code {code} doesn't end with A")))?
+        .parse::<i64>()?;
+    debug!(code, num_steps, "typed code");
+    Ok(num_steps * code_num)
+}
 
-    // Unlike Dijkstra, we don't need to keep a map of distances
-    // But unlike traditional A*, we don't actually need the path, just the total cost
-    let mut best_solution = (robot_state.clone(), i64::MAX);
+fn code_presses(code: &str, costs: &CostTable) -> Result<i64> {
+    sequence_cost(code.chars(), costs)
+        .ok_or_else(|| AocError::no_solution(format!("code {code} has a key that isn't on the keypad")))
+}
 
-    while let Some(current) = queue.pop() {
-        let position = current.state.current_pos;
-        let parent = current.state.parent.clone().unwrap();
-        if current.cost + i64::from(position.manhattan_distance(&end)) > best_solution.1 {
-            continue;
-        }
-        if position == end {
-            // We've found a path to the destination
-            // we don't stop, because we still have to press 'A' on parent, and a different path
-            // might give us a more efficient parent cost for pressing 'A'
-            let (update_parent, cost) = path_cost(parent, 'A', memo);
-            let updated_state = current.state.replace_parent(update_parent);
-            let final_cost = cost + current.cost;
-            if final_cost < best_solution.1 {
-                best_solution = (updated_state, final_cost);
-            }
-            continue;
-        }
-        for direction in [Directions::Up, Directions::Down, Directions::Left, Directions::Right] {
-            let Some(next_pos) = current.state.robot.keypad.next_point(position, direction) else {
-                continue;
-            };
-            if current.state.robot.keypad[next_pos] == 'X' {
-                continue;
-            };
-            let parent_key = match direction {
-                Directions::Up => '^',
-                Directions::Down => 'v',
-                Directions::Left => '<',
-                Directions::Right => '>',
-                _ => panic!("Invalid direction"),
-            };
-            let (updated_parent, parent_cost) =  path_cost(parent.clone(), parent_key, memo);
-            let new_cost = current.cost + parent_cost;
-            let h = new_cost + i64::from(next_pos.manhattan_distance(&end));
-            if h <= best_solution.1 {
-                let mut state = current.state.clone();
-                state.current_pos = next_pos;
-                state = state.replace_parent(updated_parent);
-                let node = Node { cost: new_cost, state };
-                queue.push(node);
-            }
-        }
-    }
-    memo.insert((robot_state, destination), best_solution.clone());
-    best_solution
+/// The cost of pressing each key in order starting with the arm at 'A', or `None` if a key isn't in `costs`
+fn sequence_cost(keys: impl IntoIterator<Item = char>, costs: &CostTable) -> Option<i64> {
+    keys.into_iter()
+        .try_fold(('A', 0), |(from, total), to| Some((to, total + costs.get(&(from, to))?)))
+        .map(|(_, total)| total)
 }
 
-fn parse_input(input: &str) -> Vec<String> {
-    input.lines().map(ToString::to_string).collect()
+fn parse_input(input: &str) -> Result<Vec<String>> {
+    input.lines()
+        .map(|code| match code.strip_suffix('A') {
+            Some(digits) if digits.parse::<i64>().is_ok() => Ok(code.to_string()),
+            _ => Err(AocError::parse(format!("invalid code {code}"))),
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+
+    aoc_test!(day21, part1, example 1, "126384");
+    aoc_test!(day21, part2, example 1, "154115708116294");
 
-    const TEST: &str = "029A
-980A
-179A
-456A
-379A";
+    #[test]
+    fn test_presses() {
+        // The lengths of the sequences in the puzzle text for 029A, with 0 to 2 robots on directional keypads
+        let (numeric, directional) = (Keypad::numeric(), Keypad::directional());
+        assert_eq!(4, presses("029A", &[&numeric]).unwrap());
+        assert_eq!(12, presses("029A", &[&numeric, &directional]).unwrap());
+        assert_eq!(28, presses("029A", &[&numeric, &directional, &directional]).unwrap());
+        assert_eq!(68, presses("029A", &robot_chain(&numeric, &directional, 2)).unwrap());
+        let lengths = ["980A", "179A", "456A", "379A"].map(|code| presses(code, &robot_chain(&numeric, &directional, 2)).unwrap());
+        assert_eq!([60, 68, 64, 64], lengths);
+        assert!(presses("02BA", &[&numeric, &directional]).is_err());
+    }
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day21::part1(&input);
-        assert_eq!("126384", result.to_string())
+    fn test_complexity() {
+        assert_eq!(68 * 29, complexity("029A", 2).unwrap());
+        assert_eq!(64 * 379, complexity("379A", 2).unwrap());
+        assert_eq!(12 * 29, complexity("029A", 0).unwrap());
+        // Regression values for the example codes at part 2 scale, which sum to the part 2 answer
+        let codes = ["029A", "980A", "179A", "456A", "379A"];
+        let complexities = codes.map(|code| complexity(code, 25).unwrap());
+        assert_eq!([2_379_451_789_590, 70_797_185_862_200, 14_543_936_021_812, 36_838_581_189_648, 29_556_553_253_044], complexities);
+        assert_eq!(154_115_708_116_294, complexities.iter().sum::<i64>());
+        assert!(complexity("029", 2).is_err());
+    }
+
+    #[test]
+    fn test_layouts() {
+        assert!(Keypad::from_layout("12\n3A\n4").is_err());
+        assert!(Keypad::from_layout("1A\n1.").is_err());
+        assert!(Keypad::from_layout("12\n3.").is_err());
+
+        // A single row of keys only needs left and right
+        let row = Keypad::from_layout("0123456789A").unwrap();
+        let arrows = Keypad::from_layout("<A>").unwrap();
+        assert_eq!(11 + 3 + 8 + 2, presses("029A", &[&row, &arrows]).unwrap());
+        assert_eq!(24 * 29, solve(&["029A".to_string()], &[&row, &Keypad::directional()]).unwrap());
+        assert!(chain_costs(&[&Keypad::numeric(), &arrows]).is_err());
+        assert!(chain_costs(&[]).is_err());
     }
 }
@@ -1,5 +1,7 @@
-use super::Day;
-use std::{collections::HashMap, fs};
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::parse::parse_lines;
+use rayon::prelude::*;
 
 /// Day 22: Monkey Market
 /// 
@@ -15,7 +17,7 @@ use std::{collections::HashMap, fs};
 /// use the initial secret + the next 2000 secret numbers. The monkey will sell
 /// when they see a 4 digit sequence that matches the change in price values for
 /// the last 4 secrets. Example: with secret | price | change
-/// ```
+/// ```text
 ///      123: 3 
 /// 15887950: 0 (-3)
 /// 16495136: 6 (6)
@@ -35,95 +37,166 @@ use std::{collections::HashMap, fs};
 pub struct Day22;
 
 impl Day<Vec<i64>> for Day22 {
-    fn read_input() -> Vec<i64> {
-        fs::read_to_string("resources/day22.txt").expect("file day22.txt not found")
-            .lines()
-            .map(|s| s.parse().unwrap())
-            .collect()
+    fn parse(input: &str) -> Result<Vec<i64>> {
+        let secrets = parse_lines(input)?;
+        match secrets.iter().find(|secret| !(0 .. TRUNC).contains(*secret)) {
+            Some(secret) => Err(AocError::parse(format!("secret {secret} is not between 0 and {TRUNC}"))),
+            None => Ok(secrets),
+        }
     }
 
-    fn part1(input: &Vec<i64>) -> impl std::fmt::Display {
-        input.iter()
+    fn part1(input: &Vec<i64>) -> Result<impl IntoAnswer> {
+        Ok(input.iter()
             .map(|&initial_secret| 
                 // run next secret 2000 times on the previous value
                 (0..2000).fold(initial_secret, |secret, _| next_secret(secret))
             )
-            .sum::<i64>()
+            .sum::<i64>())
     }
 
-    // A little slow at 0.5 seconds on release mode, but not too bad.
-    fn part2(input: &Vec<i64>) -> impl std::fmt::Display {
+    fn part2(input: &Vec<i64>) -> Result<impl IntoAnswer> {
+        Ok(sequence_totals(input)?.into_iter().max().unwrap_or_default())
+    }
+}
 
-        // First make a map of the change sequence to the banana price for each monkey
-        let price_maps = input.iter()
-            .map(|&secret| build_price_map(secret))
-            .collect::<Vec<_>>();
+/// Each price change is between -9 and 9, so a sequence of 4 changes is a 4 digit base 19 number
+const SEQUENCES: usize = 19 * 19 * 19 * 19;
 
-        // Once per price_map, add the price each sequence will fetch
-        let mut sequence_counts = HashMap::new();
-        for price_map in &price_maps {
-            for key in price_map.keys() {
-                *sequence_counts.entry(*key).or_insert(0) += price_map[key];
-            }
-        }
-        let most_bananas = sequence_counts.values().max().unwrap();
-        *most_bananas
+/// The sequence of price changes that sells the most bananas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BestSequence {
+    /// The 4 price changes, oldest first
+    pub changes: [i32; 4],
+    /// The bananas sold by every monkey together
+    pub bananas: i32,
+    /// The bananas sold by each monkey, in the same order as the secrets. 0 if the sequence never appears.
+    pub per_monkey: Vec<i32>,
+}
+
+/// Find the sequence of price changes that sells the most bananas (the first one, if there is a tie),
+/// and what each monkey sells for it
+///
+/// # Errors
+/// If no monkey has a sequence of 4 price changes
+pub fn best_sequence(secrets: &[i64]) -> Result<BestSequence> {
+    let totals = sequence_totals(secrets)?;
+    let bananas = totals.iter().copied().max().unwrap_or_default();
+    let sequence = totals.iter().position(|&total| total == bananas).unwrap_or_default();
+    let per_monkey = secrets.par_iter()
+        .map(|&secret| prices(secret).find(|&(seen, _)| seen == sequence).map_or(0, |(_, price)| price))
+        .collect();
+    let mut changes = [0; 4];
+    let mut digits = sequence;
+    for change in changes.iter_mut().rev() {
+        // each base 19 digit is a change from -9 to 9
+        *change = (-9 ..= 9).nth(digits % 19).unwrap_or_default();
+        digits /= 19;
+    }
+    Ok(BestSequence { changes, bananas, per_monkey })
+}
+
+/// The bananas sold for each sequence of price changes, totalled over all the monkeys.
+///
+/// Each monkey is independent, so the prices are added up in parallel.
+/// Every thread totals the monkeys it handled into its own array, then the arrays are added together.
+fn sequence_totals(secrets: &[i64]) -> Result<Vec<i32>> {
+    // every monkey sees 2000 price changes, so only an empty market has no sequences
+    if secrets.is_empty() {
+        return Err(AocError::no_solution("no monkey has a 4 price change sequence"));
     }
+    Ok(secrets.par_iter()
+        .fold(
+            || (vec![0; SEQUENCES], vec![0_u64; SEQUENCES.div_ceil(64)]),
+            |(mut sequence_counts, mut seen), &secret| {
+                seen.fill(0);
+                add_prices(secret, &mut sequence_counts, &mut seen);
+                (sequence_counts, seen)
+            },
+        )
+        .map(|(sequence_counts, _)| sequence_counts)
+        .reduce(|| vec![0; SEQUENCES], |mut totals, counts| {
+            for (total, count) in totals.iter_mut().zip(counts) {
+                *total += count;
+            }
+            totals
+        }))
 }
 
+/// Every secret is below this, so the math in [`next_secret`] can't overflow
+const TRUNC: i64 = 16_777_216;
+
 fn next_secret(secret: i64) -> i64 {
-    const TRUNC: i64 = 16_777_216;
     let step1 = ((secret * 64) ^ secret) % TRUNC;
     let step2 = ((step1 / 32) ^ step1) % TRUNC;
     ((step2 * 2048) ^ step2) % TRUNC
 }
 
-fn build_price_map(secret: i64) -> HashMap<[i32; 4], i32> {
-    let mut prices = Vec::new();
-    let last_digit: fn(i64) -> i32 = |s| (s % 10).try_into().unwrap();
+/// Add the price the monkey sells at for each change sequence to `sequence_counts`.
+/// The monkey sells the first time the sequence appears, so `seen` tracks the sequences already counted.
+fn add_prices(secret: i64, sequence_counts: &mut [i32], seen: &mut [u64]) {
+    for (sequence, price) in prices(secret) {
+        if seen[sequence / 64] & (1 << (sequence % 64)) == 0 {
+            seen[sequence / 64] |= 1 << (sequence % 64);
+            sequence_counts[sequence] += price;
+        }
+    }
+}
 
-    // Add 2000 new prices in addition to the first price
-    prices.push((last_digit(secret), 0));
+/// Each price after the first 4 changes (of the next 2000 secrets), along with the
+/// sequence of the last 4 changes that lead to it, as a base 19 number (see [`SEQUENCES`])
+fn prices(secret: i64) -> impl Iterator<Item = (usize, i32)> {
+    let last_digit: fn(i64) -> i32 = |s| (s % 10).try_into().unwrap();
+    let mut last_price = last_digit(secret);
+    let mut sequence = 0;
     let mut current_secret = secret;
-    for _ in 0..2000 {
+    (0 .. 2000).filter_map(move |i| {
         current_secret = next_secret(current_secret);
-        let current_price = last_digit(current_secret);
-        let last_price = prices.last().unwrap().0;
-        prices.push((current_price, current_price - last_price));
-    }
-    
-    let mut price_map= HashMap::new();
-    for i in 4 .. prices.len() {
-        let change_seq: [i32; 4] = [prices[i-3].1, prices[i-2].1, prices[i-1].1, prices[i].1];
-        let price = prices[i].0;
-        
-        // the first time the sequence appears is the price for that sequence
-        price_map.entry(change_seq).or_insert(price);
-    }
-    price_map
+        let price = last_digit(current_secret);
+        // shift the oldest change out of the sequence and the newest one in
+        let change = usize::try_from(price - last_price + 9).unwrap();
+        sequence = (sequence * 19 + change) % SEQUENCES;
+        last_price = price;
+        (i >= 3).then_some((sequence, price))
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
 
     #[test]
     fn test_next_secret() {
-        assert_eq!(15887950, next_secret(123));
-        assert_eq!(16495136, next_secret(15887950));
-        assert_eq!(527345, next_secret(16495136));
+        assert_eq!(15_887_950, next_secret(123));
+        assert_eq!(16_495_136, next_secret(15_887_950));
+        assert_eq!(527_345, next_secret(16_495_136));
+    }
+
+    aoc_test!(day22, part1, example 1, "37327623");
+    aoc_test!(day22, part2, example 2, "23");
+
+    #[test]
+    fn test_best_sequence() {
+        let input = Day22::parse(&example(22, 2)).unwrap();
+        let best = best_sequence(&input).unwrap();
+        assert_eq!([-2, 1, -1, 3], best.changes);
+        assert_eq!(23, best.bananas);
+        // the monkey starting at 3 never sees the sequence
+        assert_eq!(vec![7, 7, 0, 9], best.per_monkey);
     }
 
     #[test]
-    fn test_part_1() {
-        let input = vec![1, 10, 100, 2024];
-        assert_eq!("37327623", Day22::part1(&input).to_string());
+    fn test_invalid_secret() {
+        assert!(matches!(Day22::parse("1\n16777216"), Err(AocError::Parse(_))));
+        assert!(matches!(Day22::parse("-1"), Err(AocError::Parse(_))));
+        assert_eq!(vec![16_777_215], Day22::parse("16777215").unwrap());
     }
 
     #[test]
-    fn test_part_2() {
-        let input = vec![1, 2, 3, 2024];
-        assert_eq!("23", Day22::part2(&input).to_string());
+    fn test_no_monkeys() {
+        assert!(matches!(best_sequence(&[]), Err(AocError::NoSolution(_))));
+        assert!(matches!(Day22::part2(&vec![]), Err(AocError::NoSolution(_))));
     }
 }
 
@@ -1,6 +1,11 @@
-use super::Day;
-use std::fs;
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::parse::{key_value, two_sections};
+use crate::util::precedence::Precedence;
+use crate::util::random::Rng;
 use std::collections::HashMap;
+use std::fmt::Write;
+use tracing::debug;
 
 /// Day 24: Crossed Wires
 /// 
@@ -22,7 +27,7 @@ use std::collections::HashMap;
 /// comma separated string.
 pub struct Day24;
 
-type Input = (HashMap<String, bool>, Vec<Gate>);
+pub type Input = (HashMap<String, bool>, Vec<Gate>);
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Gate {
@@ -39,114 +44,321 @@ pub enum Operation {
     Xor,
 }
 
+impl Operation {
+    fn apply(self, lhs: bool, rhs: bool) -> bool {
+        match self {
+            Operation::And => lhs && rhs,
+            Operation::Or => lhs || rhs,
+            Operation::Xor => lhs != rhs,
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Operation::And => "AND",
+            Operation::Or => "OR",
+            Operation::Xor => "XOR",
+        };
+        write!(f, "{name}")
+    }
+}
+
 impl Day<Input> for Day24 {
-    fn read_input() -> Input {
-        let input = fs::read_to_string("resources/day24.txt").expect("file day24.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Input> {
+        parse_input(input)
     }
 
-    fn part1(input: &Input) -> impl std::fmt::Display {
+    fn part1(input: &Input) -> Result<impl IntoAnswer> {
         let (wires, gates) = input;
-        // run_gates wants a vector that doesn't own the Gate objects. This is important for part 2.
-        // So here we need to quickly convert gates to be a Vec<&Gate> instead of Vec<Gate>
-        let output = run_gates(wires, &gates.iter().collect());
+        let output = run_gates(wires, gates)?;
         binary_num('z', &output)
     }
 
-    /// Solved via pen/paper and debugging. The code below verifies this solution and shows
-    /// some of the debugging code used to find it. The process was something like:
-    /// * Observe how the logic gates build the `z` numbers. `z00` and `z01` are easy to follow.
-    /// * Observing `z02`, we can notice clear patterns. Look through the each z output to find
-    ///   z values that deviate from the pattern. These z values need swapped.
-    /// * We can also compare the z bit outputs from what the expected sum result should be. This
-    ///   tells us what z bits are wrong, and indicates roughtly where a swap is needed.
-    /// * Try out the different swaps and see what works, checking against the expected result.
-    fn part2(input: &Input) -> impl std::fmt::Display {
-        let (wires, gates) = input;
-        let mut wires = wires.clone();
-        
-        // change input values here for testing
-        wires.entry("x16".to_string()).and_modify(|v| *v = !*v);
-
-        // Make the swaps
-        let mut gates = gates.clone();
-        let swaps = vec![
-            ("qff", "qnw"),
-            ("z16", "pbv"),
-            ("z23", "qqp"),
-            ("z36", "fbq"),
-        ];
-        for &(s1, s2) in &swaps {
-            swap_outputs(s1, s2, &mut gates);
-        }
-
-        let expected = binary_num('x', &wires) + binary_num('y', &wires);
-        let wire_result = run_gates(&wires, &gates.iter().collect());
-        let result = binary_num('z', &wire_result);
-
-        // If the swaps didn't work, debug what went wrong,
-        if result != expected {
-            let expected_as_binary = format!("{expected:b}");
-            let mut z_bits = wire_result.keys()
-                .filter(|key| key.starts_with('z'))
-                .collect::<Vec<_>>();
-            z_bits.sort();
-            z_bits.reverse();
-
-            // find which z output wires have an unexpected value. These are potential outputs to swap.
-            let wrong_zs = z_bits.into_iter().zip(expected_as_binary.chars())
-                .filter(|(z_key, expected_bit)| {
-                    let z_val = wire_result[*z_key];
-                    (z_val && *expected_bit == '0') || (!z_val && *expected_bit == '1')
-                })
-                .map(|(z_key, _)| z_key)
-                .map(|z_key| gates.iter().find(|gate| &gate.output == z_key).unwrap())
-                .collect::<Vec<_>>();
-            println!("bad zs: {:?}", wrong_zs.iter().map(|g| &g.output).collect::<Vec<_>>());
-            return String::new();
-        }
-        
-        let mut swapped = swaps.into_iter()
-            .flat_map(|(s1, s2)| vec![s1, s2])
+    /// The gates should make a ripple carry adder, where every bit is a full adder:
+    /// ```text
+    /// x XOR y -> sum        sum XOR carry_in -> z
+    /// x AND y -> a          sum AND carry_in -> b        a OR b -> carry_out
+    /// ```
+    /// Gates that don't fit that shape have swapped outputs, see [`suspect_wires`].
+    /// Try pairing those wires up until the adder adds correctly.
+    fn part2(input: &Input) -> Result<impl IntoAnswer> {
+        let (_, gates) = input;
+        let mut swapped = find_swaps(gates, 4)?
+            .ok_or_else(|| AocError::no_solution("no swaps make the gates into an adder"))?
+            .into_iter()
+            .flat_map(|(s1, s2)| [s1, s2])
             .collect::<Vec<_>>();
         swapped.sort_unstable();
-        swapped.join(",")
+        Ok(swapped.join(","))
     }
 }
 
-/// Run the wires through the logic gates until we resolve the wire values.
-/// return a new map of wire values with the result state.
-fn run_gates(wires: &HashMap<String, bool>, gates: &Vec<&Gate>) -> HashMap<String, bool> {
-    let mut wires = wires.clone();
+/// Find `swaps` pairs of output wires that, once swapped, make the gates add the x and y numbers
+///
+/// # Errors
+/// If the numbers are too wide to add, see [`Adder::new`]
+fn find_swaps(gates: &[Gate], swaps: usize) -> Result<Option<Vec<(&str, &str)>>> {
+    let suspects = suspect_wires(gates);
+    debug!(?suspects, "wires that don't fit the adder");
+    let adder = Adder::new(gates)?;
+    let mut chosen = Vec::new();
+    Ok(pair_up(&suspects, swaps, &mut chosen, &|pairs: &[(&str, &str)]| adder.adds_correctly(pairs)))
+}
 
-    let mut unused_gates = gates.iter().collect::<Vec<_>>();
-    while !unused_gates.is_empty() {
-        let mut skipped = Vec::new();
-        for &gate in &unused_gates {
-            if !wires.contains_key(&gate.lhs) || !wires.contains_key(&gate.rhs) {
-                skipped.push(gate);
-                continue;
+/// Choose `swaps` pairs out of `wires` (not every wire has to be used),
+/// returning the first set of pairs that `works`
+fn pair_up<'a>(
+    wires: &[&'a str],
+    swaps: usize,
+    chosen: &mut Vec<(&'a str, &'a str)>,
+    works: &impl Fn(&[(&'a str, &'a str)]) -> bool,
+) -> Option<Vec<(&'a str, &'a str)>> {
+    if chosen.len() == swaps {
+        return works(chosen).then(|| chosen.clone());
+    }
+    let [first, rest @ ..] = wires else {
+        return None;
+    };
+    for (idx, &second) in rest.iter().enumerate() {
+        chosen.push((first, second));
+        let remaining = rest[.. idx].iter().chain(&rest[idx + 1 ..]).copied().collect::<Vec<_>>();
+        if let Some(found) = pair_up(&remaining, swaps, chosen, works) {
+            return Some(found);
+        }
+        chosen.pop();
+    }
+    // leave the first wire as is, if there are still enough wires left to pair up
+    if rest.len() >= 2 * (swaps - chosen.len()) {
+        return pair_up(rest, swaps, chosen, works);
+    }
+    None
+}
+
+/// The output wires of gates that break the rules of a ripple carry adder:
+/// * Every z output is from a XOR gate, except the last z, which is the final carry (an OR gate)
+/// * A XOR gate that doesn't take x and y inputs is the final step of a bit, so it outputs to z
+/// * An AND gate output is only used by an OR gate (to make the carry), except for the first bit
+/// * `x XOR y` is used by another XOR gate (to make z), except for the first bit
+fn suspect_wires(gates: &[Gate]) -> Vec<&str> {
+    let last_z = gates.iter()
+        .map(|gate| gate.output.as_str())
+        .filter(|output| output.starts_with('z'))
+        .max()
+        .unwrap_or_default();
+    let is_input = |wire: &str| wire.starts_with('x') || wire.starts_with('y');
+    let first_bit = |gate: &Gate| gate.lhs.ends_with("00") && gate.rhs.ends_with("00");
+    // true if any gate using `wire` as an input matches the operation check
+    let feeds = |wire: &str, check: fn(Operation) -> bool| gates.iter()
+        .any(|gate| (gate.lhs == wire || gate.rhs == wire) && check(gate.operation));
+
+    let mut suspects = gates.iter()
+        .filter(|gate| {
+            let output = gate.output.as_str();
+            match gate.operation {
+                _ if output == last_z => gate.operation != Operation::Or,
+                Operation::Xor if output.starts_with('z') => is_input(&gate.lhs) && !first_bit(gate),
+                Operation::Xor if is_input(&gate.lhs) => !first_bit(gate) && !feeds(output, |op| op == Operation::Xor),
+                Operation::Xor => true,
+                Operation::And => output.starts_with('z') || (!first_bit(gate) && feeds(output, |op| op != Operation::Or)),
+                Operation::Or => output.starts_with('z'),
             }
-            let lhs = wires[&gate.lhs];
-            let rhs = wires[&gate.rhs];
-            let result = match gate.operation {
-                Operation::And => lhs && rhs,
-                Operation::Or => lhs || rhs,
-                Operation::Xor => lhs != rhs,
-            };
-            wires.insert(gate.output.to_string(), result);
+        })
+        .map(|gate| gate.output.as_str())
+        .collect::<Vec<_>>();
+    suspects.sort_unstable();
+    suspects
+}
+
+/// An output bit that came out wrong while adding two numbers with the gates, see [`check_adder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitFault {
+    /// The z wire number
+    pub bit: u32,
+    /// The numbers that were added
+    pub x: u64,
+    pub y: u64,
+    /// The bit of `x + y`
+    pub expected: bool,
+    /// The value of the z wire, or `None` if the gates can't be evaluated or never set it
+    pub actual: Option<bool>,
+}
+
+/// Add test numbers with the gates, and report each z bit that comes out wrong (with the first numbers that show it),
+/// in bit order. A working adder has no faults.
+///
+/// The tests are 0 + 0, each bit on its own (and added to itself, to carry into the next bit),
+/// a carry through every bit, and pairs of random numbers.
+///
+/// # Errors
+/// If the x and y numbers are wider than 63 bits, so their sum doesn't fit in a `u64`
+pub fn check_adder(gates: &[Gate]) -> Result<Vec<BitFault>> {
+    let mut faults: Vec<BitFault> = Vec::new();
+    for fault in Adder::new(gates)?.faults(gates) {
+        if faults.iter().all(|found| found.bit != fault.bit) {
+            faults.push(fault);
         }
+    }
+    faults.sort_unstable_by_key(|fault| fault.bit);
+    Ok(faults)
+}
+
+/// The gates of the circuit, for checking the adder with different swaps
+struct Adder<'a> {
+    gates: &'a [Gate],
+    bits: u32,
+}
+
+/// How many pairs of random numbers to add when checking the adder
+const RANDOM_TESTS: usize = 20;
 
-        // When swapping wires, we may create a failed solution. Kill it here
-        if unused_gates == skipped {
-            return wires;
+/// The widest x and y numbers that can be added, so that their sum still fits in a `u64`
+const MAX_BITS: u32 = u64::BITS - 1;
+
+impl<'a> Adder<'a> {
+    /// # Errors
+    /// If the x and y numbers are wider than [`MAX_BITS`]
+    fn new(gates: &'a [Gate]) -> Result<Self> {
+        let bits = gates.iter()
+            .flat_map(|gate| [&gate.lhs, &gate.rhs])
+            .filter(|wire| wire.starts_with('x'))
+            .filter_map(|wire| wire[1 ..].parse::<u32>().ok())
+            .max()
+            .map_or(0, |max_bit| max_bit + 1);
+        if bits > MAX_BITS {
+            return Err(AocError::no_solution(format!("the x and y numbers have {bits} bits, only {MAX_BITS} can be added")));
         }
-        unused_gates = skipped;
+        Ok(Self { gates, bits })
+    }
+
+    /// Check every bit on its own, every carry into the next bit, and a carry through every bit
+    fn adds_correctly(&self, swaps: &[(&'a str, &'a str)]) -> bool {
+        let outputs = |wire: &str| self.gates.iter().any(|gate| gate.output == wire);
+        if !swaps.iter().all(|&(s1, s2)| outputs(s1) && outputs(s2)) {
+            return false;
+        }
+        let gates = self.gates.iter()
+            .map(|gate| {
+                let mut gate = gate.clone();
+                if let Some(&(s1, s2)) = swaps.iter().find(|&&(s1, s2)| gate.output == s1 || gate.output == s2) {
+                    gate.output = if gate.output == s1 { s2 } else { s1 }.to_string();
+                }
+                gate
+            })
+            .collect::<Vec<_>>();
+        let first_fault = self.faults(&gates).next();
+        first_fault.is_none()
+    }
+
+    /// The numbers to add when checking the adder, see [`check_adder`]
+    fn tests(&self) -> impl Iterator<Item = (u64, u64)> {
+        let all_bits = (1_u64 << self.bits) - 1;
+        let mut rng = Rng::new(24);
+        let random = std::iter::repeat_with(move || (rng.next_u64() & all_bits, rng.next_u64() & all_bits));
+        std::iter::once((0, 0))
+            .chain((0 .. self.bits).flat_map(|bit| [(1 << bit, 0), (0, 1 << bit), (1 << bit, 1 << bit)]))
+            .chain([(all_bits, 1), (1, all_bits), (all_bits, all_bits)])
+            .chain(random.take(RANDOM_TESTS))
+    }
+
+    /// Every wrong z bit from adding the test numbers with `gates`, one test at a time
+    fn faults<'g>(&self, gates: &'g [Gate]) -> impl Iterator<Item = BitFault> + 'g {
+        let bits = self.bits;
+        self.tests().flat_map(move |(x, y)| Self::add(bits, gates, x, y))
+    }
+
+    /// Run the x and y numbers through the gates, returning the z bits that don't match `x + y`
+    fn add(bits: u32, gates: &[Gate], x: u64, y: u64) -> Vec<BitFault> {
+        let wires = (0 .. bits)
+            .flat_map(|bit| [
+                (format!("x{bit:02}"), x >> bit & 1 == 1),
+                (format!("y{bit:02}"), y >> bit & 1 == 1),
+            ])
+            .collect();
+        let output = run_gates(&wires, gates).ok();
+        let sum = x + y;
+        (0 ..= bits)
+            .filter_map(|bit| {
+                let expected = sum >> bit & 1 == 1;
+                let actual = output.as_ref().and_then(|output| output.get(&format!("z{bit:02}")).copied());
+                (actual != Some(expected)).then_some(BitFault { bit, x, y, expected, actual })
+            })
+            .collect()
+    }
+}
+
+/// Evaluate the gates in topological order: a gate has to come after the gates that set its input wires.
+/// Return a new map of wire values with the result state.
+///
+/// # Errors
+/// If some gates can never be evaluated, because they are wired in a loop or an input wire is never set
+fn run_gates(wires: &HashMap<String, bool>, gates: &[Gate]) -> Result<HashMap<String, bool>> {
+    let set_by = gates.iter()
+        .enumerate()
+        .map(|(idx, gate)| (gate.output.as_str(), idx))
+        .collect::<HashMap<_, _>>();
+    let rules = gates.iter()
+        .enumerate()
+        .flat_map(|(idx, gate)| [&gate.lhs, &gate.rhs].into_iter()
+            .filter(|&wire| !wires.contains_key(wire))
+            .filter_map(|wire| set_by.get(wire.as_str()))
+            .map(move |&before| (before, idx)))
+        .collect::<Precedence<_>>();
+    let order = rules.sort(&(0 .. gates.len()).collect::<Vec<_>>())
+        .ok_or_else(|| AocError::no_solution("the gates are wired in a loop"))?;
+
+    let mut wires = wires.clone();
+    for idx in order {
+        let gate = &gates[idx];
+        let (Some(&lhs), Some(&rhs)) = (wires.get(&gate.lhs), wires.get(&gate.rhs)) else {
+            debug!(gate = gate.output, "gate input is never set");
+            return Err(AocError::no_solution(format!("gate {} is missing an input", gate.output)));
+        };
+        wires.insert(gate.output.clone(), gate.operation.apply(lhs, rhs));
+    }
+    Ok(wires)
+}
+
+/// Render the circuit as a Graphviz DOT graph, for inspecting the adder in an external viewer.
+///
+/// Every wire is a node, and each gate is drawn as its output wire, colored by the gate type,
+/// with an edge from each of its inputs. The x and y inputs are boxes, and the z outputs are double circles.
+#[must_use]
+pub fn to_dot(gates: &[Gate]) -> String {
+    let mut inputs = gates.iter()
+        .flat_map(|gate| [&gate.lhs, &gate.rhs])
+        .filter(|wire| wire.starts_with('x') || wire.starts_with('y'))
+        .collect::<Vec<_>>();
+    inputs.sort_unstable();
+    inputs.dedup();
+
+    let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n    node [style=filled];\n");
+    for wire in inputs {
+        let color = if wire.starts_with('x') { "lightblue" } else { "lightpink" };
+        writeln!(dot, "    {wire} [shape=box, fillcolor={color}];").unwrap();
+    }
+    for gate in gates {
+        let color = match gate.operation {
+            Operation::And => "gold",
+            Operation::Or => "palegreen",
+            Operation::Xor => "orchid",
+        };
+        let shape = if gate.output.starts_with('z') { "doublecircle" } else { "ellipse" };
+        let Gate { lhs, rhs, operation, output } = gate;
+        writeln!(dot, "    {output} [label=\"{output}\\n{operation}\", shape={shape}, fillcolor={color}];").unwrap();
+        writeln!(dot, "    {lhs} -> {output};").unwrap();
+        writeln!(dot, "    {rhs} -> {output};").unwrap();
     }
-    wires
+    dot.push_str("}\n");
+    dot
 }
 
-fn binary_num(starting_char: char, wires: &HashMap<String, bool>) -> i64 {
+/// The number made from the wires starting with `starting_char`, most significant bit first
+///
+/// # Errors
+/// If there are no such wires, or too many for an `i64`
+fn binary_num(starting_char: char, wires: &HashMap<String, bool>) -> Result<i64> {
     let mut bit_wires = wires.keys()
         .filter(|key| key.starts_with(starting_char))
         .collect::<Vec<_>>();
@@ -155,116 +367,151 @@ fn binary_num(starting_char: char, wires: &HashMap<String, bool>) -> i64 {
     let result = bit_wires.into_iter()
         .map(|w| if wires[w] { '1' } else { '0' })
         .collect::<String>();
-    i64::from_str_radix(&result, 2).unwrap()
+    i64::from_str_radix(&result, 2)
+        .map_err(|_| AocError::no_solution(format!("{} {starting_char} wires can't be read as a number", result.len())))
 }
 
-// Mutating the gates in place is a little complicated, but more efficient
-// and works fine for what we need it to do in part 2
-fn swap_outputs(o1: &str, o2: &str, gates: &mut [Gate]) {
-    let idx1 = gates.iter()
-        .enumerate()
-        .find(|(_, g)| g.output == o1)
-        .map(|(idx, _)| idx)
-        .unwrap();
-    let idx2 =  gates.iter()
-        .enumerate()
-        .find(|(_, g)| g.output == o2)
-        .map(|(idx, _)| idx)
-        .unwrap();
-    let g1 = gates.get_mut(idx1).unwrap();
-    g1.output = o2.to_string();
-    let g2 = gates.get_mut(idx2).unwrap();
-    g2.output = o1.to_string();
-}
-
-
-fn parse_input(input: &str) -> Input {
-    let sections = input.split("\n\n").collect::<Vec<_>>();
-    let wires = sections[0].lines()
+fn parse_input(input: &str) -> Result<Input> {
+    let (wires, gates) = two_sections(input, "wires and gates")?;
+    let wires = wires.lines()
         .map(|line|{
-            let intitial_values = line.split(": ").collect::<Vec<_>>();
-            let b = intitial_values[1] == "1";
-            (intitial_values[0].to_string(), b)
+            let (wire, value) = key_value(line, ":")?;
+            Ok((wire.to_string(), value == "1"))
         })
-        .collect::<HashMap<_,_>>();
+        .collect::<Result<HashMap<_,_>>>()?;
 
-    let gates = sections[1].lines()
+    let gates = gates.lines()
         .map(|line|{
-            let parts = line.split(" -> ").collect::<Vec<_>>();
-            let gate_input = parts[0].split_whitespace().collect::<Vec<_>>();
-            let operation = match gate_input[1] {
+            let invalid = || AocError::parse(format!("invalid gate {line}"));
+            let (gate_input, output) = key_value(line, "->")?;
+            let [lhs, operation, rhs] = gate_input.split_whitespace().collect::<Vec<_>>()[..] else {
+                return Err(invalid());
+            };
+            let operation = match operation {
                 "AND" => Operation::And,
                 "OR" => Operation::Or,
                 "XOR" => Operation::Xor,
-                _ => panic!("Invalid operation {}", gate_input[1]),
+                _ => return Err(AocError::parse(format!("Invalid operation {operation}"))),
             };
-            Gate {
-                lhs: gate_input[0].to_string(),
-                rhs: gate_input[2].to_string(),
+            Ok(Gate {
+                lhs: lhs.to_string(),
+                rhs: rhs.to_string(),
                 operation,
-                output: parts[1].to_string(),
-            }
+                output: output.to_string(),
+            })
         })
-        .collect();
-    (wires, gates)
+        .collect::<Result<_>>()?;
+    Ok((wires, gates))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
+
+    aoc_test!(day24, part1, example 1, "2024");
 
-    const TEST: &str = "x00: 1
-x01: 0
-x02: 1
-x03: 1
-x04: 0
-y00: 1
-y01: 1
-y02: 1
-y03: 1
-y04: 1
-
-ntg XOR fgs -> mjb
-y02 OR x01 -> tnw
-kwq OR kpj -> z05
-x00 OR x03 -> fst
-tgd XOR rvg -> z01
-vdt OR tnw -> bfw
-bfw AND frj -> z10
-ffh OR nrd -> bqk
-y00 AND y03 -> djm
-y03 OR y00 -> psh
-bqk OR frj -> z08
-tnw OR fst -> frj
-gnj AND tgd -> z11
-bfw XOR mjb -> z00
-x03 OR x00 -> vdt
-gnj AND wpb -> z02
-x04 AND y00 -> kjc
-djm OR pbm -> qhw
-nrd AND vdt -> hwm
-kjc AND fst -> rvg
-y04 OR y02 -> fgs
-y01 AND x02 -> pbm
-ntg OR kjc -> kwq
-psh XOR fgs -> tgd
-qhw XOR tgd -> z09
-pbm OR djm -> kpj
-x03 XOR y03 -> ffh
-x00 XOR y04 -> ntg
-bfw OR bqk -> z06
-nrd XOR fgs -> wpb
-frj XOR qhw -> z04
-bqk OR frj -> z07
-y03 OR x01 -> nrd
-hwm AND bqk -> z03
-tgd XOR rvg -> z12
-tnw OR pbm -> gnj";
+    /// A working ripple carry adder for `bits` bits, with the named output wires swapped
+    fn broken_adder(bits: u32, swaps: &[(&str, &str)]) -> Vec<Gate> {
+        let gate = |lhs: String, operation, rhs: String, output: String| Gate { lhs, rhs, operation, output };
+        let carry = |bit: u32| if bit == bits - 1 { format!("z{bits:02}") } else { format!("c{bit:02}") };
+        let mut gates = vec![
+            gate("x00".into(), Operation::Xor, "y00".into(), "z00".into()),
+            gate("x00".into(), Operation::And, "y00".into(), carry(0)),
+        ];
+        for bit in 1 .. bits {
+            let (x, y) = (format!("x{bit:02}"), format!("y{bit:02}"));
+            gates.push(gate(x.clone(), Operation::Xor, y.clone(), format!("s{bit:02}")));
+            gates.push(gate(x, Operation::And, y, format!("a{bit:02}")));
+            gates.push(gate(format!("s{bit:02}"), Operation::Xor, carry(bit - 1), format!("z{bit:02}")));
+            gates.push(gate(format!("s{bit:02}"), Operation::And, carry(bit - 1), format!("b{bit:02}")));
+            gates.push(gate(format!("a{bit:02}"), Operation::Or, format!("b{bit:02}"), carry(bit)));
+        }
+        for gate in &mut gates {
+            for &(s1, s2) in swaps {
+                if gate.output == s1 {
+                    gate.output = s2.to_string();
+                } else if gate.output == s2 {
+                    gate.output = s1.to_string();
+                }
+            }
+        }
+        gates
+    }
+
+    #[test]
+    fn test_find_swaps() {
+        let working = broken_adder(6, &[]);
+        assert!(suspect_wires(&working).is_empty());
+        assert!(Adder::new(&working).unwrap().adds_correctly(&[]));
+
+        let broken = broken_adder(6, &[("s02", "a02"), ("z04", "c03")]);
+        assert_eq!(vec!["a02", "c03", "s02", "z04"], suspect_wires(&broken));
+        let mut swaps = find_swaps(&broken, 2).unwrap().unwrap();
+        swaps.sort_unstable();
+        assert_eq!(vec![("a02", "s02"), ("c03", "z04")], swaps);
+        assert_eq!(None, find_swaps(&broken, 1).unwrap());
+    }
+
+    #[test]
+    fn test_check_adder() {
+        assert!(check_adder(&broken_adder(6, &[])).unwrap().is_empty());
+        assert!(check_adder(&broken_adder(45, &[])).unwrap().is_empty());
+        assert!(check_adder(&broken_adder(63, &[])).unwrap().is_empty());
+        assert!(check_adder(&broken_adder(64, &[])).is_err());
+
+        // z04 is set by the AND gate instead of the XOR gate, so it is wrong when only one
+        // of the sum and carry in is set, and that also breaks the carries after it
+        let faults = check_adder(&broken_adder(6, &[("z04", "b04")])).unwrap();
+        assert_eq!(vec![4, 5, 6], faults.iter().map(|fault| fault.bit).collect::<Vec<_>>());
+        assert_eq!(BitFault { bit: 4, x: 1 << 3, y: 1 << 3, expected: true, actual: Some(false) }, faults[0]);
+
+        // the sum and carry of bit 2 are swapped, so bit 2 is wrong when only one input is set
+        let faults = check_adder(&broken_adder(6, &[("s02", "a02")])).unwrap();
+        assert_eq!(Some(&BitFault { bit: 2, x: 1 << 2, y: 0, expected: true, actual: Some(false) }), faults.first());
+
+        // gates that can't be evaluated get every bit wrong
+        let mut looped = broken_adder(2, &[]);
+        // the carry out of bit 0 now depends on z01, which depends on that carry
+        looped[1].lhs = "z01".to_string();
+        let faults = check_adder(&looped).unwrap();
+        assert_eq!(3, faults.len());
+        assert!(faults.iter().all(|fault| fault.actual.is_none()));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let gates = broken_adder(2, &[]);
+        let dot = to_dot(&gates);
+        assert!(dot.starts_with("digraph circuit {"));
+        assert!(dot.contains("    x00 [shape=box, fillcolor=lightblue];"));
+        assert!(dot.contains("    y01 [shape=box, fillcolor=lightpink];"));
+        assert!(dot.contains("    z01 [label=\"z01\\nXOR\", shape=doublecircle, fillcolor=orchid];"));
+        assert!(dot.contains("    s01 -> z01;"));
+        assert_eq!(2 * gates.len(), dot.matches(" -> ").count());
+    }
+
+    #[test]
+    fn test_binary_num() {
+        assert!(binary_num('z', &HashMap::new()).is_err());
+        let wires = (0 .. 63).map(|bit| (format!("z{bit:02}"), true)).collect::<HashMap<_, _>>();
+        assert_eq!(i64::MAX, binary_num('z', &wires).unwrap());
+        let wires = (0 .. 64).map(|bit| (format!("z{bit:02}"), true)).collect::<HashMap<_, _>>();
+        assert!(binary_num('z', &wires).is_err());
+    }
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result = Day24::part1(&input);
-        assert_eq!("2024", result.to_string())
+    fn test_run_gates_cycle() {
+        let (wires, mut gates) = parse_input(&example(24, 1)).unwrap();
+        assert_eq!(2024, binary_num('z', &run_gates(&wires, &gates).unwrap()).unwrap());
+        // feed the output of the last gate back into the first one
+        gates[0].lhs = "gnj".to_string();
+        gates.last_mut().unwrap().lhs = "mjb".to_string();
+        assert!(run_gates(&wires, &gates).is_err());
+        // an input wire that nothing sets
+        let (wires, mut gates) = parse_input(&example(24, 1)).unwrap();
+        gates[0].rhs = "abc".to_string();
+        assert!(run_gates(&wires, &gates).is_err());
     }
 }
@@ -1,5 +1,7 @@
-use super::Day;
-use std::fs;
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::grid::prelude::*;
+use crate::util::parse::sections;
 
 /// Day 25: Code Chronicle
 /// 
@@ -7,7 +9,7 @@ use std::fs;
 /// If the top row is filled in, it's a lock, if the bottom row is filled, it's a key.
 /// 
 /// Here's an example lock:
-/// ```
+/// ```text
 /// #####
 /// ##.##
 /// .#.##
@@ -24,109 +26,108 @@ use std::fs;
 /// Part 1: Try every key in every lock. How many fit together?
 pub struct Day25;
 
-impl Day<(Vec<Vec<i32>>, Vec<Vec<i32>>)> for Day25 {
-    fn read_input() -> (Vec<Vec<i32>>, Vec<Vec<i32>>) {
-        let input = fs::read_to_string("resources/day25.txt").expect("file day25.txt not found");
-        parse_input(&input)
+/// The lock and key heights
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schematics {
+    pub locks: Vec<Vec<i32>>,
+    pub keys: Vec<Vec<i32>>,
+    /// The room for a lock and key to fit together in each column:
+    /// the schematic height, not counting the full top and bottom rows
+    pub space: i32,
+}
+
+impl Day<Schematics> for Day25 {
+    fn parse(input: &str) -> Result<Schematics> {
+        parse_input(input)
     }
 
-    fn part1(input: &(Vec<Vec<i32>>, Vec<Vec<i32>>)) -> impl std::fmt::Display {
-        let (locks, keys) = input;
-        let mut matches = 0;
-        for key in keys {
-            for lock in locks {
-                // compare the key and lock. If the two don't overlap (sum less than 6)
-                // for each position on the lock, then they fit
-                if key.iter().zip(lock).all(|(top, bottom)| top + bottom <= 5) {
-                    matches += 1;
-                }
-            }
-        }
-        matches
+    fn part1(input: &Schematics) -> Result<impl IntoAnswer> {
+        Ok(count_fits(input))
     }
 
-    fn part2(_: &(Vec<Vec<i32>>, Vec<Vec<i32>>)) -> impl std::fmt::Display {
-        "AOC 2024"
+    fn part2(_: &Schematics) -> Result<impl IntoAnswer> {
+        Ok("AOC 2024")
     }
 }
 
+/// Try every key in every lock, and count the pairs that fit
+#[must_use]
+pub fn count_fits(schematics: &Schematics) -> usize {
+    let Schematics { locks, keys, space } = schematics;
+    keys.iter()
+        .flat_map(|key| locks.iter().filter(move |lock| fits(lock, key, *space)))
+        .count()
+}
+
+/// A key fits a lock if, in every column, the key and the lock tumbler fit in the `space` between them
+#[must_use]
+pub fn fits(lock: &[i32], key: &[i32], space: i32) -> bool {
+    key.iter().zip(lock).all(|(top, bottom)| top + bottom <= space)
+}
+
 // This is mostly a string parsing problem. Convert the key and lock inputs
 // into a Vec<i32> describing the tumblers/grooves.
-fn parse_input(input: &str) -> (Vec<Vec<i32>>, Vec<Vec<i32>>) {
-    let grids = input.split("\n\n").collect::<Vec<_>>();
+// Every schematic has the same size as the first one, which is at least 2 rows tall.
+fn parse_input(input: &str) -> Result<Schematics> {
     let mut keys = Vec::new();
     let mut locks = Vec::new();
+    let mut size = None;
 
-    for grid in grids {
-        let lines = grid.lines()
-            .map(|line| line.chars().collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let mut grooves = Vec::new();
-        for idx in 0 .. 5 {
-            let count = lines.iter()
-                .map(|line| line[idx])
-                .filter(|&c| c == '#')
-                .count() - 1;
-            grooves.push(count.try_into().unwrap());
+    for grid in sections(input) {
+        let schematic: Vec2d<char> = grid.parse()?;
+        let (width, height) = *size.get_or_insert((schematic.line_len, schematic.height()));
+        if (schematic.line_len, schematic.height()) != (width, height) || height < 2 {
+            return Err(AocError::parse(format!("expected a {width} x {height} schematic:\n{grid}")));
         }
-        if lines[0][0] == '#' {
+        // the height of each column, not counting the full top or bottom row
+        let grooves: Vec<i32> = schematic.cols()
+            .map(|col| col.filter(|&&c| c == '#').count().saturating_sub(1).try_into().unwrap())
+            .collect();
+        let full = |row: &[char]| row.iter().all(|&c| c == '#');
+        if schematic.rows().next().is_some_and(full) {
             locks.push(grooves);
-        } else {
+        } else if schematic.rows().last().is_some_and(full) {
             keys.push(grooves);
+        } else {
+            return Err(AocError::parse(format!("neither the top nor the bottom row is full:\n{grid}")));
         }
     }
 
-    (locks, keys)
+    let (_, height) = size.ok_or_else(|| AocError::parse("no schematics"))?;
+    Ok(Schematics { locks, keys, space: height - 2 })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
 
-    const TEST: &str = "#####
-.####
-.####
-.####
-.#.#.
-.#...
-.....
-
-#####
-##.##
-.#.##
-...##
-...#.
-...#.
-.....
-
-.....
-#....
-#....
-#...#
-#.#.#
-#.###
-#####
-
-.....
-.....
-#.#..
-###..
-###.#
-###.#
-#####
-
-.....
-.....
-.....
-#....
-#.#..
-#.#.#
-#####";
+    aoc_test!(day25, part1, example 1, "3");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result = Day25::part1(&input);
-        assert_eq!("3", result.to_string())
+    fn test_other_sizes() {
+        let input = parse_input("###
+#.#
+...
+...
+
+...
+..#
+#.#
+###
+
+...
+...
+.#.
+###").unwrap();
+        assert_eq!(vec![vec![1, 0, 1]], input.locks);
+        assert_eq!(vec![vec![1, 0, 2], vec![0, 1, 0]], input.keys);
+        assert_eq!(2, input.space);
+        assert_eq!(1, count_fits(&input));
+        assert!(fits(&[1, 0, 1], &[1, 2, 1], 2));
+
+        assert!(parse_input("###\n...\n\n####\n....").is_err());
+        assert!(parse_input("#.#\n...\n#.#").is_err());
+        assert!(parse_input("").is_err());
     }
 }
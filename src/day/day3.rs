@@ -1,7 +1,6 @@
-use regex::Regex;
-
-use super::Day;
-use std::fs;
+use super::{Day, IntoAnswer};
+use crate::util::error::Result;
+use crate::util::scanner::{ScannerSpec, MUL};
 
 /// Day 3: Mull It Over
 /// 
@@ -17,58 +16,40 @@ use std::fs;
 /// Assume the program starts enabled. Sum the result of the enabled `mul()` operations.
 pub struct Day3;
 
+/// Only `mul` instructions are valid
+pub const PART1_SPEC: ScannerSpec = ScannerSpec {
+    ops: &[MUL],
+    enable: None,
+    disable: None,
+    max_digits: 3,
+    nested: false,
+};
+
+/// `mul` instructions, which can be turned off and on by `don't()` and `do()`
+pub const PART2_SPEC: ScannerSpec = ScannerSpec {
+    enable: Some("do()"),
+    disable: Some("don't()"),
+    ..PART1_SPEC
+};
+
 impl Day<String> for Day3 {
-    fn read_input() -> String {
-        fs::read_to_string("resources/day3.txt").expect("file day3.txt not found")
+    fn parse(input: &str) -> Result<String> {
+        Ok(input.to_string())
     }
 
-    fn part1(input: &String) -> impl std::fmt::Display {
-        let re = Regex::new(r"mul\((\d{1,3})\,(\d{1,3})\)").unwrap();
-        re.captures_iter(input)
-            .map(|capture| {
-                let (_, [lhs, rhs]) = capture.extract();
-                lhs.parse::<i32>().unwrap() * rhs.parse::<i32>().unwrap()
-            })
-            .sum::<i32>()
+    fn part1(input: &String) -> Result<impl IntoAnswer> {
+        Ok(PART1_SPEC.evaluate(input))
     }
 
-    fn part2(input: &String) -> impl std::fmt::Display {
-        let re = Regex::new(r"mul\((\d{1,3})\,(\d{1,3})\)|don\'t\(\)|do\(\)").unwrap();
-        let mut on = true;
-        let mut sum = 0;
-        for capture in re.captures_iter(input) {
-            // `capture.extract();` panics because of differing capture arguments for matches
-            let full_string = capture.get(0).unwrap().as_str();
-            if full_string == "don't()" {
-                on = false;
-            } else if full_string == "do()" {
-                on = true;
-            } else if on {
-                let lhs = capture.get(1).unwrap().as_str().parse::<i32>().unwrap();
-                let rhs = capture.get(2).unwrap().as_str().parse::<i32>().unwrap();
-                sum += lhs * rhs;
-            }
-        }
-        sum
+    fn part2(input: &String) -> Result<impl IntoAnswer> {
+        Ok(PART2_SPEC.evaluate(input))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part_1() {
-        let input = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))".to_string();
-        let result =  Day3::part1(&input);
-        assert_eq!("161", result.to_string())
-    }
-
-    #[test]
-    fn test_part_2() {
-        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))".to_string();
-        let result =  Day3::part2(&input);
-        assert_eq!("48", result.to_string())
-    }
+    use crate::aoc_test;
 
+    aoc_test!(day3, part1, example 1, "161");
+    aoc_test!(day3, part2, example 2, "48");
 }
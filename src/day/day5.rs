@@ -1,8 +1,9 @@
-use std::collections::{HashMap, HashSet};
-use std::fs;
-use super::Day;
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::parse::{key_value, two_sections};
+use crate::util::precedence::Precedence;
 
-type PrintEdits = (HashMap<i32, HashSet<i32>>, Vec<Vec<i32>>);
+pub type PrintEdits = (Precedence<i32>, Vec<Vec<i32>>);
 
 /// Day 5: Print Queue
 /// 
@@ -21,40 +22,28 @@ type PrintEdits = (HashMap<i32, HashSet<i32>>, Vec<Vec<i32>>);
 pub struct Day5;
 
 impl Day<PrintEdits> for Day5 {
-    fn read_input() -> PrintEdits {
-        let input = fs::read_to_string("resources/day5.txt").expect("file day5.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<PrintEdits> {
+        parse_input(input)
     }
 
-    fn part1(input: &PrintEdits) -> impl std::fmt::Display {
+    fn part1(input: &PrintEdits) -> Result<impl IntoAnswer> {
         let (rules, edits) = input;
-        edits.iter()
+        Ok(edits.iter()
             .filter(|edit| Self::is_valid_edit(edit, rules))
             .map(|edit| edit[edit.len() / 2])
-            .sum::<i32>()
+            .sum::<i32>())
     }
 
-    fn part2(input: &PrintEdits) -> impl std::fmt::Display {
+    fn part2(input: &PrintEdits) -> Result<impl IntoAnswer> {
         let (rules, edits) = input;
         edits.iter()
             .filter(|edit| !Self::is_valid_edit(edit, rules))
             .map(|edit| {
-                let mut fixed = edit.clone();
-                // Some of these will need multiple passes to fix
-                while !Self::is_valid_edit(&fixed, rules) {
-                    for i in 0 .. edit.len() - 1 {
-                        // Look at the next two pages
-                        // if they are being edited in the wrong order, swap them
-                        let valid = rules.get(&fixed[i])
-                            .is_some_and(|set| set.contains(&fixed[i+1]));
-                        if !valid {
-                            fixed.swap(i, i+1);
-                        }
-                    }
-                }
-                fixed[fixed.len() / 2]
+                let fixed = rules.sort(edit)
+                    .ok_or_else(|| AocError::no_solution(format!("the rules for the pages {edit:?} form a cycle")))?;
+                Ok(fixed[fixed.len() / 2])
             })
-            .sum::<i32>()
+            .sum::<Result<i32>>()
     }
 }
 
@@ -63,86 +52,51 @@ impl Day5 {
     /// Look at every pair of two letters (using `windows(2)`)
     /// The left number should always have a rule entry requiring it
     /// to come before the right number.
-    fn is_valid_edit(edit: &[i32], rules: &HashMap<i32, HashSet<i32>>) -> bool {
-        edit.windows(2).all(|slice| {
-            rules.get(&slice[0])
-                .unwrap_or(&HashSet::new())
-                .contains(&slice[1])
-        })
+    fn is_valid_edit(edit: &[i32], rules: &Precedence<i32>) -> bool {
+        edit.windows(2).all(|slice| rules.directly_before(slice[0], slice[1]))
     }
 }
 
-fn parse_input(input: &str) -> PrintEdits {
-    let split = input.split("\n\n").collect::<Vec<_>>();
-    let edits = split[1].lines()
+fn parse_input(input: &str) -> Result<PrintEdits> {
+    let (rules_section, edits_section) = two_sections(input, "rules and edits")?;
+    let edits = edits_section.lines()
         .map(|line| { 
             line.trim()
             .split(',')
-            .map(|v| v.parse::<i32>().unwrap())
+            .map(str::parse::<i32>)
             .collect()
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    let mut rules = HashMap::new();
-    for rule in split[0].lines() {
-        let [lhs, rhs]: [i32; 2] = rule.split('|')
-            .map(|v| v.parse::<i32>().unwrap())
-            .collect::<Vec<_>>()
-            .try_into().unwrap(); // Force the vec into an array of size two for destructuring
-        
-        let entry = rules.entry(lhs).or_insert_with(HashSet::new);
-        entry.insert(rhs);
+    let mut rules = Precedence::new();
+    for rule in rules_section.lines() {
+        let (lhs, rhs) = key_value(rule, "|")?;
+        rules.add(lhs.parse()?, rhs.parse()?);
     }
 
-    (rules, edits)
+    Ok((rules, edits))
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
 
-    const TEST: &str = "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47";
+    aoc_test!(day5, part1, example 1, "143");
+    aoc_test!(day5, part2, example 1, "123");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day5::part1(&input);
-        assert_eq!("143", result.to_string());
+    fn test_reorder() {
+        let (rules, _) = parse_input(&example(5, 1)).unwrap();
+        assert_eq!(Some(vec![97, 75, 47, 29, 13]), rules.sort(&[97, 13, 75, 29, 47]));
+        assert_eq!(Some(vec![61, 29, 13]), rules.sort(&[61, 13, 29]));
+        assert_eq!(None, rules.find_cycle());
+        assert!(rules.must_precede(97, 13));
+
+        let input = parse_input("1|2\n2|3\n3|1\n\n3,2,1").unwrap();
+        assert_eq!(3, input.0.find_cycle().unwrap().len());
+        assert!(matches!(Day5::part2(&input), Err(AocError::NoSolution(_))));
     }
-
-    #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result =  Day5::part2(&input);
-        assert_eq!("123", result.to_string());
-    }
-
 }
@@ -1,7 +1,9 @@
-use crate::util::vec2d::{Directions, Vec2d};
-use std::{collections::HashSet, fs};
+use crate::util::grid::prelude::*;
 
-use super::Day;
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::viz::{frame_interval, render, Color};
+use rayon::prelude::*;
 
 /// Day 6: Guard Gallivant
 /// 
@@ -15,123 +17,177 @@ use super::Day;
 /// How many locations on the map will cause this loop if an obstacle is added to just one space?
 pub struct Day6;
 
-impl Day<Vec2d<char>> for Day6 {
-    fn read_input() -> Vec2d<char> {
-        let input = fs::read_to_string("resources/day6.txt").expect("file day6.txt not found");
-        parse_input(&input)
+impl Day<Vec2d<u8>> for Day6 {
+    fn parse(input: &str) -> Result<Vec2d<u8>> {
+        parse_input(input)
     }
 
-    fn part1(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let start_pos = input.grid.iter().enumerate()
-            .find(|(_, &c)| c == '^' )
-            .map(|(idx, _)| idx)
-            .unwrap();
-        let mut guard_location = input.idx_to_point(start_pos);
-        let mut direction = Directions::Up;
-        let mut traversed = HashSet::new();
-        traversed.insert(guard_location);
-        loop {
-            let Some(next) = input.next_point(guard_location, direction) else {
-                break;
-            };
-            if input[next] == '#' {
-                direction = rotate_right(direction);
-            } else {
-                guard_location = next;
-                traversed.insert(next);
+    fn part1(input: &Vec2d<u8>) -> Result<impl IntoAnswer> {
+        let walls = BitGrid::from_grid(input, |&c| c == b'#');
+        Ok(guard_path(&walls, find_guard(input)?).count())
+    }
+
+    // An obstacle only changes the guard's route if it is somewhere on the original path.
+    // Rather than stepping one cell at a time, the guard jumps straight to the next wall
+    // using a precomputed table, and only the new obstacle needs to be checked separately.
+    // Each candidate obstacle is independent, so they are checked in parallel.
+    fn part2(input: &Vec2d<u8>) -> Result<impl IntoAnswer> {
+        let start = find_guard(input)?;
+        let walls = BitGrid::from_grid(input, |&c| c == b'#');
+        let jumps = JumpTable::new(&walls);
+        let path = guard_path(&walls, start);
+        let candidates = path.iter_points()
+            .filter(|&point| point != start && path.contains(point))
+            .collect::<Vec<_>>();
+        Ok(candidates.into_par_iter()
+            .filter(|&obstacle| is_guard_loop(&jumps, start, obstacle))
+            .count())
+    }
+}
+
+/// Where the guard stops (just before a wall) when walking from any point in any of the 4 directions.
+/// `None` means the guard walks off the map.
+struct JumpTable {
+    stops: [Vec<Option<Point>>; 4],
+    line_len: i32,
+    height: i32,
+}
+
+impl JumpTable {
+    fn new(walls: &BitGrid) -> Self {
+        let points = walls.iter_points().collect::<Vec<_>>();
+        let stops = Directions::cardinal().map(|direction| {
+            let mut stops = vec![None; points.len()];
+            // Fill in the point ahead of the guard before the point behind it
+            let mut ordered = points.clone();
+            if matches!(direction, Directions::Down | Directions::Right) {
+                ordered.reverse();
             }
+            for point in ordered {
+                let next = point + direction.delta();
+                stops[walls.point_to_idx(point)] = if !walls.in_bounds(next) {
+                    None
+                } else if walls.contains(next) {
+                    Some(point)
+                } else {
+                    stops[walls.point_to_idx(next)]
+                };
+            }
+            stops
+        });
+        Self { stops, line_len: walls.line_len(), height: walls.height() }
+    }
+
+    /// Where the guard stops walking from `from`, with an extra obstacle placed on the map
+    fn jump(&self, from: Point, direction: Directions, obstacle: Point) -> Option<Point> {
+        let idx = usize::try_from(from.y * self.line_len + from.x).unwrap_or_default();
+        let stop = self.stops[facing(direction)][idx];
+        // The new obstacle cuts the jump short when it is in the same row or column,
+        // in front of the guard, and closer than the wall the guard would have stopped at
+        let delta = direction.delta();
+        let offset = obstacle - from;
+        let ahead = offset.x * delta.y == offset.y * delta.x && offset.x * delta.x + offset.y * delta.y > 0;
+        let blocks = ahead && stop.is_none_or(|stop| from.manhattan_distance(&obstacle) <= from.manhattan_distance(&stop));
+        if blocks {
+            Some(obstacle - delta)
+        } else {
+            stop
         }
-        traversed.len()
     }
+}
 
-    // So there should be a better way to do this.
-    // Brute force checking each possible obstacle location is slow.
-    fn part2(input: &Vec2d<char>) -> impl std::fmt::Display {
-        input.grid.iter().enumerate()
-            .filter(|(_, &c)| c == '.')
-            .filter(|(idx, _)| {
-                let mut test_obstruction = input.clone();
-                test_obstruction.grid[*idx] = '#';
-                is_guard_loop(&test_obstruction)
-            })
-            .count()
+/// The index of the direction in [`Directions::cardinal`]
+fn facing(direction: Directions) -> usize {
+    Directions::cardinal().iter().position(|&d| d == direction).unwrap_or_default()
+}
 
-    }
+fn find_guard(map: &Vec2d<u8>) -> Result<Point> {
+    map.find(&b'^').ok_or_else(|| AocError::parse("no guard ^ on the map"))
 }
 
-fn is_guard_loop(map: &Vec2d<char>) -> bool {
-    let start_pos = map.grid.iter().enumerate()
-        .find(|(_, &c)| c == '^' )
-        .map(|(idx, _)| idx)
-        .unwrap();
-    let mut guard_location = map.idx_to_point(start_pos);
+/// Every point the guard visits before leaving the map
+fn guard_path(walls: &BitGrid, start: Point) -> BitGrid {
+    let mut guard_location = start;
     let mut direction = Directions::Up;
-    let mut traversed = HashSet::new();
-    traversed.insert((guard_location, direction));
+    let mut traversed = BitGrid::new(walls.line_len(), walls.height());
+    traversed.insert(guard_location);
     loop {
-        let Some(next) = map.next_point(guard_location, direction) else {
-            return false; // exited the map
-        };
-        if map[next] == '#' {
-            direction = rotate_right(direction);
+        let next = guard_location + direction.delta();
+        if !walls.in_bounds(next) {
+            return traversed;
+        }
+        if walls.contains(next) {
+            direction = direction.turn_right();
         } else {
             guard_location = next;
-        }
-        if !traversed.insert((guard_location, direction)) {
-            // set already contained this value, we have a guard loop
-            return true;
+            traversed.insert(next);
         }
     }
 }
 
-fn rotate_right(direction: Directions) -> Directions {
-    match direction {
-        Directions::Up => Directions::Right,
-        Directions::Right => Directions::Down,
-        Directions::Down => Directions::Left,
-        Directions::Left => Directions::Up,
-        _ => panic!("Direction {direction:?} not supported"),
+/// Frames of the guard walking its patrol until it leaves the map, about `max_frames` of them.
+/// Walls are gray, the guard's path so far is yellow, and the guard is red.
+///
+/// # Errors
+/// If there is no guard on the map
+pub fn patrol_frames(map: &Vec2d<u8>, max_frames: usize) -> Result<impl Iterator<Item = String> + '_> {
+    let walls = BitGrid::from_grid(map, |&c| c == b'#');
+    let mut route = vec![(find_guard(map)?, Directions::Up)];
+    while let Some(&(location, direction)) = route.last() {
+        let next = location + direction.delta();
+        if !walls.in_bounds(next) {
+            break;
+        }
+        route.push(if walls.contains(next) { (location, direction.turn_right()) } else { (next, direction) });
     }
+    // the step when the guard first reaches each point
+    let mut first_visit = Vec2d { grid: vec![usize::MAX; map.grid.len()], line_len: map.line_len };
+    for (step, &(point, _)) in route.iter().enumerate().rev() {
+        first_visit[point] = step;
+    }
+    let last = route.len() - 1;
+    let every = frame_interval(route.len(), max_frames);
+    let steps = (0 .. last).step_by(every).chain([last]);
+    Ok(steps.map(move |step| {
+        let (guard, direction) = route[step];
+        render(map.line_len, map.height(), |point| {
+            if point == guard {
+                (direction.to_char().unwrap_or('^'), Color::Red)
+            } else if walls.contains(point) {
+                ('#', Color::Gray)
+            } else if first_visit[point] <= step {
+                ('X', Color::Yellow)
+            } else {
+                ('.', Color::Default)
+            }
+        })
+    }))
 }
 
-fn parse_input(input: &str) -> Vec2d<char> {
-    let chars = input.lines()
-        .flat_map(|line| line.trim().chars().collect::<Vec<_>>())
-        .collect();
-    let line_len = input.lines().next().unwrap().len();
-    Vec2d {
-        grid: chars,
-        line_len: line_len as i32,
+fn is_guard_loop(jumps: &JumpTable, start: Point, obstacle: Point) -> bool {
+    let mut guard_location = start;
+    let mut direction = Directions::Up;
+    // one grid of turning points for each direction the guard can face
+    let mut turns: [BitGrid; 4] = std::array::from_fn(|_| BitGrid::new(jumps.line_len, jumps.height));
+    while let Some(stop) = jumps.jump(guard_location, direction, obstacle) {
+        if !turns[facing(direction)].insert(stop) {
+            // already turned here facing the same way, we have a guard loop
+            return true;
+        }
+        guard_location = stop;
+        direction = direction.turn_right();
     }
+    false // exited the map
+}
+
+fn parse_input(input: &str) -> Result<Vec2d<u8>> {
+    Vec2d::from_bytes(input)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    const TEST: &str = "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...";
-
-    #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day6::part1(&input);
-        assert_eq!("41", result.to_string())
-    }
-
-    #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result =  Day6::part2(&input);
-        assert_eq!("6", result.to_string())
-    }
+    use crate::aoc_test;
 
+    aoc_test!(day6, part1, example 1, "41");
+    aoc_test!(day6, part2, example 1, "6");
 }
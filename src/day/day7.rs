@@ -1,7 +1,9 @@
-use super::Day;
-use std::fs;
+use std::fmt::{self, Write};
 
-type Calibration = (i64, Vec<i64>);
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+
+pub type Calibration = (i64, Vec<i64>);
 
 /// Day 7: Bridge Repair
 /// 
@@ -16,73 +18,120 @@ type Calibration = (i64, Vec<i64>);
 /// numbers ex: `15 || 80 == 1580`. Sum the valid equations.
 pub struct Day7;
 
-enum Operation {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
     Mul,
     Add,
     Cat,
 }
 
+/// The operators allowed in part 1
+pub const PART1_OPERATORS: &[Operation] = &[Operation::Mul, Operation::Add];
+/// The operators allowed in part 2
+pub const PART2_OPERATORS: &[Operation] = &[Operation::Mul, Operation::Add, Operation::Cat];
+
 impl Day<Vec<Calibration>> for Day7 {
-    fn read_input() -> Vec<Calibration> {
-        let input = fs::read_to_string("resources/day7.txt").expect("file day7.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec<Calibration>> {
+        parse_input(input)
     }
 
-    // Slightly smart brute force approach
-    fn part1(input: &Vec<Calibration>) -> impl std::fmt::Display {
-        let operators = &[Operation::Mul, Operation::Add];
-        input.iter()
-            .filter(|(result, operations)| {
-                try_operations(*result, operations[0], &operations[1..], operators)
-            })
-            .map(|(r, _)| *r)
-            .sum::<i64>()
+    // Search backwards from the result, see `find_operators`
+    fn part1(input: &Vec<Calibration>) -> Result<impl IntoAnswer> {
+        Ok(total_calibration(input, PART1_OPERATORS))
     }
 
-    fn part2(input: &Vec<Calibration>) -> impl std::fmt::Display {
-        let operators = &[Operation::Mul, Operation::Add, Operation::Cat];
-        input.iter()
-            .filter(|(result, operations)| {
-                try_operations(*result, operations[0], &operations[1..], operators)
-            })
-            .map(|(r, _)| *r)
-            .sum::<i64>()
+    fn part2(input: &Vec<Calibration>) -> Result<impl IntoAnswer> {
+        Ok(total_calibration(input, PART2_OPERATORS))
     }
 }
 
-// Try all possible combinations of operators, but bail out / short circuit aggressively
-fn try_operations(result: i64, current: i64, remaining: &[i64], operators: &[Operation]) -> bool {
-    if current > result {
-        return false;
+/// Sum the results of the equations that can be made true with the given operators
+#[must_use]
+pub fn total_calibration(input: &[Calibration], operators: &[Operation]) -> i64 {
+    input.iter()
+        .filter(|(result, numbers)| find_operators(*result, numbers, operators).is_some())
+        .map(|(r, _)| *r)
+        .sum()
+}
+
+/// A solved equation written out, ex: `3267 = 81 + 40 * 27`,
+/// or `None` if no combination of the given operators works.
+#[must_use]
+pub fn worked_example((result, numbers): &Calibration, operators: &[Operation]) -> Option<String> {
+    let found = find_operators(*result, numbers, operators)?;
+    let mut equation = format!("{result} = {}", numbers.first()?);
+    for (op, number) in found.iter().zip(&numbers[1..]) {
+        write!(equation, " {op} {number}").unwrap();
     }
-    let next = remaining[0];
-    if remaining.len() == 1 {
-        return operators.iter().any(|op| op.operate(current, next) == result);
+    Some(equation)
+}
+
+/// The operators to put between the numbers (in order, left to right) so they evaluate to `result`,
+/// or `None` if no combination of the given operators works.
+///
+/// Work backwards from the result, undoing the last operation at each step.
+/// Most operators can't be undone (the result isn't divisible, or doesn't end with the right digits),
+/// which prunes far more branches than going forwards ever could.
+#[must_use]
+pub fn find_operators(result: i64, numbers: &[i64], operators: &[Operation]) -> Option<Vec<Operation>> {
+    match numbers {
+        [] => None,
+        [first] => (*first == result).then(Vec::new),
+        [rest @ .., last] => operators.iter()
+            .filter_map(|op| Some((*op, op.undo(result, *last)?)))
+            .find_map(|(op, previous)| {
+                let mut found = find_operators(previous, rest, operators)?;
+                found.push(op);
+                Some(found)
+            }),
     }
-    let next_remaining = &remaining[1..];
-    operators.iter()
-        .map(|op| op.operate(current, next))
-        .any(|updated| try_operations(result, updated, next_remaining, operators))
 }
 
 impl Operation {
-    fn operate(&self, lhs: i64, rhs: i64) -> i64 {
+    /// Evaluate `lhs op rhs`, or `None` on overflow
+    #[must_use]
+    pub fn apply(self, lhs: i64, rhs: i64) -> Option<i64> {
         match self {
-            Self::Add => lhs + rhs,
-            Self::Mul => lhs * rhs,
-            Self::Cat => format!("{lhs}{rhs}").parse().unwrap()
+            Self::Add => lhs.checked_add(rhs),
+            Self::Mul => lhs.checked_mul(rhs),
+            Self::Cat => lhs.checked_mul(10_i64.checked_pow(rhs.checked_ilog10().unwrap_or(0) + 1)?)?.checked_add(rhs),
+        }
+    }
+
+    /// The left hand side that gives `result` when this operation is applied with `rhs`,
+    /// or `None` if there is no such value
+    fn undo(self, result: i64, rhs: i64) -> Option<i64> {
+        match self {
+            Self::Add => result.checked_sub(rhs).filter(|_| result >= rhs),
+            Self::Mul => (result.checked_rem(rhs)? == 0).then(|| result / rhs),
+            Self::Cat => {
+                // strip the digits of rhs from the end of result
+                let shift = 10_i64.checked_pow(rhs.checked_ilog10().unwrap_or(0) + 1)?;
+                (result.checked_rem(shift)? == rhs).then(|| result / shift)
+            }
         }
     }
 }
 
-fn parse_input(input: &str) -> Vec<Calibration> {
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Add => "+",
+            Self::Mul => "*",
+            Self::Cat => "||",
+        })
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<Calibration>> {
     input.lines().map(|line|{
-        let c = line.split(": ").collect::<Vec<_>>();
-        let result = c[0].trim().parse().unwrap();
-        let operations = c[1].split_whitespace()
-            .map(|o| o.parse().unwrap())
-            .collect();
-        (result, operations)
+        let (result, operations) = line.split_once(": ")
+            .ok_or_else(|| AocError::parse(format!("invalid calibration {line}")))?;
+        let result = result.trim().parse()?;
+        let operations = operations.split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok((result, operations))
     })
     .collect()
 }
@@ -90,29 +139,44 @@ fn parse_input(input: &str) -> Vec<Calibration> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
 
-    const TEST: &str = "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20";
+    aoc_test!(day7, part1, example 1, "3749");
+    aoc_test!(day7, part2, example 1, "11387");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day7::part1(&input);
-        assert_eq!("3749", result.to_string())
+    fn test_undo() {
+        assert_eq!(Some(15), Operation::Cat.undo(1580, 80));
+        assert_eq!(Some(1), Operation::Cat.undo(10, 0));
+        assert_eq!(None, Operation::Cat.undo(1580, 8));
+        assert_eq!(None, Operation::Mul.undo(7, 2));
+        assert_eq!(None, Operation::Add.undo(7, 8));
+        assert_eq!(Some(1580), Operation::Cat.apply(15, 80));
+        assert_eq!(Some(10), Operation::Cat.apply(1, 0));
+        // too many digits to undo, or dividing the smallest number by -1
+        assert_eq!(None, Operation::Cat.undo(5, i64::MAX));
+        assert_eq!(None, Operation::Mul.undo(i64::MIN, -1));
+        assert_eq!(None, Operation::Add.undo(i64::MAX, -1));
+        let input = parse_input("5: 1 9223372036854775807").unwrap();
+        assert_eq!(0, total_calibration(&input, PART2_OPERATORS));
     }
 
     #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result =  Day7::part2(&input);
-        assert_eq!("11387", result.to_string())
+    fn test_find_operators() {
+        use Operation::{Add, Cat, Mul};
+        let input = parse_input(&example(7, 1)).unwrap();
+        assert_eq!(Some(vec![Add, Mul]), find_operators(3267, &[81, 40, 27], PART1_OPERATORS));
+        assert_eq!(None, find_operators(7290, &[6, 8, 6, 15], PART1_OPERATORS));
+        assert_eq!(Some(vec![Mul, Cat, Mul]), find_operators(7290, &[6, 8, 6, 15], PART2_OPERATORS));
+        assert_eq!(Some("156 = 15 || 6".to_string()), worked_example(&input[3], PART2_OPERATORS));
+        assert_eq!(None, worked_example(&input[3], PART1_OPERATORS));
+        for (result, numbers) in &input {
+            if let Some(found) = find_operators(*result, numbers, PART2_OPERATORS) {
+                let evaluated = found.iter().zip(&numbers[1..])
+                    .try_fold(numbers[0], |acc, (op, number)| op.apply(acc, *number));
+                assert_eq!(Some(*result), evaluated);
+            }
+        }
     }
-
 }
@@ -1,7 +1,8 @@
-use std::{collections::{HashMap, HashSet}, fs};
+use std::collections::{HashMap, HashSet};
 use crate::util::grid::prelude::*;
 
-use super::Day;
+use super::{Day, IntoAnswer};
+use crate::util::error::Result;
 
 /// Day 8: Resonant Collinearity
 /// 
@@ -13,7 +14,7 @@ use super::Day;
 /// of the same frequency, but only when one antenna is twice as far away from the other.
 /// 
 /// Example, where `#` is an antinode:
-/// ```
+/// ```text
 /// ..........
 /// ...#......
 /// ..........
@@ -34,116 +35,92 @@ use super::Day;
 pub struct Day8;
 
 impl Day<Vec2d<char>> for Day8 {
-    fn read_input() -> Vec2d<char> {
-        let input = fs::read_to_string("resources/day8.txt").expect("file day8.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec2d<char>> {
+        parse_input(input)
     }
 
-    fn part1(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let antennae = find_antennae(input);
-        let mut antinodes = HashSet::new();
-        // nodes are grouped by antenna frequency
-        for nodes in antennae.values() {
-            if nodes.len() <= 1 {
-                continue;
-            }
-            // compare each antenna of the same frequency to all the others
-            for i in 0 .. nodes.len() - 1 {
-                for j in i + 1 .. nodes.len() {
-                    let diff = nodes[i] - nodes[j];
-                    antinodes.insert(nodes[i] + diff);
-                    antinodes.insert(nodes[j] - diff);
-                }
-            }
-        }
-        antinodes.into_iter()
-            .filter(|&antinode | input.in_bounds(antinode))
-            .count()
+    fn part1(input: &Vec2d<char>) -> Result<impl IntoAnswer> {
+        Ok(antinodes(input, Resonance::Double).len())
     }
 
-    fn part2(input: &Vec2d<char>) -> impl std::fmt::Display {
-        let antennae = find_antennae(input);
-        let mut antinodes = HashSet::new();
-        for nodes in antennae.values() {
-            if nodes.len() <= 1 {
-                continue;
-            }
-            for i in 0 .. nodes.len() - 1 {
-                for j in i + 1 .. nodes.len() {
-                    // Same as part 1, but continue until we reach the bounds edge of our grid
-                    // also add the antennas themselves
-                    antinodes.insert(nodes[i]);
-                    antinodes.insert(nodes[j]);
-                    let diff = nodes[i] - nodes[j];
-                    let mut line = nodes[i] + diff;
-                    while input.in_bounds(line) {
-                        antinodes.insert(line);
-                        line = line + diff;
-                    }
-                    line = nodes[j] - diff;
-                    while input.in_bounds(line) {
-                        antinodes.insert(line);
-                        line = line - diff;
+    fn part2(input: &Vec2d<char>) -> Result<impl IntoAnswer> {
+        Ok(antinodes(input, Resonance::Harmonics(None)).len())
+    }
+}
+
+/// Where antinodes form on the line through two antennas of the same frequency.
+/// The harmonics of an antenna are the points on the line at whole multiples of the distance
+/// between the antennas, going away from the other antenna. The antenna itself is harmonic 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resonance {
+    /// Only the first harmonic, where one antenna is twice as far away as the other (part 1)
+    Double,
+    /// Every harmonic up to the max (including the antennas), or to the edge of the grid for `None` (part 2)
+    Harmonics(Option<usize>),
+}
+
+/// Every antinode on the grid, from each pair of antennas with the same frequency
+#[must_use]
+pub fn antinodes(input: &Vec2d<char>, resonance: Resonance) -> HashSet<Point> {
+    let antennae = find_antennae(input);
+    let mut antinodes = HashSet::new();
+    // nodes are grouped by antenna frequency
+    for nodes in antennae.values() {
+        if nodes.len() <= 1 {
+            continue;
+        }
+        // compare each antenna of the same frequency to all the others
+        for i in 0 .. nodes.len() - 1 {
+            for j in i + 1 .. nodes.len() {
+                // the walk starts on the antenna, and continues until the edge of the grid
+                let diff = nodes[i] - nodes[j];
+                for harmonics in [input.walk_by(nodes[i], diff), input.walk_by(nodes[j], -diff)] {
+                    match resonance {
+                        Resonance::Double => antinodes.extend(harmonics.skip(1).take(1)),
+                        Resonance::Harmonics(max) => antinodes.extend(harmonics.take(max.map_or(usize::MAX, |max| max + 1))),
                     }
                 }
             }
         }
-        antinodes.len()
     }
+    antinodes
 }
 
 fn find_antennae(input: &Vec2d<char>) -> HashMap<char, Vec<Point>> {
     let mut antennae = HashMap::new();
-    for (idx, c) in input.grid.iter().enumerate() {
-        if *c == '.' {
+    for (point, &c) in input.iter_with_points() {
+        if c == '.' {
             continue;
         }
-        let entry = antennae.entry(*c).or_insert_with(Vec::new);
-        entry.push(input.idx_to_point(idx));
+        let entry = antennae.entry(c).or_insert_with(Vec::new);
+        entry.push(point);
     }
     antennae
 }
 
-fn parse_input(input: &str) -> Vec2d<char> {
-    let chars = input.lines()
-        .flat_map(|line| line.trim().chars().collect::<Vec<_>>())
-        .collect();
-    let line_len = input.lines().next().unwrap().len();
-    Vec2d {
-        grid: chars,
-        line_len: line_len as i32,
-    }
+fn parse_input(input: &str) -> Result<Vec2d<char>> {
+    input.parse()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::aoc_test;
+    use crate::test_support::example;
 
-    const TEST: &str = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
+    aoc_test!(day8, part1, example 1, "14");
+    aoc_test!(day8, part2, example 1, "34");
 
     #[test]
-    fn test_part_1() {
-        let input = parse_input(TEST);
-        let result =  Day8::part1(&input);
-        assert_eq!("14", result.to_string())
+    fn test_antinodes() {
+        let input = parse_input(&example(8, 1)).unwrap();
+        // only the antennas themselves
+        let antennas = antinodes(&input, Resonance::Harmonics(Some(0)));
+        assert_eq!(7, antennas.len());
+        // the antennas and the first harmonics are the same as part 1, plus the antennas
+        let first = antinodes(&input, Resonance::Harmonics(Some(1)));
+        assert_eq!(antinodes(&input, Resonance::Double).union(&antennas).count(), first.len());
+        assert!(first.is_subset(&antinodes(&input, Resonance::Harmonics(Some(2)))));
+        assert_eq!(34, antinodes(&input, Resonance::Harmonics(Some(100))).len());
     }
-
-    #[test]
-    fn test_part_2() {
-        let input = parse_input(TEST);
-        let result =  Day8::part2(&input);
-        assert_eq!("34", result.to_string())
-    }
-
 }
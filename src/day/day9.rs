@@ -1,23 +1,27 @@
-use super::Day;
-use std::fs;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::{Day, IntoAnswer};
+use crate::util::error::{AocError, Result};
+use crate::util::range::Span;
 
 /// Day 9: Disk Fragmenter
 /// 
 /// The puzzle input is a list of integers such as:
-/// ```
+/// ```text
 /// 12345
 /// ```
 /// 
 /// The first value indicates the size of memory take up. The next value is how many empty blocks of memory,
 /// followed by another block of used memory, etc. Each used block of memory has an id based on the order
 /// it appears in the puzzle input. So if we write out each block with its id using `.` for empty:
-/// ```
+/// ```text
 /// 0..111....22222
 /// ```
 /// 
 /// Part 1: Move file blocks one at a time from the end of the memory list to the leftmost free memorty space.
 /// Using the previous example, the end result would look like:
-/// ```
+/// ```text
 /// 022111222......
 /// ```
 /// Calculate the file checksum by taking each memory location and multiplying the file id by the index in
@@ -40,126 +44,84 @@ pub struct Mem {
 }
 
 impl Day<Vec<Mem>> for Day9 {
-    fn read_input() -> Vec<Mem> {
-        let input = fs::read_to_string("resources/day9.txt").expect("file day9.txt not found");
-        parse_input(&input)
+    fn parse(input: &str) -> Result<Vec<Mem>> {
+        parse_input(input)
     }
 
-    /// Go from left to right, and fill in all empty memory spaces from the end of the mem list.
-    /// Truncate the mem list of trailing empty memory blocks as we go.
-    fn part1(input: &Vec<Mem>) -> impl std::fmt::Display {
-        let mut memory = input.clone();
-        let mut search_idx = 1;
-
-        while search_idx < memory.len() {
-            if memory.last().unwrap().id.is_none() {
-                memory.remove(memory.len() - 1);
-                continue;
-            }
-
-            // search idx should only point at None (empty memory) values
-            if memory[search_idx].id.is_some() {
-                search_idx += 1;
-                continue;
-            }
-
-            let end_memory = *memory.last().unwrap();
-            let current = memory[search_idx];
-            if current.space > end_memory.space {
-                let moved = end_memory;
-                let remaining = Mem {
-                    id: None,
-                    space: current.space - end_memory.space
-                };
-                memory.remove(search_idx);
-                memory.insert(search_idx, remaining);
-                memory.insert(search_idx, moved);
-                memory.remove(memory.len() - 1);
+    /// Lay out every block of memory, then move blocks with two pointers:
+    /// the left pointer looks for empty blocks, the right pointer for file blocks to move into them.
+    fn part1(input: &Vec<Mem>) -> Result<impl IntoAnswer> {
+        let mut blocks = input.iter()
+            .flat_map(|mem| std::iter::repeat_n(mem.id, usize::try_from(mem.space).unwrap_or_default()))
+            .collect::<Vec<_>>();
+        let (mut left, mut right) = (0, blocks.len().saturating_sub(1));
+        while left < right {
+            if blocks[left].is_some() {
+                left += 1;
+            } else if blocks[right].is_none() {
+                right -= 1;
             } else {
-                let moved = Mem { id: end_memory.id, space: current.space };
-                memory.remove(search_idx);
-                memory.insert(search_idx, moved);
-                memory.remove(memory.len() - 1);
-                if current.space != end_memory.space {
-                    memory.push(Mem {
-                        id: end_memory.id,
-                        space: end_memory.space - current.space
-                    });
-                }
-            }
-            search_idx += 1;
-        }
-
-        let mut idx: i64 = 0;
-        let mut sum: i64 = 0;
-        for mem in memory {
-            let Some(mem_idx) = mem.id else {
-                panic!("Invalid Empty Memory block in final array");
-            };
-            for _ in 0 .. mem.space {
-                sum += idx * i64::from(mem_idx);
-                idx += 1;
+                blocks.swap(left, right);
             }
         }
-        sum
-        
+        Ok(blocks.iter()
+            .map_while(|&id| id)
+            .zip(0..)
+            .map(|(id, idx)| idx * i64::from(id))
+            .sum::<i64>())
     }
 
-    // Go from right to left, no truncation, so there will be empty memory blocks in the final result
-    fn part2(input: &Vec<Mem>) -> impl std::fmt::Display {
-        let mut memory = input.clone();
-        let mut end_ptr = memory.len() - 1;
-
-        while end_ptr > 0{
-            if memory[end_ptr].id.is_none() {
-                end_ptr -= 1;
-                continue;
+    /// Keep a min-heap of empty spans for each span size (spans are at most 9 blocks).
+    /// The leftmost span that fits a file is the leftmost one at the top of the heaps for that size or larger.
+    /// Files are moved from right to left, and only ever move left, so the space a file leaves behind is never reused.
+    fn part2(input: &Vec<Mem>) -> Result<impl IntoAnswer> {
+        let mut files = Vec::new();
+        let mut free_spans: [BinaryHeap<Reverse<Span>>; 10] = Default::default();
+        let mut location = 0;
+        for mem in input {
+            let span = Span::with_len(location, i64::from(mem.space));
+            match mem.id {
+                Some(id) => files.push((id, span)),
+                None if !span.is_empty() => free_spans[span_size(span)].push(Reverse(span)),
+                None => (),
             }
-            let empty = &memory[.. end_ptr].iter()
-                .enumerate()
-                .find(|(_, mem)| mem.id.is_none() && mem.space >= memory[end_ptr].space);
-            if let Some((empty_idx, empty)) = empty {
-                // copy to avoid memory borrow
-                let empty = **empty;
-                let empty_ptr = *empty_idx;
-
-                memory[empty_ptr] = memory[end_ptr];
-                memory[end_ptr].id = None;
-                if empty.space > memory[end_ptr].space {
-                    memory.insert(empty_ptr + 1, Mem {
-                        id: None,
-                        space: empty.space - memory[end_ptr].space,
-                    });
-                }
-            }
-            end_ptr -= 1;
+            location = span.end;
         }
 
-        let mut idx: i64 = 0;
-        let mut sum: i64 = 0;
-        for mem in memory {
-            if let Some(mem_idx) = mem.id {
-                for _ in 0 .. mem.space {
-                    sum += idx * i64::from(mem_idx);
-                    idx += 1;
-                }
-            } else {
-                idx += i64::from(mem.space);
+        let mut sum = 0;
+        for &(id, file) in files.iter().rev() {
+            let leftmost_span = (span_size(file) ..= 9)
+                .filter_map(|size| free_spans[size].peek().map(|&Reverse(free)| free))
+                .filter(|free| free.start < file.start)
+                .min();
+            let Some(free) = leftmost_span else {
+                sum += i64::from(id) * file.sum();
+                continue;
+            };
+            free_spans[span_size(free)].pop();
+            let moved = Span::with_len(free.start, file.len());
+            if let (_, Some(rest)) = free.subtract(moved) {
+                free_spans[span_size(rest)].push(Reverse(rest));
             }
+            sum += i64::from(id) * moved.sum();
         }
-        sum
+        Ok(sum)
     }
 
 }
 
+/// Which of the free span heaps a span goes in
+fn span_size(span: Span) -> usize {
+    usize::try_from(span.len()).unwrap_or_default()
+}
 
-fn parse_input(input: &str) -> Vec<Mem> {
+fn parse_input(input: &str) -> Result<Vec<Mem>> {
     let ints = input.chars().map(|c| 
         c.to_digit(10)
-            .unwrap()
-            .try_into().unwrap()
+            .ok_or_else(|| AocError::parse(format!("invalid digit {c}")))
+            .map(|digit| digit.try_into().unwrap())
         )
-        .collect::<Vec<i32>>();
+        .collect::<Result<Vec<i32>>>()?;
     let mut idx = 0;
     let mut empty = false;
     let mut memory = Vec::new();
@@ -173,25 +135,14 @@ fn parse_input(input: &str) -> Vec<Mem> {
         }
         empty = !empty;
     }
-    memory
+    Ok(memory)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use crate::aoc_test;
 
-    #[test]
-    fn test_part_1() {
-        let input = parse_input("2333133121414131402");
-        let result =  Day9::part1(&input);
-        assert_eq!("1928", result.to_string())
-    }
-
-    #[test]
-    fn test_part_2() {
-        let input = parse_input("2333133121414131402");
-        let result =  Day9::part2(&input);
-        assert_eq!("2858", result.to_string())
-    }
+    aoc_test!(day9, part1, example 1, "1928");
+    aoc_test!(day9, part2, example 1, "2858");
 
 }
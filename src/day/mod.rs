@@ -1,103 +1,692 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-
-use day1::Day1;
-use day2::Day2;
-use day3::Day3;
-use day4::Day4;
-use day5::Day5;
-use day6::Day6;
-use day7::Day7;
-use day8::Day8;
-use day9::Day9;
-use day10::Day10;
-use day11::Day11;
-use day12::Day12;
-use day13::Day13;
-use day14::Day14;
-use day15::Day15;
-use day16::Day16;
-use day17::Day17;
-use day18::Day18;
-use day19::Day19;
-use day20::Day20;
-use day21::Day21;
-use day22::Day22;
-use day23::Day23;
-use day24::Day24;
-use day25::Day25;
-
-use std::fmt::Display;
-use std::time::Instant;
-
-trait Day<T> {
-    fn read_input() -> T;
-    fn part1(input: &T) -> impl Display;
-    fn part2(input: &T) -> impl Display;
-
-    fn run() {
-        let input = Self::read_input();
+/// Registers each day module with its day number.
+/// Adding a new day only requires a single line here.
+macro_rules! days {
+    ($($day:literal => $module:ident :: $solver:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// Every implemented day, in calendar order
+        #[must_use]
+        pub fn registry() -> Vec<Box<dyn DayRunner>> {
+            vec![$(Box::new(Registered::<$module::$solver, _>::new($day))),*]
+        }
+    };
+}
+
+days! {
+    1 => day1::Day1,
+    2 => day2::Day2,
+    3 => day3::Day3,
+    4 => day4::Day4,
+    5 => day5::Day5,
+    6 => day6::Day6,
+    7 => day7::Day7,
+    8 => day8::Day8,
+    9 => day9::Day9,
+    10 => day10::Day10,
+    11 => day11::Day11,
+    12 => day12::Day12,
+    13 => day13::Day13,
+    14 => day14::Day14,
+    15 => day15::Day15,
+    16 => day16::Day16,
+    17 => day17::Day17,
+    18 => day18::Day18,
+    19 => day19::Day19,
+    20 => day20::Day20,
+    21 => day21::Day21,
+    22 => day22::Day22,
+    23 => day23::Day23,
+    24 => day24::Day24,
+    25 => day25::Day25,
+}
+
+use std::fmt::{Display, Write};
+use std::fs;
+use std::hint::black_box;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tracing::info_span;
+
+use crate::config::Config;
+use crate::util::error::{AocError, Result};
+use crate::util::parse::normalize;
+
+mod answer;
+pub use answer::Answer;
+mod context;
+pub use context::Context;
+
+/// What a part of a puzzle returns: a number or text that can be printed and turned into an [`Answer`]
+pub trait IntoAnswer: Display + Into<Answer> {}
+
+impl<T: Display + Into<Answer>> IntoAnswer for T {}
+
+/// A single day's puzzle: parse the input, then solve each part from the parsed input.
+///
+/// Malformed input is reported as [`AocError::Parse`] rather than a panic,
+/// and a part that cannot find an answer returns [`AocError::NoSolution`].
+pub trait Day<T> {
+    /// # Errors
+    /// If the input is not in the expected format
+    fn parse(input: &str) -> Result<T>;
+    /// # Errors
+    /// If there is no answer for the input
+    fn part1(input: &T) -> Result<impl IntoAnswer>;
+    /// # Errors
+    /// If there is no answer for the input
+    fn part2(input: &T) -> Result<impl IntoAnswer>;
+
+    /// The names of every algorithm that can solve this day, the default first.
+    /// Empty for days that only have one way of solving them.
+    const ALGORITHMS: &'static [&'static str] = &[];
+
+    /// The names of the parameters this day reads from its [`Context`], such as a grid size
+    /// that differs between the examples and the puzzle. Each has a default for the puzzle input.
+    const PARAMS: &'static [&'static str] = &[];
+
+    /// Solve part 1 with the algorithm and parameters in the context (see [`Day::ALGORITHMS`] and [`Day::PARAMS`]).
+    /// Days with more than one algorithm or any parameters override this, and leave it alone if part 1 uses neither.
+    ///
+    /// # Errors
+    /// If there is no answer for the input, or a parameter is out of range
+    fn part1_with(input: &T, _context: &Context) -> Result<Answer> {
+        Ok(Self::part1(input)?.into())
+    }
+
+    /// Solve part 2 with the algorithm and parameters in the context (see [`Day::ALGORITHMS`] and [`Day::PARAMS`]).
+    /// Days with more than one algorithm or any parameters override this, and leave it alone if part 2 uses neither.
+    ///
+    /// # Errors
+    /// If there is no answer for the input, or a parameter is out of range
+    fn part2_with(input: &T, _context: &Context) -> Result<Answer> {
+        Ok(Self::part2(input)?.into())
+    }
+
+    /// Read the puzzle input from a file and parse it
+    ///
+    /// # Errors
+    /// If the file cannot be read or the input is not in the expected format
+    fn read_input_from(path: &Path) -> Result<T> {
+        Self::parse(&read_input(path)?)
+    }
+
+    /// Read the input and solve both parts with the `context` (see [`Day::part1_with`]),
+    /// timing each step separately. Each step runs in its own tracing span.
+    ///
+    /// # Errors
+    /// If the input cannot be read or parsed, or either part fails
+    fn solve(path: &Path, context: &Context) -> Result<DaySolution> {
         let now = Instant::now();
-        let part1 = Self::part1(&input);
-        println!("Part 1: {part1} ({}ms)", now.elapsed().as_nanos() as f64 / 1_000_000.0);
+        let input = info_span!("parse").in_scope(|| Self::read_input_from(path))?;
+        let parse_time = now.elapsed();
+        let now = Instant::now();
+        let part1 = info_span!("part", part = 1).in_scope(|| Self::part1_with(&input, context))?;
+        let part1_time = now.elapsed();
+        let now = Instant::now();
+        let part2 = info_span!("part", part = 2).in_scope(|| Self::part2_with(&input, context))?;
+        let part2_time = now.elapsed();
+        Ok(DaySolution { part1, part2, parse_time, part1_time, part2_time })
+    }
+
+    /// Read the input and solve only the requested part (1 or 2).
+    /// Useful when one part is slow while iterating on the other.
+    ///
+    /// # Errors
+    /// If the input cannot be read or parsed, or the part fails
+    ///
+    /// # Panics
+    /// If `part` is not 1 or 2
+    fn run_part(path: &Path, part: u8, context: &Context) -> Result<(Answer, Duration)> {
+        let input = info_span!("parse").in_scope(|| Self::read_input_from(path))?;
         let now = Instant::now();
-        let part2 = Self::part2(&input);
-        println!("Part 2: {part2} ({}ms)", now.elapsed().as_nanos() as f64 / 1_000_000.0);
-    }
-}
-
-pub fn run(day: i32) {
-    println!("Day {day}:");
-    match day {
-        1 => Day1::run(),
-        2 => Day2::run(),
-        3 => Day3::run(),
-        4 => Day4::run(),
-        5 => Day5::run(),
-        6 => Day6::run(),
-        7 => Day7::run(),
-        8 => Day8::run(),
-        9 => Day9::run(),
-        10 => Day10::run(),
-        11 => Day11::run(),
-        12 => Day12::run(),
-        13 => Day13::run(),
-        14 => Day14::run(),
-        15 => Day15::run(),
-        16 => Day16::run(),
-        17 => Day17::run(),
-        18 => Day18::run(),
-        19 => Day19::run(),
-        20 => Day20::run(),
-        21 => Day21::run(),
-        22 => Day22::run(),
-        23 => Day23::run(),
-        24 => Day24::run(),
-        25 => Day25::run(),
-        _ => println!("Day {day} not implemented"),
+        let _span = info_span!("part", part).entered();
+        let answer = match part {
+            1 => Self::part1_with(&input, context)?,
+            2 => Self::part2_with(&input, context)?,
+            _ => panic!("Invalid part {part}"),
+        };
+        Ok((answer, now.elapsed()))
     }
+
+    /// Time parsing and each part `iterations` times, after an untimed warmup run.
+    /// Both parts reuse the same parsed input, so parsing is excluded from the part timings.
+    ///
+    /// # Errors
+    /// If the input cannot be read, or the warmup run of any step fails
+    fn bench(path: &Path, iterations: usize, context: &Context) -> Result<DayBench> {
+        let raw = read_input(path)?;
+        let parse = time_iterations(iterations, || black_box(Self::parse(&raw)).map(|_| ()))?;
+        let input = Self::parse(&raw)?;
+        let part1 = time_iterations(iterations, || black_box(Self::part1_with(&input, context)).map(|_| ()))?;
+        let part2 = time_iterations(iterations, || black_box(Self::part2_with(&input, context)).map(|_| ()))?;
+        Ok(DayBench { parse, part1, part2 })
+    }
+}
+
+/// Read a puzzle input file, normalized (see [`normalize`]) so parsers don't have to handle
+/// `\r\n` line endings or trailing blank lines
+///
+/// # Errors
+/// If the file cannot be read
+pub fn read_input(path: &Path) -> Result<String> {
+    let input = fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AocError::InputNotFound(path.to_path_buf()),
+        _ => AocError::Io(path.to_path_buf(), e),
+    })?;
+    Ok(normalize(&input))
+}
+
+/// Time `f` over `iterations` runs, after an untimed warmup run that must succeed
+fn time_iterations(iterations: usize, mut f: impl FnMut() -> Result<()>) -> Result<Vec<Duration>> {
+    f()?;
+    Ok((0 .. iterations)
+        .map(|_| {
+            let now = Instant::now();
+            let _ = f();
+            now.elapsed()
+        })
+        .collect())
+}
+
+/// The answers and timings from solving both parts of a day
+#[derive(Debug, Clone)]
+pub struct DaySolution {
+    pub part1: Answer,
+    pub part2: Answer,
+    pub parse_time: Duration,
+    pub part1_time: Duration,
+    pub part2_time: Duration,
 }
 
+impl DaySolution {
+    /// A single line JSON object with the answers and timings for the day.
+    /// Numeric answers are JSON numbers, and text answers are strings.
+    #[must_use]
+    pub fn to_json(&self, day: i32) -> String {
+        format!(
+            "{{\"day\":{day},\"part1\":{},\"part1_ms\":{},\"part2\":{},\"part2_ms\":{}}}",
+            json_answer(&self.part1),
+            as_millis(self.part1_time),
+            json_answer(&self.part2),
+            as_millis(self.part2_time),
+        )
+    }
+}
+
+/// How the runner writes results to stdout
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable output
+    #[default]
+    Text,
+    /// One JSON object per day, one per line
+    Json,
+}
+
+/// Quote and escape a string for use as a JSON value
+fn json_string(s: &str) -> String {
+    let mut json = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// An answer as a JSON value: a number, or a string for text
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Int(value) => value.to_string(),
+        Answer::Text(text) => json_string(text),
+    }
+}
+
+/// Every timing sample from benchmarking a day
+#[derive(Debug, Clone)]
+pub struct DayBench {
+    pub parse: Vec<Duration>,
+    pub part1: Vec<Duration>,
+    pub part2: Vec<Duration>,
+}
+
+/// The default location of the puzzle input for a day
+#[must_use]
+pub fn input_path(day: i32) -> PathBuf {
+    PathBuf::from(format!("resources/day{day}.txt"))
+}
+
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn as_millis(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1_000_000.0
+}
+
+/// Object safe handle to a registered [`Day`].
+/// Each day has a different input type, so the registry stores them behind this trait.
+/// Runners are `Send` so a day can be solved on a worker thread (see [`with_timeout`]).
+///
+/// Every method that solves the day takes a [`Context`], and fails with [`AocError::UnknownAlgorithm`]
+/// if its algorithm isn't one of the [`DayRunner::algorithms`], or [`AocError::UnknownParam`]
+/// if it sets anything other than the [`DayRunner::params`].
+pub trait DayRunner: Send {
+    fn day(&self) -> i32;
+    /// See [`Day::ALGORITHMS`]
+    fn algorithms(&self) -> &'static [&'static str];
+    /// See [`Day::PARAMS`]
+    fn params(&self) -> &'static [&'static str];
+    /// # Errors
+    /// See [`Day::solve`]
+    fn solve(&self, input: &Path, context: &Context) -> Result<DaySolution>;
+    /// # Errors
+    /// See [`Day::run_part`]
+    fn run_part(&self, input: &Path, part: u8, context: &Context) -> Result<(Answer, Duration)>;
+    /// # Errors
+    /// See [`Day::bench`]
+    fn bench(&self, input: &Path, iterations: usize, context: &Context) -> Result<DayBench>;
+    /// Parse the puzzle input (after [`normalize`]) and solve both parts without timing anything
+    ///
+    /// # Errors
+    /// If the input is not in the expected format, or either part fails
+    fn answers(&self, input: &str, context: &Context) -> Result<(Answer, Answer)>;
+    /// Parse the puzzle input (after [`normalize`]) without solving, to check that it is valid.
+    /// Malformed input is an error, never a panic.
+    ///
+    /// # Errors
+    /// If the input is not in the expected format
+    fn parse(&self, input: &str) -> Result<()>;
+}
+
+struct Registered<D, T> {
+    day: i32,
+    solver: PhantomData<fn() -> (D, T)>,
+}
+
+impl<D, T> Registered<D, T> {
+    fn new(day: i32) -> Self {
+        Self { day, solver: PhantomData }
+    }
+}
+
+impl<D: Day<T>, T> Registered<D, T> {
+    fn check_context(&self, context: &Context) -> Result<()> {
+        if let Some(name) = context.algorithm().filter(|name| !D::ALGORITHMS.contains(name)) {
+            return Err(AocError::UnknownAlgorithm(self.day, name.to_string(), D::ALGORITHMS));
+        }
+        match context.params.keys().find(|name| !D::PARAMS.contains(&name.as_str())) {
+            Some(name) => Err(AocError::UnknownParam(self.day, name.clone(), D::PARAMS)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<D: Day<T>, T> DayRunner for Registered<D, T> {
+    fn day(&self) -> i32 {
+        self.day
+    }
+
+    fn algorithms(&self) -> &'static [&'static str] {
+        D::ALGORITHMS
+    }
+
+    fn params(&self) -> &'static [&'static str] {
+        D::PARAMS
+    }
+
+    fn solve(&self, input: &Path, context: &Context) -> Result<DaySolution> {
+        self.check_context(context)?;
+        info_span!("day", day = self.day).in_scope(|| D::solve(input, context))
+    }
+
+    fn run_part(&self, input: &Path, part: u8, context: &Context) -> Result<(Answer, Duration)> {
+        self.check_context(context)?;
+        info_span!("day", day = self.day).in_scope(|| D::run_part(input, part, context))
+    }
+
+    fn bench(&self, input: &Path, iterations: usize, context: &Context) -> Result<DayBench> {
+        self.check_context(context)?;
+        D::bench(input, iterations, context)
+    }
+
+    fn answers(&self, input: &str, context: &Context) -> Result<(Answer, Answer)> {
+        self.check_context(context)?;
+        let input = D::parse(&normalize(input))?;
+        let part1 = D::part1_with(&input, context)?;
+        let part2 = D::part2_with(&input, context)?;
+        Ok((part1, part2))
+    }
+
+    fn parse(&self, input: &str) -> Result<()> {
+        D::parse(&normalize(input)).map(|_| ())
+    }
+}
+
+/// Find the registered day, or `None` if the day has not been implemented.
+#[must_use]
+pub fn find_day(day: i32) -> Option<Box<dyn DayRunner>> {
+    registry().into_iter().find(|runner| runner.day() == day)
+}
+
+/// Run `f` on a worker thread, giving up and returning `None` if it has not finished within `timeout`.
+/// With no timeout, `f` is run on the current thread.
+///
+/// The solvers have no way to be interrupted, so a timed out worker is abandoned
+/// and keeps running in the background until the program exits.
+///
+/// # Panics
+/// If `f` panics, the panic is resumed on the calling thread
+pub fn with_timeout<R: Send + 'static>(
+    timeout: Option<Duration>,
+    f: impl FnOnce() -> R + Send + 'static,
+) -> Option<R> {
+    let Some(timeout) = timeout else {
+        return Some(f());
+    };
+    let (sender, receiver) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        let _ = sender.send(f());
+    });
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Some(result),
+        Err(RecvTimeoutError::Timeout) => None,
+        // The worker dropped the sender without sending, so it must have panicked
+        Err(RecvTimeoutError::Disconnected) => match worker.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(()) => unreachable!("worker finished without a result"),
+        },
+    }
+}
+
+fn print_timed_out(day: i32, timeout: Option<Duration>, format: OutputFormat) {
+    let seconds = timeout.unwrap_or_default().as_secs_f64();
+    match format {
+        OutputFormat::Text => println!("Timed out after {seconds}s"),
+        OutputFormat::Json => println!("{{\"day\":{day},\"timed_out\":true}}"),
+    }
+}
+
+fn print_error(day: i32, error: &AocError, format: OutputFormat) {
+    match format {
+        OutputFormat::Text => eprintln!("{error}"),
+        OutputFormat::Json => println!("{{\"day\":{day},\"error\":{}}}", json_string(&error.to_string())),
+    }
+}
+
+/// Solve and print a single day, returning the solution or `None` if the day
+/// has not been implemented, failed, or did not finish within `timeout`.
+/// Uses the input file at `input` if provided, otherwise the default [`input_path`].
+pub fn run(
+    day: i32,
+    input: Option<&Path>,
+    context: &Context,
+    format: OutputFormat,
+    timeout: Option<Duration>,
+) -> Option<DaySolution> {
+    if format == OutputFormat::Text {
+        println!("Day {day}:");
+    }
+    let Some(runner) = find_day(day) else {
+        eprintln!("Day {day} not implemented");
+        return None;
+    };
+    let path = input.map_or_else(|| input_path(day), Path::to_path_buf);
+    let context = context.clone();
+    let Some(result) = with_timeout(timeout, move || runner.solve(&path, &context)) else {
+        print_timed_out(day, timeout, format);
+        return None;
+    };
+    let solution = match result {
+        Ok(solution) => solution,
+        Err(e) => {
+            print_error(day, &e, format);
+            return None;
+        }
+    };
+    match format {
+        OutputFormat::Text => print_solution(&solution),
+        OutputFormat::Json => println!("{}", solution.to_json(day)),
+    }
+    Some(solution)
+}
+
+/// Solve only one part of a single day, returning the answer and how long it took
+///
+/// # Errors
+/// If the day has not been implemented, or the input cannot be read or solved
+pub fn solve_part(day: i32, input: Option<&Path>, part: u8, context: &Context) -> Result<(Answer, Duration)> {
+    let runner = find_day(day).ok_or(AocError::NotImplemented(day))?;
+    runner.run_part(&input.map_or_else(|| input_path(day), Path::to_path_buf), part, context)
+}
+
+/// Solve and print only one part of a single day, giving up after `timeout`
+pub fn run_part(
+    day: i32,
+    input: Option<&Path>,
+    part: u8,
+    context: &Context,
+    format: OutputFormat,
+    timeout: Option<Duration>,
+) {
+    if format == OutputFormat::Text {
+        println!("Day {day}:");
+    }
+    let input = input.map(Path::to_path_buf);
+    let context = context.clone();
+    let Some(result) = with_timeout(timeout, move || solve_part(day, input.as_deref(), part, &context)) else {
+        print_timed_out(day, timeout, format);
+        return;
+    };
+    let (answer, time) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            print_error(day, &e, format);
+            return;
+        }
+    };
+    match format {
+        OutputFormat::Text => println!("Part {part}: {answer} ({}ms)", as_millis(time)),
+        OutputFormat::Json => println!(
+            "{{\"day\":{day},\"part{part}\":{},\"part{part}_ms\":{}}}",
+            json_answer(&answer),
+            as_millis(time),
+        ),
+    }
+}
+
+fn print_solution(solution: &DaySolution) {
+    println!("Part 1: {} ({}ms)", solution.part1, as_millis(solution.part1_time));
+    println!("Part 2: {} ({}ms)", solution.part2, as_millis(solution.part2_time));
+}
+
+/// Run every implemented day in order, then print a summary table
+/// of the answers and timings so the whole solution set can be checked at a glance.
+/// The summary table is skipped for JSON output, which already has one line per day.
+/// Days that do not finish within `timeout` are left out of the summary.
+/// Each day reads its input from, and is solved with the context in, the `config`.
+#[must_use]
+pub fn run_all(config: &Config, format: OutputFormat, timeout: Option<Duration>) -> Vec<(i32, DaySolution)> {
+    let solutions = registry().into_iter()
+        .filter_map(|runner| {
+            let day = runner.day();
+            run(day, Some(&config.input_path(day)), &config.context(day), format, timeout).map(|solution| (day, solution))
+        })
+        .collect::<Vec<_>>();
+    if format == OutputFormat::Text {
+        print_summary_table(&solutions);
+    }
+    solutions
+}
+
+fn print_summary_table(solutions: &[(i32, DaySolution)]) {
+    let part1_width = solutions.iter()
+        .map(|(_, s)| s.part1.to_string().len())
+        .chain([6])
+        .max()
+        .unwrap();
+    let part2_width = solutions.iter()
+        .map(|(_, s)| s.part2.to_string().len())
+        .chain([6])
+        .max()
+        .unwrap();
+
+    println!();
+    println!(
+        "Day | {:<part1_width$} | {:<part2_width$} | {:>10} | {:>10} | {:>10}",
+        "Part 1", "Part 2", "Parse ms", "Part 1 ms", "Part 2 ms",
+    );
+    let time_dashes = "-".repeat(10);
+    println!(
+        "----|-{}-|-{}-|-{time_dashes}-|-{time_dashes}-|-{time_dashes}",
+        "-".repeat(part1_width),
+        "-".repeat(part2_width),
+    );
+    let mut total = Duration::ZERO;
+    for (day, solution) in solutions {
+        println!(
+            "{day:>3} | {:<part1_width$} | {:<part2_width$} | {:>10.3} | {:>10.3} | {:>10.3}",
+            solution.part1,
+            solution.part2,
+            as_millis(solution.parse_time),
+            as_millis(solution.part1_time),
+            as_millis(solution.part2_time),
+        );
+        total += solution.parse_time + solution.part1_time + solution.part2_time;
+    }
+    println!("Total time: {:.3}ms", as_millis(total));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+    use crate::test_support::Rng;
+
+    #[test]
+    fn test_to_json() {
+        let solution = DaySolution {
+            part1: Answer::Int(7036),
+            part2: Answer::from("a\"b"),
+            parse_time: Duration::ZERO,
+            part1_time: Duration::from_micros(1200),
+            part2_time: Duration::from_micros(30_500),
+        };
+        assert_eq!(
+            r#"{"day":16,"part1":7036,"part1_ms":1.2,"part2":"a\"b","part2_ms":30.5}"#,
+            solution.to_json(16),
+        );
+    }
+
+    #[test]
+    fn test_registry() {
+        let registered = registry().iter().map(|runner| runner.day()).collect::<Vec<_>>();
+        assert_eq!((1 ..= 25).collect::<Vec<_>>(), registered);
+        let day1 = find_day(1).unwrap();
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+        assert_eq!((Answer::Int(11), Answer::Int(31)), day1.answers(input, &Context::default()).unwrap());
+        assert!(find_day(26).is_none());
+    }
+
+    #[test]
+    fn test_input_normalized() {
+        let day1 = find_day(1).unwrap();
+        let input = "3   4\r\n4   3\r\n2   5\r\n1   3\r\n3   9\r\n3   3\r\n\r\n";
+        assert_eq!((Answer::Int(11), Answer::Int(31)), day1.answers(input, &Context::default()).unwrap());
+        let day9 = find_day(9).unwrap();
+        assert_eq!((Answer::Int(1928), Answer::Int(2858)), day9.answers("2333133121414131402\r\n\n", &Context::default()).unwrap());
+    }
+
+    #[test]
+    fn test_unknown_algorithm() {
+        let day1 = find_day(1).unwrap();
+        let input = "3   4\n4   3";
+        assert!(day1.algorithms().is_empty());
+        assert!(day1.answers(input, &Context::default()).is_ok());
+        let fast = Context::default().with_algorithm(Some("fast"));
+        assert!(matches!(day1.answers(input, &fast), Err(AocError::UnknownAlgorithm(1, _, []))));
+        let day18 = find_day(18).unwrap();
+        for &algorithm in day18.algorithms() {
+            let context = Context::default().with_algorithm(Some(algorithm));
+            assert!(day18.answers("", &context).is_err_and(|e| !matches!(e, AocError::UnknownAlgorithm(..))));
+        }
+    }
+
+    #[test]
+    fn test_unknown_param() {
+        let day1 = find_day(1).unwrap();
+        let input = "3   4\n4   3";
+        assert!(day1.params().is_empty());
+        let size = Context::default().with_param("size", 7);
+        assert!(matches!(day1.answers(input, &size), Err(AocError::UnknownParam(1, _, []))));
+        let day18 = find_day(18).unwrap();
+        assert_eq!(&["size", "fallen"], day18.params());
+        assert!(day18.answers("", &size).is_err_and(|e| !matches!(e, AocError::UnknownParam(..))));
+        assert!(matches!(day18.answers("", &size.with_param("bytes", 12)), Err(AocError::UnknownParam(18, _, _))));
+    }
+
+    /// Malformed versions of a puzzle input: truncated, with a line removed, with one character replaced,
+    /// or random text using the same characters
+    fn malformed(input: &str, rng: &mut Rng) -> Vec<String> {
+        let boundaries = input.char_indices().map(|(idx, _)| idx).collect::<Vec<_>>();
+        let lines = input.lines().collect::<Vec<_>>();
+        let mut alphabet = input.chars().collect::<Vec<_>>();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        let mut malformed = vec![String::new(), format!(" \n{input}")];
+        malformed.extend((1 .. lines.len().min(40)).map(|count| lines[.. count].join("\n")));
+        for _ in 0 .. 20 {
+            let cut = boundaries[rng.below(boundaries.len())];
+            malformed.push(input[.. cut].to_string());
+
+            let mut without_line = lines.clone();
+            without_line.remove(rng.below(lines.len()));
+            malformed.push(without_line.join("\n"));
+
+            let replaced = boundaries[rng.below(boundaries.len())];
+            let replacement = ["", "0", "99999999999999999999", "-", ",", ":", "\n", "\n\n", " ", "x", "#", "é"][rng.below(12)];
+            let mut changed = input.to_string();
+            let end = replaced + changed[replaced ..].chars().next().map_or(0, char::len_utf8);
+            changed.replace_range(replaced .. end, replacement);
+            malformed.push(changed);
+        }
+        for _ in 0 .. 200 {
+            let len = rng.below(100);
+            malformed.push((0 .. len).map(|_| alphabet[rng.below(alphabet.len())]).collect());
+        }
+        malformed
+    }
+
+    #[test]
+    fn test_parse_malformed_input() {
+        let mut rng = Rng::new(97);
+        let mut panics = Vec::new();
+        for runner in registry() {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(input_path(runner.day()));
+            let Ok(input) = read_input(&path) else { continue };
+            for input in malformed(&input, &mut rng) {
+                if panic::catch_unwind(AssertUnwindSafe(|| runner.parse(&input))).is_err() {
+                    panics.push(format!("day {}: {:?}", runner.day(), input.chars().take(60).collect::<String>()));
+                }
+            }
+        }
+        assert!(panics.is_empty(), "parsing panicked for:\n{}", panics.join("\n"));
+    }
+
+    #[test]
+    fn test_with_timeout() {
+        assert_eq!(Some(4), with_timeout(None, || 2 + 2));
+        assert_eq!(Some(4), with_timeout(Some(Duration::from_secs(5)), || 2 + 2));
+        let slow = || thread::sleep(Duration::from_secs(5));
+        assert_eq!(None, with_timeout(Some(Duration::from_millis(10)), slow));
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use advent2024::day::{day17, input_path, Day};
+use advent2024::day17::{Computer, Step};
+
+const HELP: &str = "Commands:
+    s (or enter)  run the next instruction
+    o             run until the next output
+    c             run until the program halts
+    a <value>     restart the program with register a set to value
+    r             restart the program with the registers from the input
+    q             quit";
+
+/// Step through the day 17 program interactively, reading commands from stdin.
+/// Uses the input file at `input` if provided, otherwise the default [`input_path`].
+///
+/// # Errors
+/// If the day has no program to debug, or its input cannot be read
+pub fn run(day: i32, input: Option<&Path>) -> Result<(), String> {
+    if day != 17 {
+        return Err(format!("Day {day} has no program to debug"));
+    }
+    let path = input.map_or_else(|| input_path(day), Path::to_path_buf);
+    let (computer, program) = day17::Day17::read_input_from(&path).map_err(|e| e.to_string())?;
+    let mut session = Session::new(computer, program);
+
+    print!("{}", day17::disassemble(&session.program));
+    println!("{HELP}");
+    println!("{}", session.status());
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        match session.execute(&line.map_err(|e| e.to_string())?) {
+            Ok(true) => println!("{}", session.status()),
+            Ok(false) => return Ok(()),
+            Err(message) => println!("{message}"),
+        }
+    }
+}
+
+/// The program being debugged, and the registers to restart it with
+struct Session {
+    initial: Computer,
+    computer: Computer,
+    program: Vec<u64>,
+}
+
+impl Session {
+    fn new(computer: Computer, program: Vec<u64>) -> Self {
+        Self { initial: computer.clone(), computer, program }
+    }
+
+    /// Run a debugger command, returning false if the session should end
+    fn execute(&mut self, command: &str) -> Result<bool, String> {
+        match command.split_whitespace().collect::<Vec<_>>()[..] {
+            [] | ["s"] => {
+                if self.computer.step(&self.program).map_err(|e| e.to_string())? == Step::Halted {
+                    return Err("The program has halted".to_string());
+                }
+            }
+            ["o"] => {
+                if self.computer.run_to_output(&self.program).map_err(|e| e.to_string())?.is_none() {
+                    println!("The program halted without output");
+                }
+            }
+            ["c"] => self.computer.run(&self.program).map_err(|e| e.to_string())?,
+            ["a", value] => {
+                let value = value.parse().map_err(|_| format!("Invalid register value: {value}"))?;
+                self.computer = self.initial.clone();
+                self.computer.set_register_a(value);
+            }
+            ["r"] => self.computer = self.initial.clone(),
+            ["q"] => return Ok(false),
+            _ => return Err(HELP.to_string()),
+        }
+        Ok(true)
+    }
+
+    /// The registers, the output so far, and the next instruction to run
+    fn status(&self) -> String {
+        let (a, b, c) = self.computer.registers();
+        let ip = self.computer.instruction_pointer();
+        let output = self.computer.output().iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
+        let next = match self.program.get(ip ..) {
+            Some(instruction) if !instruction.is_empty() => {
+                format!("{ip}: {}", day17::disassemble_instruction(&instruction[.. instruction.len().min(2)]))
+            }
+            _ => "halted".to_string(),
+        };
+        format!("a={a} b={b} c={c} output=[{output}]\nnext {next}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let mut session = Session::new(Computer::new(4, 0, 0), vec![0, 1, 5, 4, 3, 0]);
+        assert_eq!("a=4 b=0 c=0 output=[]\nnext 0: a = a >> 1", session.status());
+        assert_eq!(Ok(true), session.execute(""));
+        assert_eq!(Ok(true), session.execute("o"));
+        assert_eq!("a=2 b=0 c=0 output=[2]\nnext 4: jnz 0", session.status());
+        assert_eq!(Ok(true), session.execute("c"));
+        assert_eq!("a=0 b=0 c=0 output=[2,1,0]\nnext halted", session.status());
+        assert!(session.execute("s").is_err());
+        assert_eq!(Ok(true), session.execute("a 8"));
+        assert_eq!("a=8 b=0 c=0 output=[]\nnext 0: a = a >> 1", session.status());
+        assert!(session.execute("a eight").is_err());
+        assert_eq!(Ok(false), session.execute("q"));
+    }
+}
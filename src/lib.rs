@@ -0,0 +1,63 @@
+//! Advent of Code 2024 solutions.
+//!
+//! Every day module is re-exported at the crate root, so a solver can be used directly
+//! through the [`Day`] trait, without going through the binary:
+//!
+//! ```
+//! use advent2024::{day1, Day};
+//!
+//! let input = day1::Day1::parse("3   4\n4   3\n2   5\n1   3\n3   9\n3   3").unwrap();
+//! assert_eq!("11", day1::Day1::part1(&input).unwrap().to_string());
+//! assert_eq!("31", day1::Day1::part2(&input).unwrap().to_string());
+//! ```
+#![warn(clippy::all, clippy::pedantic)]
+pub mod config;
+pub mod day;
+pub mod util;
+#[cfg(test)]
+pub mod test_support;
+
+pub use day::*;
+
+use util::error::AocError;
+
+/// Solve both parts of a day using the given puzzle input (the file contents, not a path).
+/// Numeric answers come back as [`Answer::Int`].
+///
+/// # Errors
+/// If the day has not been implemented, or the input cannot be parsed or solved
+pub fn solve_day(day: i32, input: &str) -> Result<(Answer, Answer), AocError> {
+    day::find_day(day)
+        .ok_or(AocError::NotImplemented(day))?
+        .answers(input, &Context::default())
+}
+
+/// Parse a day's puzzle input without solving it, to check that it is valid
+///
+/// # Errors
+/// If the day has not been implemented, or the input cannot be parsed
+pub fn parse_day(day: i32, input: &str) -> Result<(), AocError> {
+    day::find_day(day)
+        .ok_or(AocError::NotImplemented(day))?
+        .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_day() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+        assert_eq!((Answer::Int(11), Answer::Int(31)), solve_day(1, input).unwrap());
+        assert!(matches!(solve_day(26, input), Err(AocError::NotImplemented(26))));
+        assert!(matches!(solve_day(1, "3 x"), Err(AocError::Parse(_))));
+    }
+
+    #[test]
+    fn test_parse_day() {
+        assert!(parse_day(13, "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400").is_ok());
+        assert!(matches!(parse_day(13, "Button A: X+94, Y+34\nButton B: X+22, Y+67"), Err(AocError::Parse(_))));
+        assert!(matches!(parse_day(26, ""), Err(AocError::NotImplemented(26))));
+    }
+}
@@ -1,26 +1,250 @@
 #![warn(clippy::all, clippy::pedantic)]
-mod day;
-pub mod util;
+mod aoc;
+mod bench;
+mod cli;
+mod crosscheck;
+mod debugger;
+mod report;
+mod scaffold;
+mod tui;
+mod verify;
+mod visualize;
 
 use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process;
-use day::run;
+use cli::{Command, Options, Render};
+use advent2024::config::{Config, CONFIG_FILE};
+use advent2024::day::{find_day, registry, run, run_all, run_part, solve_part};
+use tracing::Level;
+use tracing_subscriber::fmt::format::FmtSpan;
 
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() == 1 {
-        println!("Usage - list each day you want to run");
-        println!("    to run days 1 and 15:");
-        println!("    cargo run 1 15");
+        print_usage();
         process::exit(0);
     }
-    let days = &args[1..];
-    for day in days {
-        if let Ok(day) = day.parse::<i32>() {
-            run(day);
+    let options = match Options::parse(&args[1..]) {
+        Ok(options) => options,
+        Err(message) => {
+            println!("{message}");
+            process::exit(1);
+        }
+    };
+    if options.verbose {
+        // Closing a span logs how long was spent in it (parse, part 1, part 2)
+        tracing_subscriber::fmt()
+            .with_max_level(Level::DEBUG)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(io::stderr)
+            .init();
+    }
+    let config = match load_config(&options) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("{e}");
+            process::exit(1);
+        }
+    };
+    if options.command == Command::Submit {
+        submit(&options, &config);
+        return;
+    }
+    if options.command == Command::Verify {
+        match verify::verify(&options.days, &config) {
+            Ok(true) => println!("All answers match"),
+            Ok(false) => process::exit(1),
+            Err(message) => {
+                println!("{message}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    if options.command == Command::NewDay {
+        if let Err(message) = scaffold::new_day(options.days[0]) {
+            println!("{message}");
+            process::exit(1);
+        }
+        return;
+    }
+    if options.command == Command::Debug {
+        if let Err(message) = debugger::run(options.days[0], Some(&input(&options, &config, options.days[0]))) {
+            println!("{message}");
+            process::exit(1);
+        }
+        return;
+    }
+    if options.command == Command::Tui {
+        if let Err(e) = tui::run() {
+            println!("Terminal error: {e}");
+            process::exit(1);
+        }
+        return;
+    }
+    if let Some(render) = options.render {
+        print_rendered(render, &options, &config);
+        return;
+    }
+    if options.crosscheck {
+        run_crosscheck(&options, &config);
+        return;
+    }
+    if let Some(iterations) = options.bench {
+        let runners = if options.all {
+            registry()
         } else {
-            println!("Invalid argument: {day}");
+            options.days.iter().filter_map(|&day| find_day(day)).collect()
+        };
+        bench::run_bench(&runners, iterations, &config);
+        return;
+    }
+    solve_days(&options, &config);
+}
+
+/// Solve the days (or every day for `--all`), and write a report if one was asked for
+fn solve_days(options: &Options, config: &Config) {
+    let format = options.format.unwrap_or(config.format);
+    let solutions = if options.all {
+        run_all(config, format, options.timeout)
+    } else {
+        let mut solutions = Vec::new();
+        for &day in &options.days {
+            let input = input(options, config, day);
+            let context = config.context(day);
+            if let Some(part) = options.part {
+                run_part(day, Some(&input), part, &context, format, options.timeout);
+            } else if let Some(solution) = run(day, Some(&input), &context, format, options.timeout) {
+                solutions.push((day, solution));
+            }
+        }
+        solutions
+    };
+    if let Some(format) = options.report {
+        match report::write_report(format, &solutions) {
+            Ok(path) => eprintln!("Report written to {}", path.display()),
+            Err(e) => {
+                println!("Failed to write report: {e}");
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn print_usage() {
+    println!("Usage - list each day you want to run");
+    println!("    to run days 1 and 15:");
+    println!("    cargo run 1 15");
+    println!("    to run every day and print a summary:");
+    println!("    cargo run -- --all");
+    println!("    to run only part 2 of day 6:");
+    println!("    cargo run -- 6 --part 2");
+    println!("    to run day 16 against a different input file:");
+    println!("    cargo run -- 16 --input path/to/input.txt");
+    println!("    to submit the answer for part 1 of day 3:");
+    println!("    cargo run -- submit 3 --part 1");
+    println!("    to check every day against the expected answers in answers.toml:");
+    println!("    cargo run -- verify");
+    println!("    to open an interactive dashboard of every day:");
+    println!("    cargo run -- tui");
+    println!("    to step through the day 17 program interactively:");
+    println!("    cargo run -- debug 17");
+    println!("    to start a new day 5 from the template:");
+    println!("    cargo run -- new-day 5");
+    println!("    to benchmark days 1 and 15 over 100 iterations (use --all for every day):");
+    println!("    cargo run --release -- 1 15 --bench 100");
+    println!("    to print machine readable results, one JSON object per day:");
+    println!("    cargo run -- --all --format json");
+    println!("    to write a markdown (or csv) table of every answer to the output directory:");
+    println!("    cargo run -- --all --report markdown");
+    println!("    to log how long each step takes, and debug events from the solvers:");
+    println!("    cargo run -- 16 --verbose");
+    println!("    to print the day 24 circuit as a Graphviz DOT graph:");
+    println!("    cargo run -- 24 --visualize > day24.dot");
+    println!("    to animate the day 6 guard in the terminal, showing each frame for 20ms:");
+    println!("    cargo run -- 6 --visualize --delay 20");
+    println!("    to save pictures of the day 14 christmas tree to output/day14:");
+    println!("    cargo run -- 14 --visualize");
+    println!("    to print the day 17 program as readable pseudo-code:");
+    println!("    cargo run -- 17 --disasm");
+    println!("    to save a picture of every best path through the day 16 maze to output/day16.svg:");
+    println!("    cargo run -- 16 --svg");
+    println!("    to solve day 18 with a different algorithm (an unknown name lists the choices):");
+    println!("    cargo run --release -- 18 --algo binary-search");
+    println!("    to check that every algorithm for day 18 gives the same answers:");
+    println!("    cargo run --release -- 18 --crosscheck");
+    println!("    to give up on any day that takes longer than 10 seconds:");
+    println!("    cargo run -- --all --timeout 10");
+    println!("    settings such as the input directory, output format, and each day's algorithm and parameters");
+    println!("    are read from advent.toml if it exists, see the README");
+}
+
+/// Read the config file, with `--algo` replacing the configured algorithm for the day it is given for
+fn load_config(options: &Options) -> Result<Config, String> {
+    let mut config = Config::load(Path::new(CONFIG_FILE)).map_err(|e| e.to_string())?;
+    if let Some(algorithm) = &options.algorithm {
+        config.days.entry(options.days[0]).or_default().algorithm = Some(algorithm.clone());
+    }
+    Ok(config)
+}
+
+/// The `--input` file, otherwise the day's input in the configured input directory
+fn input(options: &Options, config: &Config, day: i32) -> PathBuf {
+    options.input.clone().unwrap_or_else(|| config.input_path(day))
+}
+
+/// Crosscheck every day with more than one algorithm for `--all`, otherwise only the listed days
+fn run_crosscheck(options: &Options, config: &Config) {
+    let runners = if options.all {
+        registry().into_iter().filter(|runner| !runner.algorithms().is_empty()).collect()
+    } else {
+        options.days.iter().filter_map(|&day| find_day(day)).collect::<Vec<_>>()
+    };
+    if !crosscheck::crosscheck(&runners, options.input.as_deref(), config) {
+        process::exit(1);
+    }
+}
+
+fn print_rendered(render: Render, options: &Options, config: &Config) {
+    let day = options.days[0];
+    let input = input(options, config, day);
+    let input = Some(input.as_path());
+    let rendered = match render {
+        Render::Picture => {
+            let delay = options.delay.unwrap_or(visualize::DEFAULT_DELAY);
+            visualize::visualize(day, input, delay, &config.context(day))
+        }
+        Render::Disassembly => visualize::disassemble(day, input),
+        Render::Svg => visualize::svg(day, input),
+    };
+    match rendered {
+        Ok(text) => print!("{text}"),
+        Err(message) => {
+            println!("{message}");
+            process::exit(1);
+        }
+    }
+}
+
+fn submit(options: &Options, config: &Config) {
+    let day = options.days[0];
+    let part = options.part.unwrap();
+    let (answer, _) = match solve_part(day, Some(&input(options, config, day)), part, &config.context(day)) {
+        Ok(result) => result,
+        Err(e) => {
+            println!("{e}");
+            process::exit(1);
+        }
+    };
+    println!("Submitting {answer} for day {day} part {part}");
+    match aoc::submit(day, part, &answer.to_string(), &config.session_file) {
+        Ok(verdict) => println!("Result: {verdict}"),
+        Err(message) => {
+            println!("{message}");
+            process::exit(1);
         }
     }
 }
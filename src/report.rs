@@ -0,0 +1,106 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use advent2024::day::{as_millis, DaySolution};
+
+/// Reports are written to this directory
+pub const OUTPUT_DIR: &str = "output";
+
+/// File formats for the summary report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Csv,
+}
+
+impl ReportFormat {
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Markdown => "report.md",
+            Self::Csv => "report.csv",
+        }
+    }
+}
+
+/// Render a table of the answers and timings for each day
+#[must_use]
+pub fn render(format: ReportFormat, solutions: &[(i32, DaySolution)]) -> String {
+    let header = ["Day", "Part 1", "Part 2", "Parse ms", "Part 1 ms", "Part 2 ms"];
+    let rows = solutions.iter()
+        .map(|(day, solution)| [
+            day.to_string(),
+            solution.part1.to_string(),
+            solution.part2.to_string(),
+            format!("{:.3}", as_millis(solution.parse_time)),
+            format!("{:.3}", as_millis(solution.part1_time)),
+            format!("{:.3}", as_millis(solution.part2_time)),
+        ]);
+    let mut lines = Vec::new();
+    match format {
+        ReportFormat::Markdown => {
+            lines.push(format!("| {} |", header.join(" | ")));
+            lines.push(format!("|{}", "---|".repeat(header.len())));
+            lines.extend(rows.map(|row| format!("| {} |", row.join(" | "))));
+        }
+        ReportFormat::Csv => {
+            lines.push(header.join(","));
+            lines.extend(rows.map(|row| row.map(|field| csv_field(&field)).join(",")));
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Quote a CSV field if it contains a comma or quote (day 17 and 23 answers are comma separated)
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Write the report to the output directory, returning the path of the report file
+///
+/// # Errors
+/// If the output directory or report file cannot be written
+pub fn write_report(format: ReportFormat, solutions: &[(i32, DaySolution)]) -> io::Result<PathBuf> {
+    fs::create_dir_all(OUTPUT_DIR)?;
+    let path = PathBuf::from(OUTPUT_DIR).join(format.file_name());
+    fs::write(&path, render(format, solutions))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent2024::day::Answer;
+    use std::time::Duration;
+
+    fn solutions() -> Vec<(i32, DaySolution)> {
+        vec![(17, DaySolution {
+            part1: Answer::from("4,6,3"),
+            part2: Answer::Int(117_440),
+            parse_time: Duration::from_micros(10),
+            part1_time: Duration::from_micros(1500),
+            part2_time: Duration::from_millis(20),
+        })]
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let expected = "| Day | Part 1 | Part 2 | Parse ms | Part 1 ms | Part 2 ms |
+|---|---|---|---|---|---|
+| 17 | 4,6,3 | 117440 | 0.010 | 1.500 | 20.000 |
+";
+        assert_eq!(expected, render(ReportFormat::Markdown, &solutions()));
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let expected = "Day,Part 1,Part 2,Parse ms,Part 1 ms,Part 2 ms
+17,\"4,6,3\",117440,0.010,1.500,20.000
+";
+        assert_eq!(expected, render(ReportFormat::Csv, &solutions()));
+    }
+}
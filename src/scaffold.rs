@@ -0,0 +1,137 @@
+use std::fs;
+use std::path::Path;
+
+const DAY_DIR: &str = "src/day";
+/// Example inputs for the day tests, see `aoc_test!`
+const EXAMPLE_DIR: &str = "resources/examples";
+
+/// Generate the module for a new day from a template, register it in `src/day/mod.rs`,
+/// and create empty input and example files to paste the puzzle input and first example into.
+/// Must be run from the root of the repository.
+///
+/// # Errors
+/// If the day already exists, or any of the files cannot be read or written
+pub fn new_day(day: i32) -> Result<(), String> {
+    let module_path = Path::new(DAY_DIR).join(format!("day{day}.rs"));
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()));
+    }
+    let mod_path = Path::new(DAY_DIR).join("mod.rs");
+    let mod_rs = fs::read_to_string(&mod_path)
+        .map_err(|e| format!("Failed to read {}: {e}", mod_path.display()))?;
+    let mod_rs = register(&mod_rs, day)?;
+
+    fs::write(&module_path, template(day))
+        .map_err(|e| format!("Failed to write {}: {e}", module_path.display()))?;
+    fs::write(&mod_path, mod_rs)
+        .map_err(|e| format!("Failed to write {}: {e}", mod_path.display()))?;
+    println!("Created {}", module_path.display());
+
+    let example_path = Path::new(EXAMPLE_DIR).join(format!("day{day:02}_1.txt"));
+    for path in [advent2024::day::input_path(day), example_path] {
+        if !path.exists() {
+            fs::write(&path, "")
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+            println!("Created {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Add the day to the `days!` registry in `mod.rs`, keeping the days in order
+fn register(mod_rs: &str, day: i32) -> Result<String, String> {
+    let start = mod_rs.find("days! {").ok_or("Could not find the days! registry in mod.rs")?;
+    let end = start + mod_rs[start..].find("\n}").ok_or("Could not find the end of the days! registry")?;
+
+    let mut insert_at = end + 1;
+    let mut offset = start;
+    for line in mod_rs[start..end].split_inclusive('\n') {
+        let registered = line.split_once("=>")
+            .and_then(|(number, _)| number.trim().parse::<i32>().ok());
+        match registered {
+            Some(registered) if registered == day => return Err(format!("Day {day} is already registered")),
+            Some(registered) if registered > day => {
+                insert_at = offset;
+                break;
+            }
+            _ => (),
+        }
+        offset += line.len();
+    }
+    let mut mod_rs = mod_rs.to_string();
+    mod_rs.insert_str(insert_at, &format!("    {day} => day{day}::Day{day},\n"));
+    Ok(mod_rs)
+}
+
+/// A new day module. The tests fail until the example input and answers are filled in.
+fn template(day: i32) -> String {
+    format!(
+"use super::{{Day, IntoAnswer}};
+use crate::util::error::Result;
+
+/// Day {day}: 
+/// 
+/// Part 1: 
+/// 
+/// Part 2: 
+pub struct Day{day};
+
+impl Day<Vec<String>> for Day{day} {{
+    fn parse(input: &str) -> Result<Vec<String>> {{
+        parse_input(input)
+    }}
+
+    fn part1(_input: &Vec<String>) -> Result<impl IntoAnswer> {{
+        Ok(0)
+    }}
+
+    fn part2(_input: &Vec<String>) -> Result<impl IntoAnswer> {{
+        Ok(0)
+    }}
+}}
+
+fn parse_input(input: &str) -> Result<Vec<String>> {{
+    Ok(input.lines().map(ToString::to_string).collect())
+}}
+
+#[cfg(test)]
+mod tests {{
+    use crate::aoc_test;
+
+    aoc_test!(day{day}, part1, example 1, \"\");
+}}
+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOD_RS: &str = "macro_rules! days {}
+
+days! {
+    1 => day1::Day1,
+    2 => day2::Day2,
+    10 => day10::Day10,
+}
+
+use std::fs;
+";
+
+    #[test]
+    fn test_register() {
+        let registered = register(MOD_RS, 3).unwrap();
+        assert!(registered.contains("    2 => day2::Day2,\n    3 => day3::Day3,\n    10 => day10::Day10,\n"));
+        let registered = register(MOD_RS, 11).unwrap();
+        assert!(registered.contains("    10 => day10::Day10,\n    11 => day11::Day11,\n}\n"));
+        assert!(register(MOD_RS, 2).is_err());
+    }
+
+    #[test]
+    fn test_template() {
+        let template = template(26);
+        assert!(template.contains("pub struct Day26;"));
+        assert!(template.contains("impl Day<Vec<String>> for Day26 {"));
+        assert!(template.contains("    aoc_test!(day26, part1, example 1, \"\");"));
+    }
+}
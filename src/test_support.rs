@@ -0,0 +1,131 @@
+//! Helpers for the day tests: example inputs are kept in `resources/examples/dayNN_N.txt`
+//! (ex: `day16_2.txt` is the second example for day 16), so a day can have as many as the puzzle gives,
+//! along with any small inputs made up for a test.
+//!
+//! There are also random generators for the grid utilities (on [`Rng`]), and [`check`] to test
+//! a property against many random cases.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use crate::day::read_input;
+use crate::util::grid::{Point, Vec2d};
+pub use crate::util::random::Rng;
+
+/// The path to example `n` for a day
+#[must_use]
+pub fn example_path(day: i32, n: u32) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "resources", "examples", &format!("day{day:02}_{n}.txt")].iter().collect()
+}
+
+/// Example `n` for a day, normalized the same way as puzzle inputs
+///
+/// # Panics
+/// If there is no such example file
+#[must_use]
+pub fn example(day: i32, n: u32) -> String {
+    let path = example_path(day, n);
+    read_input(&path).unwrap_or_else(|e| panic!("example {n} for day {day}: {e}"))
+}
+
+/// The day number from a day module name like `day16`
+///
+/// # Panics
+/// If the name isn't `day` followed by a number
+#[must_use]
+pub fn day_number(module: &str) -> i32 {
+    module.strip_prefix("day")
+        .and_then(|day| day.parse().ok())
+        .unwrap_or_else(|| panic!("expected a day module like day16, found {module}"))
+}
+
+/// Declare a test that solves one part of a day for its example files, and checks each answer:
+///
+/// ```ignore
+/// aoc_test!(day16, part1, example 1, "7036"; example 2, "11048");
+/// ```
+///
+/// The part is solved through the registry, just like the runner does, and the answer is compared
+/// as an [`Answer`](crate::day::Answer), so `"7036"` checks for the number 7036.
+/// The test is named `test_part_1` or `test_part_2`, so list every example for a part in one `aoc_test!`.
+#[macro_export]
+macro_rules! aoc_test {
+    (@test $name:ident, $part:literal, $day:ident, $(example $n:literal, $expected:literal);+ $(;)?) => {
+        #[test]
+        fn $name() {
+            let day = $crate::test_support::day_number(stringify!($day));
+            let runner = $crate::day::find_day(day).expect("the day is registered");
+            $(
+                let path = $crate::test_support::example_path(day, $n);
+                let (answer, _) = runner.run_part(&path, $part, &$crate::day::Context::default())
+                    .unwrap_or_else(|e| panic!("part {} example {}: {e}", $part, $n));
+                let Ok(expected) = $expected.parse::<$crate::day::Answer>();
+                assert_eq!(expected, answer, "part {} example {}", $part, $n);
+            )+
+        }
+    };
+    ($day:ident, part1, $($examples:tt)+) => {
+        $crate::aoc_test!(@test test_part_1, 1, $day, $($examples)+);
+    };
+    ($day:ident, part2, $($examples:tt)+) => {
+        $crate::aoc_test!(@test test_part_2, 2, $day, $($examples)+);
+    };
+}
+
+/// Generators for the grid utilities
+impl Rng {
+    /// A random grid from 1 by 1 up to `max_size` by `max_size`, with `cell` picking each value
+    pub fn grid<T: Clone>(&mut self, max_size: i32, mut cell: impl FnMut(&mut Self) -> T) -> Vec2d<T> {
+        let (line_len, height) = (self.range(1, max_size + 1), self.range(1, max_size + 1));
+        Vec2d {
+            grid: (0 .. line_len * height).map(|_| cell(self)).collect(),
+            line_len,
+        }
+    }
+
+    /// A random point with both coordinates in `-bound ..= bound`
+    pub fn point(&mut self, bound: i32) -> Point {
+        Point::new(self.range(-bound, bound + 1), self.range(-bound, bound + 1))
+    }
+
+    /// A random point inside the grid
+    pub fn point_in<T: Clone>(&mut self, grid: &Vec2d<T>) -> Point {
+        Point::new(self.range(0, grid.line_len), self.range(0, grid.height()))
+    }
+}
+
+/// Run `property` for `cases` random cases. Each case gets its own seed, which is
+/// printed if the case fails, so it can be replayed with `property(&mut Rng::new(seed))`.
+///
+/// # Panics
+/// If the property fails for any case
+pub fn check(cases: u64, property: impl Fn(&mut Rng)) {
+    for seed in 0 .. cases {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| property(&mut Rng::new(seed))));
+        if let Err(e) = result {
+            eprintln!("property failed for seed {seed}");
+            panic::resume_unwind(e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        assert!(example_path(9, 1).ends_with("resources/examples/day09_1.txt"));
+        assert_eq!("2333133121414131402", example(9, 1));
+        assert_eq!(16, day_number("day16"));
+    }
+
+    #[test]
+    fn test_generators() {
+        let mut rng = Rng::new(7);
+        let grid = rng.grid(5, |rng| rng.chance(1, 2));
+        assert!((1 ..= 5).contains(&grid.line_len) && (1 ..= 5).contains(&grid.height()));
+        assert!(grid.in_bounds(rng.point_in(&grid)));
+        assert!(rng.point(2).chebyshev_distance(&Point::new(0, 0)) <= 2);
+    }
+}
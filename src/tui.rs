@@ -0,0 +1,260 @@
+use std::collections::VecDeque;
+use std::env;
+use std::io;
+use std::process::{Command, Output};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+use advent2024::day::registry;
+
+/// How long to wait for a key press before checking on running days
+const TICK: Duration = Duration::from_millis(100);
+
+/// Interactive dashboard listing every day with its answers and timings.
+///
+/// Each day is solved by running this binary again as a child process, one day at a time.
+/// This keeps the terminal intact when a day prints (day 14 draws its christmas tree)
+/// or panics, and the full output is shown in the pane next to the list.
+///
+/// * `Up`/`Down` (or `k`/`j`) select a day
+/// * `Enter` (or `r`) run the selected day
+/// * `a` run every day
+/// * `PageUp`/`PageDown` scroll the output pane
+/// * `q` (or `Esc`) quit
+///
+/// # Errors
+/// If the terminal cannot be drawn to
+pub fn run() -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = App::new().run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    NotRun,
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+struct DayEntry {
+    day: i32,
+    status: Status,
+    part1: Option<(String, f64)>,
+    part2: Option<(String, f64)>,
+    output: String,
+}
+
+struct App {
+    entries: Vec<DayEntry>,
+    table: TableState,
+    /// Index of each entry waiting to run, in order
+    queue: VecDeque<usize>,
+    running: bool,
+    scroll: u16,
+    sender: Sender<(usize, io::Result<Output>)>,
+    receiver: Receiver<(usize, io::Result<Output>)>,
+}
+
+impl App {
+    fn new() -> Self {
+        let entries = registry().iter()
+            .map(|runner| DayEntry {
+                day: runner.day(),
+                status: Status::NotRun,
+                part1: None,
+                part2: None,
+                output: String::new(),
+            })
+            .collect();
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            entries,
+            table: TableState::default().with_selected(0),
+            queue: VecDeque::new(),
+            running: false,
+            scroll: 0,
+            sender,
+            receiver,
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            self.check_finished();
+            self.start_next()?;
+            terminal.draw(|frame| self.draw(frame))?;
+            if !event::poll(TICK)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.select(1),
+                KeyCode::Enter | KeyCode::Char('r') => {
+                    if let Some(index) = self.table.selected() {
+                        self.enqueue(index);
+                    }
+                }
+                KeyCode::Char('a') => (0 .. self.entries.len()).for_each(|index| self.enqueue(index)),
+                KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+                KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+                _ => (),
+            }
+        }
+    }
+
+    fn select(&mut self, offset: isize) {
+        let current = self.table.selected().unwrap_or(0);
+        let selected = current.saturating_add_signed(offset).min(self.entries.len() - 1);
+        if selected != current {
+            self.table.select(Some(selected));
+            self.scroll = 0;
+        }
+    }
+
+    fn enqueue(&mut self, index: usize) {
+        let entry = &mut self.entries[index];
+        if matches!(entry.status, Status::Queued | Status::Running) {
+            return;
+        }
+        entry.status = Status::Queued;
+        self.queue.push_back(index);
+    }
+
+    /// Days are run one at a time so they don't compete with each other and skew the timings
+    fn start_next(&mut self) -> io::Result<()> {
+        if self.running {
+            return Ok(());
+        }
+        let Some(index) = self.queue.pop_front() else {
+            return Ok(());
+        };
+        let exe = env::current_exe()?;
+        let day = self.entries[index].day;
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let output = Command::new(exe).arg(day.to_string()).output();
+            let _ = sender.send((index, output));
+        });
+        self.entries[index].status = Status::Running;
+        self.running = true;
+        Ok(())
+    }
+
+    fn check_finished(&mut self) {
+        while let Ok((index, output)) = self.receiver.try_recv() {
+            self.running = false;
+            let entry = &mut self.entries[index];
+            match output {
+                Ok(output) => {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    entry.output = format!("{stdout}{stderr}");
+                    entry.part1 = None;
+                    entry.part2 = None;
+                    for (part, answer, ms) in stdout.lines().filter_map(parse_part_line) {
+                        match part {
+                            1 => entry.part1 = Some((answer, ms)),
+                            _ => entry.part2 = Some((answer, ms)),
+                        }
+                    }
+                    entry.status = if output.status.success() { Status::Done } else { Status::Failed };
+                }
+                Err(e) => {
+                    entry.output = format!("Failed to run day {}: {e}", entry.day);
+                    entry.status = Status::Failed;
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+        let [list, output] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(main);
+
+        let header = Row::new(["Day", "Status", "Part 1", "Part 2", "Total ms"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = self.entries.iter().map(|entry| {
+            let answer = |part: &Option<(String, f64)>| part.as_ref().map_or(String::new(), |(a, _)| a.clone());
+            let total = match (&entry.part1, &entry.part2) {
+                (Some((_, ms1)), Some((_, ms2))) => format!("{:>10.3}", ms1 + ms2),
+                _ => String::new(),
+            };
+            let (status, color) = match entry.status {
+                Status::NotRun => ("", Color::Reset),
+                Status::Queued => ("queued", Color::DarkGray),
+                Status::Running => ("running", Color::Yellow),
+                Status::Done => ("done", Color::Green),
+                Status::Failed => ("failed", Color::Red),
+            };
+            Row::new([
+                format!("{:>3}", entry.day),
+                status.to_string(),
+                answer(&entry.part1),
+                answer(&entry.part2),
+                total,
+            ])
+            .style(Style::new().fg(color))
+        });
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(7),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(10),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title("Advent of Code 2024"))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, list, &mut self.table);
+
+        let selected = &self.entries[self.table.selected().unwrap_or(0)];
+        let pane = Paragraph::new(selected.output.as_str())
+            .block(Block::bordered().title(format!("Day {} output", selected.day)))
+            .scroll((self.scroll, 0));
+        frame.render_widget(pane, output);
+
+        let keys = "↑/↓ select  enter run  a run all  pgup/pgdn scroll  q quit";
+        frame.render_widget(Line::from(keys).style(Style::new().fg(Color::DarkGray)), help);
+    }
+}
+
+/// Parse a `Part 1: <answer> (<time>ms)` line printed by the runner
+fn parse_part_line(line: &str) -> Option<(u8, String, f64)> {
+    let (part, rest) = line.strip_prefix("Part ")?.split_once(": ")?;
+    let (answer, time) = rest.rsplit_once(" (")?;
+    let ms = time.strip_suffix("ms)")?.parse().ok()?;
+    Some((part.parse().ok()?, answer.to_string(), ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_part_line() {
+        assert_eq!(Some((1, "6,1,6,4,2".to_string(), 0.017)), parse_part_line("Part 1: 6,1,6,4,2 (0.017ms)"));
+        assert_eq!(Some((2, "85432".to_string(), 1234.5)), parse_part_line("Part 2: 85432 (1234.5ms)"));
+        assert_eq!(None, parse_part_line("Day 16:"));
+        assert_eq!(None, parse_part_line("Part 1: no timing"));
+    }
+}
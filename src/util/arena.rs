@@ -0,0 +1,143 @@
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+
+use super::hash::FxHashMap;
+
+/// The states found by a search, each stored once in an arena and referred to by its index (id),
+/// along with the states each one was reached from.
+///
+/// A state can have more than one parent (ex: reached by several equally good paths).
+/// Parents are kept as linked lists of ids in one shared `Vec`,
+/// so tracking them doesn't allocate per state or clone any states.
+#[derive(Debug, Clone)]
+pub struct SearchTree<S> {
+    states: Vec<S>,
+    ids: FxHashMap<S, usize>,
+    /// the first link in each state's list of parents
+    heads: Vec<Option<usize>>,
+    /// a parent, and the next link in the same list
+    links: Vec<(usize, Option<usize>)>,
+}
+
+impl<S> Default for SearchTree<S> {
+    fn default() -> Self {
+        Self { states: Vec::new(), ids: FxHashMap::default(), heads: Vec::new(), links: Vec::new() }
+    }
+}
+
+impl<S: Clone + Eq + Hash> SearchTree<S> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of states found
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// The id of `state`, adding it (with no parents) if it's new.
+    /// Returns true along with the id if the state was added.
+    pub fn insert(&mut self, state: S) -> (usize, bool) {
+        match self.ids.entry(state) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let id = self.states.len();
+                self.states.push(entry.key().clone());
+                self.heads.push(None);
+                entry.insert(id);
+                (id, true)
+            }
+        }
+    }
+
+    /// The id of `state`, or `None` if it hasn't been found
+    #[must_use]
+    pub fn id(&self, state: &S) -> Option<usize> {
+        self.ids.get(state).copied()
+    }
+
+    /// # Panics
+    /// If there is no state with this id
+    #[must_use]
+    pub fn state(&self, id: usize) -> &S {
+        &self.states[id]
+    }
+
+    /// Make `parent` the only parent of `id`
+    pub fn set_parent(&mut self, id: usize, parent: usize) {
+        self.heads[id] = None;
+        self.add_parent(id, parent);
+    }
+
+    /// Add another parent for `id`
+    pub fn add_parent(&mut self, id: usize, parent: usize) {
+        self.links.push((parent, self.heads[id]));
+        self.heads[id] = Some(self.links.len() - 1);
+    }
+
+    /// The parents of `id`, most recently added first
+    pub fn parents(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.heads[id], |&link| self.links[link].1).map(|link| self.links[link].0)
+    }
+
+    /// The ids from a state with no parents to `id` (including both ends),
+    /// following the first parent added (since it was last set) at each step
+    #[must_use]
+    pub fn path_to(&self, id: usize) -> Vec<usize> {
+        let mut path = vec![id];
+        let mut current = id;
+        while let Some(parent) = self.parents(current).last() {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Every id that is one of `ids` or reaches one of them by following any parents.
+    /// Returned as a flag for each id, true if it's included.
+    #[must_use]
+    pub fn ancestors(&self, ids: impl IntoIterator<Item = usize>) -> Vec<bool> {
+        let mut included = vec![false; self.len()];
+        let mut stack = ids.into_iter().collect::<Vec<_>>();
+        while let Some(id) = stack.pop() {
+            if !std::mem::replace(&mut included[id], true) {
+                stack.extend(self.parents(id).filter(|&parent| !included[parent]));
+            }
+        }
+        included
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_tree() {
+        let mut tree = SearchTree::new();
+        let ids = ['a', 'b', 'c', 'd', 'e'].map(|state| tree.insert(state).0);
+        assert_eq!((ids[1], false), tree.insert('b'));
+        assert_eq!(Some(ids[2]), tree.id(&'c'));
+        assert_eq!(None, tree.id(&'z'));
+
+        // a -> b -> d, a -> c -> d, e on its own
+        tree.set_parent(ids[1], ids[0]);
+        tree.set_parent(ids[2], ids[4]);
+        tree.set_parent(ids[2], ids[0]);
+        tree.set_parent(ids[3], ids[1]);
+        tree.add_parent(ids[3], ids[2]);
+        assert_eq!(vec![ids[2], ids[1]], tree.parents(ids[3]).collect::<Vec<_>>());
+        assert_eq!(vec![ids[0], ids[1], ids[3]], tree.path_to(ids[3]));
+        assert_eq!(vec![ids[0], ids[2]], tree.path_to(ids[2]));
+        assert_eq!(vec![true, true, true, true, false], tree.ancestors([ids[3]]));
+        assert_eq!('d', *tree.state(ids[3]));
+    }
+}
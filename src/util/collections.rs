@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Sub};
+
+/// A multiset that counts how many times each item has been added.
+/// Items with a count of zero are never stored, so two counters are equal when every count matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Self { counts: HashMap::new() }
+    }
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    pub fn add_n(&mut self, item: T, n: usize) {
+        if n > 0 {
+            *self.counts.entry(item).or_default() += n;
+        }
+    }
+
+    /// Remove up to `n` of `item`, returning how many were actually removed
+    pub fn remove_n(&mut self, item: &T, n: usize) -> usize {
+        let Some(count) = self.counts.get_mut(item) else {
+            return 0;
+        };
+        let removed = n.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+        removed
+    }
+
+    /// How many times `item` has been added, zero if it never was
+    #[must_use]
+    pub fn count(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// The number of distinct items
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of every count
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Each distinct item with its count, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// Each distinct item with its count, from most to least common.
+    /// Items with the same count are ordered smallest first.
+    #[must_use]
+    pub fn most_common(&self) -> Vec<(&T, usize)>
+    where
+        T: Ord,
+    {
+        let mut items = self.iter().collect::<Vec<_>>();
+        items.sort_unstable_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        items
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(items);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.add(item);
+        }
+    }
+}
+
+impl<T: Eq + Hash> AddAssign for Counter<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (item, count) in rhs.counts {
+            self.add_n(item, count);
+        }
+    }
+}
+
+/// Combine the counts of both counters
+impl<T: Eq + Hash> Add for Counter<T> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+/// Take away the counts of `rhs`, dropping any item whose count would go below zero
+impl<T: Eq + Hash> Sub for Counter<T> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        for (item, count) in rhs.counts {
+            self.remove_n(&item, count);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter() {
+        let mut counter = "abracadabra".chars().collect::<Counter<_>>();
+        assert_eq!(5, counter.count(&'a'));
+        assert_eq!(0, counter.count(&'z'));
+        assert_eq!(5, counter.len());
+        assert_eq!(11, counter.total());
+        assert_eq!(vec![(&'a', 5), (&'b', 2), (&'r', 2), (&'c', 1), (&'d', 1)], counter.most_common());
+
+        assert_eq!(2, counter.remove_n(&'b', 3));
+        assert_eq!(0, counter.count(&'b'));
+        assert_eq!(4, counter.len());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = [1, 1, 2, 3].into_iter().collect::<Counter<_>>();
+        let b = [1, 2, 2, 4].into_iter().collect::<Counter<_>>();
+        let sum = a.clone() + b.clone();
+        assert_eq!(3, sum.count(&1));
+        assert_eq!(3, sum.count(&2));
+        assert_eq!(8, sum.total());
+
+        let difference = a - b;
+        assert_eq!([1, 3].into_iter().collect::<Counter<_>>(), difference);
+    }
+}
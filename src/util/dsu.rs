@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use super::grid::{Directions, Vec2d};
+
+/// Union-Find (disjoint set union) over the elements `0..len`,
+/// using path compression and union by rank.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+}
+
+impl DisjointSet {
+    /// Every element starts out in a set by itself
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0 .. len).collect(),
+            ranks: vec![0; len],
+        }
+    }
+
+    /// Group the cells of a grid, using the grid indexes as elements.
+    /// Neighboring cells (up, down, left, or right) are in the same set when `connected` is true for them.
+    #[must_use]
+    pub fn from_grid<T: Clone>(grid: &Vec2d<T>, connected: impl Fn(&T, &T) -> bool) -> Self {
+        let mut set = Self::new(grid.grid.len());
+        for (idx, cell) in grid.grid.iter().enumerate() {
+            let point = grid.idx_to_point(idx);
+            for direction in [Directions::Right, Directions::Down] {
+                if let Some(next) = grid.next_point(point, direction) {
+                    if connected(cell, &grid[next]) {
+                        set.union(idx, grid.point_to_idx(next));
+                    }
+                }
+            }
+        }
+        set
+    }
+
+    /// The representative element of the set containing `element`
+    ///
+    /// # Panics
+    /// If `element` is not less than the size of the disjoint set
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = element;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merge the sets containing `a` and `b`.
+    /// Returns false if they were already in the same set.
+    ///
+    /// # Panics
+    /// If either element is not less than the size of the disjoint set
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (parent, child) = if self.ranks[a] < self.ranks[b] { (b, a) } else { (a, b) };
+        self.parents[child] = parent;
+        if self.ranks[parent] == self.ranks[child] {
+            self.ranks[parent] += 1;
+        }
+        true
+    }
+
+    /// # Panics
+    /// If either element is not less than the size of the disjoint set
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// All of the sets, each listing its elements in increasing order
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for element in 0 .. self.parents.len() {
+            let root = self.find(element);
+            groups.entry(root).or_default().push(element);
+        }
+        let mut groups = groups.into_values().collect::<Vec<_>>();
+        groups.sort_unstable();
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut set = DisjointSet::new(6);
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert!(!set.union(0, 2));
+        assert!(set.connected(0, 3));
+        assert!(!set.connected(0, 4));
+        assert_eq!(vec![vec![0, 1, 2, 3], vec![4], vec![5]], set.groups());
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let grid = Vec2d {
+            grid: "AAB\nBAB\nBBA".lines().flat_map(str::chars).collect(),
+            line_len: 3,
+        };
+        let mut set = DisjointSet::from_grid(&grid, |a, b| a == b);
+        assert_eq!(vec![vec![0, 1, 4], vec![2, 5], vec![3, 6, 7], vec![8]], set.groups());
+    }
+}
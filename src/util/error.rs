@@ -0,0 +1,96 @@
+use std::fmt::Display;
+use std::io;
+use std::num::ParseIntError;
+use std::path::PathBuf;
+
+/// Everything that can go wrong while reading, parsing, or solving a puzzle
+#[derive(Debug)]
+pub enum AocError {
+    /// The puzzle input file does not exist
+    InputNotFound(PathBuf),
+    /// The puzzle input file exists but could not be read
+    Io(PathBuf, io::Error),
+    /// The puzzle input is not in the expected format
+    Parse(String),
+    /// The solver could not find an answer for this input
+    NoSolution(String),
+    /// There is no solver for the day
+    NotImplemented(i32),
+    /// The day has no algorithm with this name. Holds the day, the name, and the names the day does have.
+    UnknownAlgorithm(i32, String, &'static [&'static str]),
+    /// The day has no parameter with this name. Holds the day, the name, and the names the day does have.
+    UnknownParam(i32, String, &'static [&'static str]),
+    /// The config file or a parameter value is invalid
+    Config(String),
+}
+
+pub type Result<T, E = AocError> = std::result::Result<T, E>;
+
+impl AocError {
+    #[must_use]
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::Parse(message.into())
+    }
+
+    #[must_use]
+    pub fn no_solution(message: impl Into<String>) -> Self {
+        Self::NoSolution(message.into())
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InputNotFound(path) => write!(
+                f,
+                "{} not found — save the puzzle input from adventofcode.com there or pass --input",
+                path.display(),
+            ),
+            Self::Io(path, e) => write!(f, "Failed to read {}: {e}", path.display()),
+            Self::Parse(message) => write!(f, "Invalid puzzle input: {message}"),
+            Self::NoSolution(message) => write!(f, "No solution found: {message}"),
+            Self::NotImplemented(day) => write!(f, "Day {day} not implemented"),
+            Self::UnknownAlgorithm(day, name, []) => write!(f, "Day {day} has no algorithm named {name}, it only has one"),
+            Self::UnknownAlgorithm(day, name, known) => {
+                write!(f, "Day {day} has no algorithm named {name}, choose from {}", known.join(", "))
+            }
+            Self::UnknownParam(day, name, []) => write!(f, "Day {day} has no parameter named {name}, it has none"),
+            Self::UnknownParam(day, name, known) => {
+                write!(f, "Day {day} has no parameter named {name}, choose from {}", known.join(", "))
+            }
+            Self::Config(message) => write!(f, "Invalid configuration: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseIntError> for AocError {
+    fn from(e: ParseIntError) -> Self {
+        Self::Parse(format!("invalid number ({e})"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = AocError::InputNotFound(PathBuf::from("resources/day21.txt"));
+        assert!(error.to_string().starts_with("resources/day21.txt not found"));
+        let error = AocError::from("12a".parse::<i32>().unwrap_err());
+        assert_eq!("Invalid puzzle input: invalid number (invalid digit found in string)", error.to_string());
+        let error = AocError::UnknownAlgorithm(18, "fast".to_string(), &["reverse-union", "binary-search"]);
+        assert_eq!("Day 18 has no algorithm named fast, choose from reverse-union, binary-search", error.to_string());
+        let error = AocError::UnknownParam(20, "threshold".to_string(), &["min_saving"]);
+        assert_eq!("Day 20 has no parameter named threshold, choose from min_saving", error.to_string());
+    }
+}
@@ -0,0 +1,134 @@
+use std::cmp::Ordering;
+
+use super::grid::Point64;
+use super::math::gcd;
+
+/// Twice the area of the polygon with these vertices, in order (either direction).
+/// The shoelace formula gives double the area as an integer, so it is kept exact here.
+/// The polygon is closed automatically; don't repeat the first vertex at the end.
+#[must_use]
+pub fn double_area(vertices: &[Point64]) -> i64 {
+    edges(vertices)
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum::<i64>()
+        .abs()
+}
+
+/// The area of the polygon with these vertices. See [`double_area`] for an exact value.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn area(vertices: &[Point64]) -> f64 {
+    double_area(vertices) as f64 / 2.0
+}
+
+/// The number of lattice points that lie on the edges of the polygon
+#[must_use]
+pub fn boundary_points(vertices: &[Point64]) -> i64 {
+    edges(vertices)
+        .map(|(a, b)| gcd((b.x - a.x).abs(), (b.y - a.y).abs()))
+        .sum()
+}
+
+/// The number of lattice points strictly inside the polygon, using Pick's theorem:
+/// `A = i + b/2 - 1`, where `A` is the area and `b` the number of boundary points.
+///
+/// Add [`boundary_points`] to count every grid cell a dig path or pipe loop covers.
+#[must_use]
+pub fn interior_points(vertices: &[Point64]) -> i64 {
+    // 2A - b is always even, so this division is exact
+    (double_area(vertices) - boundary_points(vertices)) / 2 + 1
+}
+
+/// True if the segment `a1 -> a2` and the segment `b1 -> b2` share at least one point.
+/// Touching at an end point or overlapping along a line both count.
+#[must_use]
+pub fn segments_intersect((a1, a2): (Point64, Point64), (b1, b2): (Point64, Point64)) -> bool {
+    let d1 = orientation(b1, b2, a1);
+    let d2 = orientation(b1, b2, a2);
+    let d3 = orientation(a1, a2, b1);
+    let d4 = orientation(a1, a2, b2);
+    let collinear = Ordering::Equal;
+    if d1 != d2 && d3 != d4 && ![d1, d2, d3, d4].contains(&collinear) {
+        // the end points of each segment are on opposite sides of the other segment
+        return true;
+    }
+    (d1 == collinear && on_segment(b1, b2, a1))
+        || (d2 == collinear && on_segment(b1, b2, a2))
+        || (d3 == collinear && on_segment(a1, a2, b1))
+        || (d4 == collinear && on_segment(a1, a2, b2))
+}
+
+/// Each pair of consecutive vertices, wrapping around from the last vertex to the first
+fn edges(vertices: &[Point64]) -> impl Iterator<Item = (Point64, Point64)> + '_ {
+    vertices.iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+/// Which side of the line `a -> b` the point `c` is on. `Equal` means the three points are collinear.
+/// Uses `i128` so the cross product can't overflow.
+fn orientation(a: Point64, b: Point64, c: Point64) -> Ordering {
+    let cross = i128::from(b.x - a.x) * i128::from(c.y - a.y) - i128::from(b.y - a.y) * i128::from(c.x - a.x);
+    cross.cmp(&0)
+}
+
+/// For a point `c` already known to be collinear with `a -> b`, true if it lies between them
+fn on_segment(a: Point64, b: Point64, c: Point64) -> bool {
+    (a.x.min(b.x) ..= a.x.max(b.x)).contains(&c.x) && (a.y.min(b.y) ..= a.y.max(b.y)).contains(&c.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(i64, i64)]) -> Vec<Point64> {
+        points.iter().map(|&(x, y)| Point64::new(x, y)).collect()
+    }
+
+    #[test]
+    fn test_area() {
+        let square = polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]);
+        assert_eq!(32, double_area(&square));
+        assert_eq!(16, boundary_points(&square));
+        assert_eq!(9, interior_points(&square));
+
+        // Clockwise and counter-clockwise give the same answer
+        let triangle = polygon(&[(0, 0), (0, 3), (3, 0)]);
+        let reversed = triangle.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(9, double_area(&triangle));
+        assert_eq!(9, double_area(&reversed));
+        assert!((area(&triangle) - 4.5).abs() < f64::EPSILON);
+        assert_eq!(9, boundary_points(&triangle));
+        assert_eq!(1, interior_points(&triangle));
+    }
+
+    #[test]
+    fn test_region_cells() {
+        // The outline of an L shaped garden plot of 3 + 1 cells, traced along the cell corners.
+        // Shoelace area matches the number of cells in the region.
+        let l_shape = polygon(&[(0, 0), (3, 0), (3, 1), (1, 1), (1, 2), (0, 2)]);
+        assert_eq!(8, double_area(&l_shape));
+        assert_eq!(10, boundary_points(&l_shape));
+
+        // Tracing the cell centers instead: interior + boundary points is the number of cells
+        let dig_path = polygon(&[(0, 0), (6, 0), (6, 5), (0, 5)]);
+        assert_eq!(42, interior_points(&dig_path) + boundary_points(&dig_path));
+    }
+
+    #[test]
+    fn test_segments_intersect() {
+        let p = |x, y| Point64::new(x, y);
+        assert!(segments_intersect((p(0, 0), p(4, 4)), (p(0, 4), p(4, 0))));
+        assert!(!segments_intersect((p(0, 0), p(1, 1)), (p(0, 4), p(4, 0))));
+        // touching at an end point
+        assert!(segments_intersect((p(0, 0), p(2, 2)), (p(2, 2), p(5, 0))));
+        // collinear, overlapping and not
+        assert!(segments_intersect((p(0, 0), p(4, 0)), (p(3, 0), p(8, 0))));
+        assert!(!segments_intersect((p(0, 0), p(2, 0)), (p(3, 0), p(8, 0))));
+        // parallel
+        assert!(!segments_intersect((p(0, 0), p(4, 0)), (p(0, 1), p(4, 1))));
+        // large coordinates don't overflow
+        let big = 3_000_000_000_000;
+        assert!(segments_intersect((p(-big, -big), p(big, big)), (p(-big, big), p(big, -big))));
+    }
+}
@@ -0,0 +1,444 @@
+use std::hash::{Hash, Hasher};
+
+use super::dsu::DisjointSet;
+use super::hash::FxHashMap;
+
+/// A set of vertices `0..len`, stored as a bitset so unions, intersections and differences are bitwise operations.
+/// The set grows to fit any vertex inserted into it.
+#[derive(Debug, Clone)]
+pub struct VertexSet {
+    words: Vec<u64>,
+}
+
+impl VertexSet {
+    /// An empty set that can hold the vertices `0..len`
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self { words: vec![0; len.div_ceil(64)] }
+    }
+
+    /// A set containing every vertex `0..len`
+    #[must_use]
+    pub fn full(len: usize) -> Self {
+        let mut set = Self::new(len);
+        for vertex in 0 .. len {
+            set.insert(vertex);
+        }
+        set
+    }
+
+    /// Add `vertex`, returning false if it was already in the set
+    pub fn insert(&mut self, vertex: usize) -> bool {
+        let was_set = self.contains(vertex);
+        if self.words.len() <= vertex / 64 {
+            self.words.resize(vertex / 64 + 1, 0);
+        }
+        self.words[vertex / 64] |= 1 << (vertex % 64);
+        !was_set
+    }
+
+    /// Remove `vertex`, returning true if it was in the set
+    pub fn remove(&mut self, vertex: usize) -> bool {
+        let was_set = self.contains(vertex);
+        if was_set {
+            self.words[vertex / 64] &= !(1 << (vertex % 64));
+        }
+        was_set
+    }
+
+    #[must_use]
+    pub fn contains(&self, vertex: usize) -> bool {
+        self.words.get(vertex / 64).is_some_and(|word| word & (1 << (vertex % 64)) != 0)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// The vertices in the set, from lowest to highest
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, &word)| {
+            // pop the lowest set bit until the word is empty
+            std::iter::successors(Some(word).filter(|&w| w != 0), |&w| Some(w & (w - 1)).filter(|&w| w != 0))
+                .map(move |w| idx * 64 + w.trailing_zeros() as usize)
+        })
+    }
+
+    /// The vertices in both sets
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// The vertices in either set
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// The vertices in this set that are not in `other`
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    fn combine(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let word = |words: &[u64], idx: usize| words.get(idx).copied().unwrap_or_default();
+        let words = (0 .. self.words.len().max(other.words.len()))
+            .map(|idx| op(word(&self.words, idx), word(&other.words, idx)))
+            .collect();
+        Self { words }
+    }
+
+    /// Remove every vertex up to and including `vertex`
+    fn remove_through(&mut self, vertex: usize) {
+        let word = vertex / 64;
+        for low in self.words.iter_mut().take(word) {
+            *low = 0;
+        }
+        if let Some(last) = self.words.get_mut(word) {
+            *last &= u64::MAX.checked_shl(u32::try_from(vertex % 64 + 1).unwrap_or(64)).unwrap_or(0);
+        }
+    }
+
+    /// The words without any empty words at the end, which make no difference to the set
+    fn trimmed(&self) -> &[u64] {
+        let len = self.words.iter().rposition(|&word| word != 0).map_or(0, |idx| idx + 1);
+        &self.words[.. len]
+    }
+}
+
+impl FromIterator<usize> for VertexSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(vertices: I) -> Self {
+        let mut set = Self::new(0);
+        for vertex in vertices {
+            set.insert(vertex);
+        }
+        set
+    }
+}
+
+impl PartialEq for VertexSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.trimmed() == other.trimmed()
+    }
+}
+
+impl Eq for VertexSet {}
+
+impl Hash for VertexSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.trimmed().hash(state);
+    }
+}
+
+/// An undirected graph, with each node (ex: a name or a point) interned as a vertex index `0..len`
+/// in the order it was added. The algorithms work on vertex indexes; use [`Graph::node`] to get the node back.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    ids: FxHashMap<N, usize>,
+    neighbors: Vec<VertexSet>,
+}
+
+impl<N> Default for Graph<N> {
+    fn default() -> Self {
+        Self { nodes: Vec::new(), ids: FxHashMap::default(), neighbors: Vec::new() }
+    }
+}
+
+impl<N: Clone + Eq + Hash> FromIterator<(N, N)> for Graph<N> {
+    /// Build from a list of edges
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(edges: I) -> Self {
+        let mut graph = Self::new();
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    /// A graph with no nodes
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The vertex for `node`, adding it with no edges if it's new
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&vertex) = self.ids.get(&node) {
+            return vertex;
+        }
+        let vertex = self.nodes.len();
+        self.ids.insert(node.clone(), vertex);
+        self.nodes.push(node);
+        self.neighbors.push(VertexSet::new(0));
+        vertex
+    }
+
+    /// Connect `a` and `b` in both directions, adding either node if it's new
+    pub fn add_edge(&mut self, a: N, b: N) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        self.neighbors[a].insert(b);
+        self.neighbors[b].insert(a);
+    }
+
+    /// The vertex for `node`, or `None` if it isn't in the graph
+    #[must_use]
+    pub fn vertex(&self, node: &N) -> Option<usize> {
+        self.ids.get(node).copied()
+    }
+
+    /// # Panics
+    /// If the vertex is not in the graph
+    #[must_use]
+    pub fn node(&self, vertex: usize) -> &N {
+        &self.nodes[vertex]
+    }
+
+    /// Every node, indexed by vertex
+    #[must_use]
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// # Panics
+    /// If the vertex is not in the graph
+    #[must_use]
+    pub fn neighbors(&self, vertex: usize) -> &VertexSet {
+        &self.neighbors[vertex]
+    }
+
+    /// # Panics
+    /// If either vertex is not in the graph
+    #[must_use]
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.neighbors[a].contains(b)
+    }
+
+    /// The groups of vertices that can reach each other through edges, each in increasing order
+    #[must_use]
+    pub fn connected_components(&self) -> Vec<Vec<usize>> {
+        let mut components = DisjointSet::new(self.len());
+        for (a, neighbors) in self.neighbors.iter().enumerate() {
+            for b in neighbors.iter().filter(|&b| b > a) {
+                components.union(a, b);
+            }
+        }
+        components.groups()
+    }
+
+    /// Every set of three vertices that are all connected to each other, each listed once in increasing order
+    #[must_use]
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        self.cliques_of_size(3)
+            .into_iter()
+            .map(|clique| [clique[0], clique[1], clique[2]])
+            .collect()
+    }
+
+    /// Every set of `size` vertices that are all connected to each other,
+    /// each listed once in increasing order
+    #[must_use]
+    pub fn cliques_of_size(&self, size: usize) -> Vec<Vec<usize>> {
+        let mut cliques = Vec::new();
+        self.extend_cliques(&mut Vec::new(), &VertexSet::full(self.len()), size, &mut cliques);
+        cliques
+    }
+
+    /// Every set of `size` vertices that includes `vertex` and are all connected to each other,
+    /// each listed once in increasing order
+    ///
+    /// # Panics
+    /// If the vertex is not in the graph
+    #[must_use]
+    pub fn cliques_containing(&self, vertex: usize, size: usize) -> Vec<Vec<usize>> {
+        if size == 0 {
+            return Vec::new();
+        }
+        let mut cliques = Vec::new();
+        self.extend_cliques(&mut vec![vertex], &self.neighbors[vertex], size, &mut cliques);
+        for clique in &mut cliques {
+            clique.sort_unstable();
+        }
+        cliques
+    }
+
+    /// Add every way of growing `clique` to `size` vertices using the `candidates`
+    /// (vertices connected to everything in the clique). Vertices are added in increasing order,
+    /// so each clique is only found once.
+    fn extend_cliques(&self, clique: &mut Vec<usize>, candidates: &VertexSet, size: usize, cliques: &mut Vec<Vec<usize>>) {
+        if clique.len() == size {
+            cliques.push(clique.clone());
+            return;
+        }
+        if clique.len() + candidates.len() < size {
+            return;
+        }
+        for vertex in candidates.iter() {
+            clique.push(vertex);
+            if clique.len() == size {
+                cliques.push(clique.clone());
+            } else {
+                let mut next = candidates.intersection(&self.neighbors[vertex]);
+                next.remove_through(vertex);
+                self.extend_cliques(clique, &next, size, cliques);
+            }
+            clique.pop();
+        }
+    }
+
+    /// The largest set of vertices that are all connected to each other, from lowest to highest vertex.
+    /// When there is a tie, one of the largest cliques is returned.
+    #[must_use]
+    pub fn largest_clique(&self) -> Vec<usize> {
+        let mut largest = VertexSet::new(0);
+        self.bron_kerbosch(
+            &VertexSet::new(0),
+            VertexSet::full(self.len()),
+            VertexSet::new(0),
+            &mut largest,
+        );
+        largest.iter().collect()
+    }
+
+    /// <https://en.wikipedia.org/wiki/Bron%E2%80%93Kerbosch_algorithm>
+    ///
+    /// Bron Kerbosch finds the maximal cliques of a graph using recursive backtracking.
+    /// This variant chooses the vertex with the most edges as a 'pivot' to reduce the number of recursive calls.
+    /// `clique` is the clique so far, `candidates` the vertices that can extend it,
+    /// and `excluded` the vertices that were already tried.
+    fn bron_kerbosch(&self, clique: &VertexSet, mut candidates: VertexSet, mut excluded: VertexSet, largest: &mut VertexSet) {
+        if candidates.is_empty() {
+            if excluded.is_empty() && clique.len() > largest.len() {
+                *largest = clique.clone();
+            }
+            return;
+        }
+        let Some(pivot) = candidates.union(&excluded).iter().max_by_key(|&v| self.neighbors[v].len()) else {
+            return;
+        };
+        for v in candidates.difference(&self.neighbors[pivot]).iter().collect::<Vec<_>>() {
+            let mut next_clique = clique.clone();
+            next_clique.insert(v);
+            self.bron_kerbosch(
+                &next_clique,
+                candidates.intersection(&self.neighbors[v]),
+                excluded.intersection(&self.neighbors[v]),
+                largest,
+            );
+            candidates.remove(v);
+            excluded.insert(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vertex_set() {
+        let mut set = VertexSet::new(130);
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(set.insert(129));
+        assert!(!set.insert(3));
+        assert_eq!(vec![3, 129], set.iter().collect::<Vec<_>>());
+        assert_eq!(2, set.len());
+
+        let full = VertexSet::full(130);
+        assert_eq!(130, full.len());
+        assert_eq!(128, full.difference(&set).len());
+        assert_eq!(set, full.intersection(&set));
+        assert!(set.remove(129));
+        assert!(!set.contains(129));
+        assert!(!set.contains(500));
+    }
+
+    #[test]
+    fn test_vertex_set_growth() {
+        let mut set = VertexSet::new(0);
+        assert!(set.insert(200));
+        assert!(!set.remove(500));
+        assert_eq!(vec![200], set.union(&VertexSet::new(10)).iter().collect::<Vec<_>>());
+        assert!(set.remove(200));
+        assert_eq!(VertexSet::new(10), set);
+
+        let mut set = [3, 63, 64, 100, 130].into_iter().collect::<VertexSet>();
+        set.remove_through(63);
+        assert_eq!(vec![64, 100, 130], set.iter().collect::<Vec<_>>());
+        set.remove_through(100);
+        assert_eq!(vec![130], set.iter().collect::<Vec<_>>());
+    }
+
+    /// a square 0-1-2-3 with both diagonals, and a triangle 4-5-6 joined to 0 through 4
+    fn example() -> Graph<usize> {
+        [(0, 1), (1, 2), (2, 3), (3, 0), (0, 2), (4, 5), (5, 6), (6, 4), (0, 4), (1, 3)].into_iter().collect()
+    }
+
+    #[test]
+    fn test_largest_clique() {
+        let graph = example();
+        assert_eq!(vec![0, 1, 2, 3], graph.largest_clique());
+        assert_eq!(vec![1, 2, 3, 4], graph.neighbors(0).iter().collect::<Vec<_>>());
+        assert!(Graph::<usize>::new().largest_clique().is_empty());
+    }
+
+    #[test]
+    fn test_interning() {
+        let mut graph = Graph::new();
+        graph.add_edge("kh", "tc");
+        graph.add_edge("qp", "kh");
+        assert_eq!(3, graph.len());
+        assert_eq!(Some(1), graph.vertex(&"tc"));
+        assert_eq!(&"qp", graph.node(2));
+        assert!(graph.connected(0, 2));
+        assert!(!graph.connected(1, 2));
+        assert_eq!(0, graph.add_node("kh"));
+        assert_eq!(3, graph.add_node("ub"));
+        assert_eq!(&["kh", "tc", "qp", "ub"], graph.nodes());
+    }
+
+    #[test]
+    fn test_components_and_triangles() {
+        let mut graph = example();
+        graph.add_edge(7, 8);
+        graph.add_node(9);
+        assert_eq!(vec![vec![0, 1, 2, 3, 4, 5, 6], vec![7, 8], vec![9]], graph.connected_components());
+        assert_eq!(
+            vec![[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3], [4, 5, 6]],
+            graph.triangles(),
+        );
+    }
+
+    #[test]
+    fn test_cliques() {
+        let graph = example();
+        assert_eq!(vec![vec![0, 1, 2, 3]], graph.cliques_of_size(4));
+        assert!(graph.cliques_of_size(5).is_empty());
+        assert_eq!(10, graph.cliques_of_size(2).len());
+        assert_eq!(7, graph.cliques_of_size(1).len());
+        assert_eq!(vec![vec![4, 5, 6]], graph.cliques_containing(4, 3));
+        assert_eq!(vec![vec![0, 1, 2], vec![0, 1, 3], vec![0, 2, 3]], graph.cliques_containing(0, 3));
+        assert_eq!(vec![vec![0, 4], vec![4, 5], vec![4, 6]], graph.cliques_containing(4, 2));
+        assert_eq!(vec![vec![5]], graph.cliques_containing(5, 1));
+    }
+}
@@ -0,0 +1,159 @@
+use super::{Grid, GridMut, Point, Vec2d};
+
+/// A grid of booleans packed 64 to a word. Cheap to clone and clear, which makes it a good fit
+/// for walls and visited markers in searches that run many times.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    bits: Vec<u64>,
+    line_len: i32,
+    height: i32,
+}
+
+impl BitGrid {
+    /// A `line_len` by `height` grid with every point unset
+    ///
+    /// # Panics
+    /// If either dimension is negative
+    #[must_use]
+    pub fn new(line_len: i32, height: i32) -> Self {
+        let len = usize::try_from(line_len * height).expect("Invalid grid size");
+        Self {
+            bits: vec![0; len.div_ceil(64)],
+            line_len,
+            height,
+        }
+    }
+
+    /// The same size as `grid`, with the points where `predicate` is true set
+    ///
+    /// # Panics
+    /// If the grid has more rows than fit in an `i32`
+    #[must_use]
+    pub fn from_grid<T: Clone>(grid: &Vec2d<T>, predicate: impl Fn(&T) -> bool) -> Self {
+        let height = i32::try_from(grid.rows().count()).expect("Invalid grid size");
+        let mut bits = Self::new(grid.line_len, height);
+        for (point, value) in grid.iter_with_points() {
+            if predicate(value) {
+                bits.insert(point);
+            }
+        }
+        bits
+    }
+
+    #[must_use]
+    pub fn line_len(&self) -> i32 {
+        self.line_len
+    }
+
+    #[must_use]
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    #[must_use]
+    pub fn in_bounds(&self, point: Point) -> bool {
+        point.x >= 0 && point.y >= 0 && point.x < self.line_len && point.y < self.height
+    }
+
+    /// # Panics
+    /// If the point is outside the grid
+    #[must_use]
+    pub fn point_to_idx(&self, point: Point) -> usize {
+        assert!(self.in_bounds(point), "Invalid Point -> index");
+        usize::try_from(point.y * self.line_len + point.x).expect("Invalid Point -> index")
+    }
+
+    /// True if `point` is in bounds and set
+    #[must_use]
+    pub fn contains(&self, point: Point) -> bool {
+        self.in_bounds(point) && {
+            let idx = self.point_to_idx(point);
+            self.bits[idx / 64] & (1 << (idx % 64)) != 0
+        }
+    }
+
+    /// Set `point`, returning false if it was already set
+    ///
+    /// # Panics
+    /// If the point is outside the grid
+    pub fn insert(&mut self, point: Point) -> bool {
+        let idx = self.point_to_idx(point);
+        let mask = 1 << (idx % 64);
+        let was_set = self.bits[idx / 64] & mask != 0;
+        self.bits[idx / 64] |= mask;
+        !was_set
+    }
+
+    /// Unset `point`, returning true if it was set
+    ///
+    /// # Panics
+    /// If the point is outside the grid
+    pub fn remove(&mut self, point: Point) -> bool {
+        let idx = self.point_to_idx(point);
+        let mask = 1 << (idx % 64);
+        let was_set = self.bits[idx / 64] & mask != 0;
+        self.bits[idx / 64] &= !mask;
+        was_set
+    }
+
+    /// Unset every point
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+
+    /// The number of points that are set
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.bits.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+impl Grid for BitGrid {
+    type Cell = bool;
+
+    fn get(&self, point: Point) -> Option<&bool> {
+        self.in_bounds(point).then_some(if self.contains(point) { &true } else { &false })
+    }
+
+    fn in_bounds(&self, point: Point) -> bool {
+        BitGrid::in_bounds(self, point)
+    }
+
+    fn iter_points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0 .. self.height).flat_map(|y| (0 .. self.line_len).map(move |x| Point::new(x, y)))
+    }
+}
+
+impl GridMut for BitGrid {
+    /// # Panics
+    /// If the point is outside the grid
+    fn set(&mut self, point: Point, value: bool) -> Option<bool> {
+        Some(if value { !self.insert(point) } else { self.remove(point) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::new(10, 10);
+        assert!(grid.insert(Point::new(9, 9)));
+        assert!(!grid.insert(Point::new(9, 9)));
+        assert!(grid.contains(Point::new(9, 9)));
+        assert!(!grid.contains(Point::new(10, 9)));
+        assert_eq!(Some(false), grid.set(Point::new(0, 6), true));
+        assert_eq!(Some(&true), grid.get(Point::new(0, 6)));
+        assert_eq!(None, grid.get(Point::new(-1, 6)));
+        assert_eq!(2, grid.count());
+        assert!(grid.remove(Point::new(9, 9)));
+        assert_eq!(Some(Point::new(0, 6)), grid.find(&true));
+        grid.clear();
+        assert_eq!(0, grid.count());
+
+        let walls = BitGrid::from_grid(&"#..\n..#".parse::<Vec2d<char>>().unwrap(), |&c| c == '#');
+        assert_eq!(2, walls.count());
+        assert!(walls.contains(Point::new(2, 1)));
+    }
+}
@@ -0,0 +1,45 @@
+//! 2D grids, the points that index them, and the directions to move between points
+
+mod bit;
+mod point;
+mod sparse;
+mod vec2d;
+mod view;
+
+pub use bit::BitGrid;
+pub use point::{Coordinate, Point, Point64};
+pub use sparse::SparseGrid;
+pub use vec2d::{Directions, Vec2d};
+pub use view::GridView;
+
+/// The operations shared by the dense [`Vec2d`] and the [`SparseGrid`],
+/// so a solver can be written once and swap representations
+pub trait Grid {
+    type Cell;
+
+    /// The value at `point`, or `None` if there isn't one
+    fn get(&self, point: Point) -> Option<&Self::Cell>;
+
+    fn in_bounds(&self, point: Point) -> bool;
+
+    /// Every point that holds a value
+    fn iter_points(&self) -> impl Iterator<Item = Point> + '_;
+
+    /// The first point returned by [`Self::iter_points`] with a value equal to `item`
+    fn find(&self, item: &Self::Cell) -> Option<Point>
+        where Self::Cell: PartialEq
+    {
+        self.iter_points().find(|&point| self.get(point) == Some(item))
+    }
+}
+
+/// A [`Grid`] that can be changed (borrowed views can't be)
+pub trait GridMut: Grid {
+    /// Store `value` at `point`, returning the value that was there before
+    fn set(&mut self, point: Point, value: Self::Cell) -> Option<Self::Cell>;
+}
+
+/// Everything a day module needs to work with grids: `use crate::util::grid::prelude::*;`
+pub mod prelude {
+    pub use super::{BitGrid, Coordinate, Directions, Grid, GridMut, GridView, Point, Point64, SparseGrid, Vec2d};
+}
@@ -0,0 +1,221 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::vec2d::Directions;
+
+/// The signed integer types a [`Point`] can use for its coordinates
+pub trait Coordinate:
+    Copy + Ord + Hash + Debug
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+{
+    #[must_use]
+    fn abs(self) -> Self;
+    #[must_use]
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+macro_rules! coordinate {
+    ($($t:ty),*) => {
+        $(impl Coordinate for $t {
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn rem_euclid(self, rhs: Self) -> Self {
+                <$t>::rem_euclid(self, rhs)
+            }
+        })*
+    };
+}
+
+coordinate!(i32, i64);
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Hash)]
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A point for coordinates too large for `i32`
+pub type Point64 = Point<i64>;
+
+impl<T: Coordinate> Point<T> {
+
+    #[must_use]
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    #[must_use]
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        (other.x - self.x).abs() + (other.y - self.y).abs()
+    }
+
+    /// The number of king moves (diagonals allowed) between the points
+    #[must_use]
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        (other.x - self.x).abs().max((other.y - self.y).abs())
+    }
+
+    /// Rotate a quarter turn clockwise around the origin (`y` increases going down)
+    #[must_use]
+    pub fn rotate90_cw(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotate a quarter turn counter-clockwise around the origin (`y` increases going down)
+    #[must_use]
+    pub fn rotate90_ccw(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Wrap each coordinate into `0 .. bounds`, so points that leave one side re-enter from the other
+    #[must_use]
+    pub fn rem_euclid(self, bounds: Self) -> Self {
+        Self::new(self.x.rem_euclid(bounds.x), self.y.rem_euclid(bounds.y))
+    }
+}
+
+impl Point {
+    /// The points up, right, down, and left of this one
+    pub fn neighbors4(self) -> impl Iterator<Item = Point> {
+        Directions::cardinal().into_iter().map(move |direction| self + direction.delta())
+    }
+
+    /// The eight points surrounding this one, including diagonals
+    pub fn neighbors8(self) -> impl Iterator<Item = Point> {
+        Directions::all().into_iter().map(move |direction| self + direction.delta())
+    }
+
+    /// Every point on the line from this point to `other`, including both ends.
+    /// Diagonal lines that aren't at 45 degrees are rasterized with Bresenham's algorithm.
+    pub fn points_between(self, other: Point) -> impl Iterator<Item = Point> {
+        let (dx, dy) = ((other.x - self.x).abs(), -(other.y - self.y).abs());
+        let step = Point::new((other.x - self.x).signum(), (other.y - self.y).signum());
+        let mut error = dx + dy;
+        let mut next = Some(self);
+        std::iter::from_fn(move || {
+            let point = next?;
+            next = (point != other).then(|| {
+                let mut moved = point;
+                let doubled = 2 * error;
+                if doubled >= dy {
+                    error += dy;
+                    moved.x += step.x;
+                }
+                if doubled <= dx {
+                    error += dx;
+                    moved.y += step.y;
+                }
+                moved
+            });
+            Some(point)
+        })
+    }
+}
+
+impl Point64 {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn to_f64(&self) -> (f64, f64) {
+        (self.x as f64, self.y as f64)
+    }
+}
+
+impl From<Point> for Point64 {
+    fn from(point: Point) -> Self {
+        Self::new(i64::from(point.x), i64::from(point.y))
+    }
+}
+
+impl<T: Coordinate> Add for Point<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let x = self.x + rhs.x;
+        let y = self.y + rhs.y;
+        Self { x, y }
+    }
+}
+
+/// Adds the same amount to both coordinates
+impl<T: Coordinate> Add<T> for Point<T> {
+    type Output = Self;
+    fn add(self, rhs: T) -> Self {
+        let x = self.x + rhs;
+        let y = self.y + rhs;
+        Self { x, y }
+    }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let x = self.x - rhs.x;
+        let y = self.y - rhs.y;
+        Self { x, y }
+    }
+}
+
+impl<T: Coordinate> Mul<T> for Point<T> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        let x = self.x * rhs;
+        let y = self.y * rhs;
+        Self { x, y }
+    }
+}
+
+impl<T: Coordinate> Neg for Point<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { x: -self.x, y: -self.y }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::check;
+
+    #[test]
+    fn test_point_math() {
+        let point = Point::new(2, -1);
+        assert_eq!(Point::new(-2, 1), -point);
+        assert_eq!(Point::new(1, 2), point.rotate90_cw());
+        assert_eq!(point, point.rotate90_cw().rotate90_ccw());
+        assert_eq!(3, point.chebyshev_distance(&Point::new(-1, 0)));
+        assert_eq!(Point::new(2, 4), point.rem_euclid(Point::new(5, 5)));
+        assert_eq!(4, point.neighbors4().count());
+        assert!(point.neighbors8().all(|n| n.chebyshev_distance(&point) == 1));
+
+        let big = Point64::from(point) * 10_000_000_000;
+        assert_eq!(Point64::new(20_000_000_000, -10_000_000_000), big);
+        assert_eq!(30_000_000_000, big.manhattan_distance(&Point64::new(0, 0)));
+        assert_eq!(Point64::new(3, 0), Point64::from(point) + 1);
+    }
+
+    #[test]
+    fn test_points_between() {
+        let line = |a: Point, b: Point| a.points_between(b).map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        assert_eq!(vec![(0, 0), (1, 0), (2, 0)], line(Point::new(0, 0), Point::new(2, 0)));
+        assert_eq!(vec![(3, 3), (2, 2), (1, 1)], line(Point::new(3, 3), Point::new(1, 1)));
+        assert_eq!(vec![(0, 0), (1, 0), (2, 1), (3, 1)], line(Point::new(0, 0), Point::new(3, 1)));
+        assert_eq!(vec![(0, 0), (1, -1), (1, -2)], line(Point::new(0, 0), Point::new(1, -2)));
+        assert_eq!(vec![(5, 5)], line(Point::new(5, 5), Point::new(5, 5)));
+    }
+
+    #[test]
+    fn test_point_properties() {
+        check(500, |rng| {
+            let (a, b) = (rng.point(1000), rng.point(1000));
+            assert_eq!(a, a.rotate90_cw().rotate90_cw().rotate90_cw().rotate90_cw());
+            assert_eq!(a.manhattan_distance(&b), b.manhattan_distance(&a));
+            assert!(a.chebyshev_distance(&b) <= a.manhattan_distance(&b));
+            assert_eq!(a, a + b - b);
+            let bounds = Point::new(rng.range(1, 50), rng.range(1, 50));
+            let wrapped = a.rem_euclid(bounds);
+            assert!((0 .. bounds.x).contains(&wrapped.x) && (0 .. bounds.y).contains(&wrapped.y));
+        });
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use super::{Grid, GridMut, Point};
+
+/// A grid that only stores the points that have a value.
+/// Useful when the coordinates are huge, negative, or unbounded, where a [`super::Vec2d`]
+/// would waste memory or can't be used at all.
+///
+/// The bounds are the smallest rectangle that contains every point that was ever set.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    pub cells: HashMap<Point, T>,
+    min: Point,
+    max: Point,
+}
+
+impl<T> SparseGrid<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            min: Point::new(i32::MAX, i32::MAX),
+            max: Point::new(i32::MIN, i32::MIN),
+        }
+    }
+
+    /// The top left and bottom right corners of the bounding box, or `None` if the grid is empty
+    #[must_use]
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        (self.min.x <= self.max.x).then_some((self.min, self.max))
+    }
+
+    /// Removes the value at `point`. The bounds do not shrink.
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        self.cells.remove(&point)
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        for (point, value) in iter {
+            grid.set(point, value);
+        }
+        grid
+    }
+}
+
+impl<T> Grid for SparseGrid<T> {
+    type Cell = T;
+
+    fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    fn in_bounds(&self, point: Point) -> bool {
+        (self.min.x ..= self.max.x).contains(&point.x) && (self.min.y ..= self.max.y).contains(&point.y)
+    }
+
+    /// Every point that has a value, in no particular order
+    fn iter_points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+}
+
+impl<T> GridMut for SparseGrid<T> {
+    fn set(&mut self, point: Point, value: T) -> Option<T> {
+        self.min = Point::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Point::new(self.max.x.max(point.x), self.max.y.max(point.y));
+        self.cells.insert(point, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::grid::Vec2d;
+
+    fn count_walls(grid: &impl Grid<Cell = char>) -> usize {
+        grid.iter_points().filter(|&point| grid.get(point) == Some(&'#')).count()
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(None, grid.bounds());
+        assert_eq!(None, grid.set(Point::new(-5, 2), '#'));
+        assert_eq!(None, grid.set(Point::new(1_000_000, -3), '.'));
+        assert_eq!(Some(&'#'), grid.get(Point::new(-5, 2)));
+        assert_eq!(None, grid.get(Point::new(0, 0)));
+        assert!(grid.in_bounds(Point::new(0, 0)));
+        assert!(!grid.in_bounds(Point::new(0, 3)));
+        assert_eq!(Some((Point::new(-5, -3), Point::new(1_000_000, 2))), grid.bounds());
+        assert_eq!(Some(Point::new(1_000_000, -3)), grid.find(&'.'));
+
+        let dense: Vec2d<char> = "#.\n.#".parse().unwrap();
+        let sparse = dense.iter_with_points().map(|(point, &c)| (point, c)).collect::<SparseGrid<_>>();
+        assert_eq!(2, count_walls(&dense));
+        assert_eq!(2, count_walls(&sparse));
+    }
+}
@@ -0,0 +1,621 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+use crate::util::error::{AocError, Result};
+use crate::util::pathfinding::bfs;
+use super::{Grid, GridMut, GridView};
+use super::point::Point;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Hash)]
+pub enum Directions {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Directions {
+    /// Every direction, clockwise starting from `Up`
+    #[must_use]
+    pub fn all() -> [Directions; 8] {
+        [
+            Directions::Up,
+            Directions::UpRight,
+            Directions::Right,
+            Directions::DownRight,
+            Directions::Down,
+            Directions::DownLeft,
+            Directions::Left,
+            Directions::UpLeft,
+        ]
+    }
+
+    /// Up, right, down, and left (no diagonals)
+    #[must_use]
+    pub fn cardinal() -> [Directions; 4] {
+        [Directions::Up, Directions::Right, Directions::Down, Directions::Left]
+    }
+
+    /// Rotate 90 degrees clockwise
+    #[must_use]
+    pub fn turn_right(self) -> Directions {
+        self.rotate(2)
+    }
+
+    /// Rotate 90 degrees counter-clockwise
+    #[must_use]
+    pub fn turn_left(self) -> Directions {
+        self.rotate(6)
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Directions {
+        self.rotate(4)
+    }
+
+    /// Rotate clockwise by `steps` eighths of a turn
+    fn rotate(self, steps: usize) -> Directions {
+        let all = Self::all();
+        let idx = all.iter().position(|&d| d == self).unwrap_or_default();
+        all[(idx + steps) % all.len()]
+    }
+
+    /// The change in position from moving one step in this direction.
+    /// `y` increases going down.
+    #[must_use]
+    pub fn delta(self) -> Point {
+        match self {
+            Directions::Up => Point::new(0, -1),
+            Directions::UpRight => Point::new(1, -1),
+            Directions::Right => Point::new(1, 0),
+            Directions::DownRight => Point::new(1, 1),
+            Directions::Down => Point::new(0, 1),
+            Directions::DownLeft => Point::new(-1, 1),
+            Directions::Left => Point::new(-1, 0),
+            Directions::UpLeft => Point::new(-1, -1),
+        }
+    }
+
+    /// The cardinal direction for an arrow character: `^`, `v`, `<`, or `>`
+    #[must_use]
+    pub fn from_char(c: char) -> Option<Directions> {
+        match c {
+            '^' => Some(Directions::Up),
+            'v' => Some(Directions::Down),
+            '<' => Some(Directions::Left),
+            '>' => Some(Directions::Right),
+            _ => None,
+        }
+    }
+
+    /// The arrow character for a cardinal direction. Diagonals have no arrow.
+    #[must_use]
+    pub fn to_char(self) -> Option<char> {
+        match self {
+            Directions::Up => Some('^'),
+            Directions::Down => Some('v'),
+            Directions::Left => Some('<'),
+            Directions::Right => Some('>'),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Vec2d<T> 
+    where T: Clone
+{
+    pub grid: Vec<T>,
+    pub line_len: i32,
+}
+
+impl<T> Vec2d<T> 
+    where T: Clone
+{
+    /// Parse a grid with one row per line, converting each character with `cell`.
+    /// Whitespace around each line and blank lines are ignored, so CRLF line endings work too.
+    ///
+    /// # Errors
+    /// If the grid is empty, the rows are not all the same length, or `cell` fails
+    pub fn parse_with(input: &str, mut cell: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        Self::parse_rows(input, |grid, line| {
+            for c in line.chars() {
+                grid.push(cell(c)?);
+            }
+            Ok(())
+        })
+    }
+
+    // Build the grid from the trimmed, non blank lines of the input, with `push_row` adding the cells for each line
+    fn parse_rows(input: &str, mut push_row: impl FnMut(&mut Vec<T>, &str) -> Result<()>) -> Result<Self> {
+        let mut grid = Vec::new();
+        let mut line_len = None;
+        for (row, line) in input.lines().map(str::trim).filter(|line| !line.is_empty()).enumerate() {
+            let row_start = grid.len();
+            push_row(&mut grid, line)?;
+            let row_len = grid.len() - row_start;
+            match line_len {
+                None => line_len = Some(row_len),
+                Some(expected) if expected != row_len => return Err(AocError::parse(
+                    format!("grid row {} has {row_len} cells, expected {expected}", row + 1)
+                )),
+                Some(_) => (),
+            }
+        }
+        let line_len = line_len.ok_or_else(|| AocError::parse("empty grid"))?;
+        Ok(Self {
+            grid,
+            line_len: line_len.try_into().map_err(|_| AocError::parse("grid rows are too long"))?,
+        })
+    }
+
+    #[must_use]
+    pub fn in_bounds(&self, point: Point) -> bool {
+        let rows = self.grid.len() / self.line_len.unsigned_abs() as usize;
+        point.x >= 0 && point.x < self.line_len && usize::try_from(point.y).is_ok_and(|y| y < rows)
+    }
+
+    /// # Panics
+    /// If you give an index out of bounds of a signed 32 bit integer
+    #[must_use]
+    pub fn idx_to_point(&self, idx: usize) -> Point {
+        let idx: i32 = idx.try_into().expect("Invalid index");
+        Point::new(idx % self.line_len, idx / self.line_len)
+    }
+
+    /// # Panics
+    /// if you pass a point that cannot be converted to an index.
+    /// For example: a point with a negative value for x of y.
+    /// Validate your point first using the [`in_bounds`] method
+    #[must_use]
+    pub fn point_to_idx(&self, point: Point) -> usize {
+        (point.y * self.line_len + point.x)
+            .try_into()
+            .expect("Invalid Point -> index")
+    }
+
+    /// Find the next point in the direction specified
+    /// 
+    /// ## Warning
+    /// This point is not bounded by the grid and attempting to look
+    /// up the grid value at this point could panic. See [`Self::next_point`].
+    #[must_use]
+    pub fn next_unbounded(&self, point: Point, direction: Directions) -> Point {
+        point + direction.delta()
+    }
+
+    /// Finds the next point in the grid in the direction specified.
+    /// Returns `None` if the next point is outside the grid. 
+    #[must_use]
+    pub fn next_point(&self, point: Point, direction: Directions) -> Option<Point> {
+        let next = self.next_unbounded(point, direction);
+        if self.in_bounds(next) {
+            Some(next)
+        } else {
+            None
+        }
+    }
+
+    /// The points from `start` (included) moving in `direction`, until the edge of the grid
+    pub fn walk(&self, start: Point, direction: Directions) -> impl Iterator<Item = Point> + '_ {
+        self.walk_by(start, direction.delta())
+    }
+
+    /// The points from `start` (included) moving `step` at a time, until the edge of the grid
+    pub fn walk_by(&self, start: Point, step: Point) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(start), move |&point| Some(point + step))
+            .take_while(|&point| self.in_bounds(point))
+    }
+
+    /// Every point in the grid, row by row
+    pub fn iter_points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0 .. self.grid.len()).map(|idx| self.idx_to_point(idx))
+    }
+
+    /// Every value in the grid along with its point, row by row
+    pub fn iter_with_points(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.iter_points().zip(self.grid.iter())
+    }
+
+    /// # Panics
+    /// If `line_len` is not a positive number
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.grid.chunks(self.line_len.try_into().expect("Invalid line length"))
+    }
+
+    /// Each column is an iterator over its values, top to bottom
+    ///
+    /// # Panics
+    /// If `line_len` is not a positive number
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let line_len = self.line_len.try_into().expect("Invalid line length");
+        (0 .. line_len).map(move |x| self.grid.iter().skip(x).step_by(line_len))
+    }
+
+    /// The first point (row by row) with a value that satisfies `predicate`
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        self.grid.iter()
+            .position(predicate)
+            .map(|idx| self.idx_to_point(idx))
+    }
+
+    /// The value at `point`, or `None` if the point is outside the grid
+    #[must_use]
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if self.in_bounds(point) {
+            self.grid.get(self.point_to_idx(point))
+        } else {
+            None
+        }
+    }
+
+    /// A mutable reference to the value at `point`, or `None` if the point is outside the grid
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.in_bounds(point) {
+            let idx = self.point_to_idx(point);
+            self.grid.get_mut(idx)
+        } else {
+            None
+        }
+    }
+
+    /// A borrowed `line_len` by `height` rectangle of the grid starting at `top_left`
+    ///
+    /// # Panics
+    /// If any part of the view is outside the grid
+    #[must_use]
+    pub fn view(&self, top_left: Point, line_len: i32, height: i32) -> GridView<'_, T> {
+        GridView::new(self, top_left, line_len, height)
+    }
+
+    /// Every `line_len` by `height` view of the grid, row by row. Windows overlap, like [`slice::windows`].
+    pub fn windows(&self, line_len: i32, height: i32) -> impl Iterator<Item = GridView<'_, T>> {
+        let (max_x, max_y) = (self.line_len - line_len, self.height() - height);
+        (0 ..= max_y)
+            .flat_map(move |y| (0 ..= max_x).map(move |x| Point::new(x, y)))
+            .map(move |top_left| self.view(top_left, line_len, height))
+    }
+
+    /// Swap rows and columns, so the first row becomes the first column
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        self.remap(self.height(), |point| Point::new(point.y, point.x))
+    }
+
+    /// Rotate a quarter turn clockwise, so the first row becomes the last column
+    #[must_use]
+    pub fn rotate_cw(&self) -> Self {
+        let height = self.height();
+        self.remap(height, |point| Point::new(point.y, height - 1 - point.x))
+    }
+
+    /// Rotate a quarter turn counter-clockwise, so the first row becomes the first column (upside down)
+    #[must_use]
+    pub fn rotate_ccw(&self) -> Self {
+        self.remap(self.height(), |point| Point::new(self.line_len - 1 - point.y, point.x))
+    }
+
+    /// Mirror left to right
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Self {
+        self.remap(self.line_len, |point| Point::new(self.line_len - 1 - point.x, point.y))
+    }
+
+    /// Mirror top to bottom
+    #[must_use]
+    pub fn flip_vertical(&self) -> Self {
+        let height = self.height();
+        self.remap(self.line_len, |point| Point::new(point.x, height - 1 - point.y))
+    }
+
+    /// The number of rows
+    ///
+    /// # Panics
+    /// If the grid has more cells than fit in an `i32`
+    #[must_use]
+    pub fn height(&self) -> i32 {
+        i32::try_from(self.grid.len()).expect("Invalid grid size") / self.line_len
+    }
+
+    /// A grid of the same size (with rows `line_len` long) where each point
+    /// takes the value from the point given by `source` in this grid
+    fn remap(&self, line_len: i32, source: impl Fn(Point) -> Point) -> Self {
+        let mut grid = Self { grid: Vec::with_capacity(self.grid.len()), line_len };
+        for idx in 0 .. self.grid.len() {
+            let value = self[source(grid.idx_to_point(idx))].clone();
+            grid.grid.push(value);
+        }
+        grid
+    }
+
+    /// Draw the grid with one line per row, using `cell` to pick the character for each value
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        self.render_highlighted(cell, &HashSet::new(), ' ')
+    }
+
+    /// Draw the grid like [`Self::render`], except every point in `highlight` is drawn as `mark`
+    pub fn render_highlighted(&self, cell: impl Fn(&T) -> char, highlight: &HashSet<Point>, mark: char) -> String {
+        let mut rendered = String::with_capacity(self.grid.len() + self.rows().count());
+        for (point, value) in self.iter_with_points() {
+            if point.x == 0 && point.y > 0 {
+                rendered.push('\n');
+            }
+            rendered.push(if highlight.contains(&point) { mark } else { cell(value) });
+        }
+        rendered
+    }
+
+    /// Every point connected to `start` by moving up, down, left, or right
+    /// through cells where `predicate` is true (including `start` itself).
+    #[must_use]
+    pub fn flood_fill(&self, start: Point, predicate: impl Fn(&T) -> bool) -> HashSet<Point> {
+        let neighbors = |&point: &Point| {
+            Directions::cardinal().into_iter()
+                .filter_map(move |direction| self.next_point(point, direction))
+                .filter(|&next| predicate(&self[next]))
+                .collect::<Vec<_>>()
+        };
+        bfs(start, neighbors).map(|(point, _)| point).collect()
+    }
+}
+
+impl <T> Vec2d<T> 
+    where T: Clone,
+    T: PartialEq,
+{
+    /// The first point (row by row) with a value equal to `item`
+    #[must_use]
+    pub fn find(&self, item: &T) -> Option<Point> {
+        self.position(|c| c == item)
+    }
+
+    /// Every point with a value equal to `item`, row by row
+    #[must_use]
+    pub fn find_all(&self, item: &T) -> Vec<Point> {
+        self.iter_with_points()
+            .filter(|&(_, c)| c == item)
+            .map(|(point, _)| point)
+            .collect()
+    }
+}
+
+impl<T: Clone> Grid for Vec2d<T> {
+    type Cell = T;
+
+    fn get(&self, point: Point) -> Option<&T> {
+        Vec2d::get(self, point)
+    }
+
+    fn in_bounds(&self, point: Point) -> bool {
+        Vec2d::in_bounds(self, point)
+    }
+
+    fn iter_points(&self) -> impl Iterator<Item = Point> + '_ {
+        Vec2d::iter_points(self)
+    }
+}
+
+impl<T: Clone> GridMut for Vec2d<T> {
+    /// # Panics
+    /// If `point` is outside the grid
+    fn set(&mut self, point: Point, value: T) -> Option<T> {
+        Some(std::mem::replace(&mut self[point], value))
+    }
+}
+
+impl Vec2d<u8> {
+    /// Parse a grid of ASCII characters with one byte per cell, like [`Vec2d::parse_with`].
+    /// Cheaper to build and to scan than a `Vec2d<char>`, compare cells with byte literals (`b'#'`).
+    ///
+    /// # Errors
+    /// If the grid is empty, the rows are not all the same length, or there are non-ASCII characters
+    pub fn from_bytes(input: &str) -> Result<Self> {
+        Self::parse_rows(input, |grid, line| {
+            if !line.is_ascii() {
+                return Err(AocError::parse(format!("grid row {line} is not ASCII")));
+            }
+            grid.extend_from_slice(line.as_bytes());
+            Ok(())
+        })
+    }
+}
+
+impl FromStr for Vec2d<char> {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_with(s, Ok)
+    }
+}
+
+impl FromStr for Vec2d<u8> {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_bytes(s)
+    }
+}
+
+impl Display for Vec2d<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(|&c| c))
+    }
+}
+
+impl Display for Vec2d<u8> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(|&b| char::from(b)))
+    }
+}
+
+impl <T: Clone> Index<Point> for Vec2d<T>{
+    type Output = T;
+
+    fn index(&self, index: Point) -> &Self::Output {
+        let idx = self.point_to_idx(index);
+        &self.grid[idx]
+    }
+}
+
+impl <T: Clone> IndexMut<Point> for Vec2d<T> {
+
+    fn index_mut(&mut self, index: Point) -> &mut Self::Output {
+        let idx = self.point_to_idx(index);
+        self.grid.get_mut(idx).expect("Invalid Index")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::check;
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Directions::Right, Directions::Up.turn_right());
+        assert_eq!(Directions::UpLeft, Directions::UpRight.turn_left());
+        assert_eq!(Directions::DownLeft, Directions::UpRight.opposite());
+        assert_eq!(Point::new(-1, 1), Directions::DownLeft.delta());
+        for direction in Directions::cardinal() {
+            assert_eq!(Some(direction), direction.to_char().and_then(Directions::from_char));
+        }
+        assert_eq!(None, Directions::UpLeft.to_char());
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = Vec2d {
+            grid: "abcdef".chars().collect(),
+            line_len: 3,
+        };
+        assert_eq!(Some((Point::new(1, 1), &'e')), grid.iter_with_points().nth(4));
+        assert_eq!(vec![&['a', 'b', 'c'][..], &['d', 'e', 'f']], grid.rows().collect::<Vec<_>>());
+        let cols = grid.cols().map(Iterator::collect::<String>).collect::<Vec<_>>();
+        assert_eq!(vec!["ad", "be", "cf"], cols);
+
+        let walked = grid.walk(Point::new(0, 1), Directions::Right).map(|p| grid[p]).collect::<String>();
+        assert_eq!("def", walked);
+        assert_eq!(vec![Point::new(2, 1), Point::new(1, 0)], grid.walk_by(Point::new(2, 1), Point::new(-1, -1)).collect::<Vec<_>>());
+        assert_eq!(0, grid.walk(Point::new(3, 0), Directions::Left).count());
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = "#.\r\n.#\r\n".parse::<Vec2d<char>>().unwrap();
+        assert_eq!(vec!['#', '.', '.', '#'], grid.grid);
+        assert_eq!(2, grid.line_len);
+        assert!("#.\n.".parse::<Vec2d<char>>().is_err());
+        assert!("".parse::<Vec2d<char>>().is_err());
+        let numbers = Vec2d::parse_with("12\n34", |c| Ok(c.to_digit(10).unwrap())).unwrap();
+        assert_eq!(Some(&3), numbers.get(Point::new(0, 1)));
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let grid = Vec2d::from_bytes("#.\r\n.#\r\n").unwrap();
+        assert_eq!(b"#..#", &grid.grid[..]);
+        assert_eq!(Some(Point::new(1, 1)), grid.find_all(&b'#').last().copied());
+        assert_eq!("#.\n.#", grid.to_string());
+        assert!(Vec2d::from_bytes("#.\n.").is_err());
+        assert!(Vec2d::from_bytes("é.").is_err());
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid: Vec2d<char> = "abc\ndef".parse().unwrap();
+        assert_eq!("ad\nbe\ncf", grid.transpose().to_string());
+        assert_eq!("da\neb\nfc", grid.rotate_cw().to_string());
+        assert_eq!("cf\nbe\nad", grid.rotate_ccw().to_string());
+        assert_eq!("cba\nfed", grid.flip_horizontal().to_string());
+        assert_eq!("def\nabc", grid.flip_vertical().to_string());
+        assert_eq!(grid.to_string(), grid.rotate_cw().rotate_ccw().to_string());
+    }
+
+    #[test]
+    fn test_render() {
+        let grid = Vec2d {
+            grid: "#..#..".chars().collect(),
+            line_len: 3,
+        };
+        assert_eq!("#..\n#..", grid.to_string());
+        let highlight = HashSet::from([Point::new(1, 1)]);
+        assert_eq!("#..\n#O.", grid.render_highlighted(|&c| c, &highlight, 'O'));
+        let walls = Vec2d {
+            grid: grid.grid.iter().map(|&c| c == '#').collect(),
+            line_len: 3,
+        };
+        assert_eq!("X  \nX  ", walls.render(|&wall| if wall { 'X' } else { ' ' }));
+    }
+
+    #[test]
+    fn test_find() {
+        let grid = Vec2d {
+            grid: "abab".chars().collect(),
+            line_len: 2,
+        };
+        assert_eq!(Some(Point::new(1, 0)), grid.find(&'b'));
+        assert_eq!(None, grid.find(&'c'));
+        assert_eq!(vec![Point::new(0, 0), Point::new(0, 1)], grid.find_all(&'a'));
+        assert_eq!(Some(Point::new(1, 0)), grid.position(|&c| c != 'a'));
+    }
+
+    #[test]
+    fn test_index_round_trip() {
+        check(200, |rng| {
+            let grid = rng.grid(20, |_| ());
+            for (idx, point) in grid.iter_points().enumerate() {
+                assert!(grid.in_bounds(point));
+                assert_eq!(idx, grid.point_to_idx(point));
+                assert_eq!(point, grid.idx_to_point(idx));
+            }
+            assert!(!grid.in_bounds(grid.idx_to_point(grid.grid.len())));
+        });
+    }
+
+    #[test]
+    fn test_next_point_in_bounds() {
+        check(200, |rng| {
+            let grid = rng.grid(20, |_| ());
+            let point = rng.point_in(&grid);
+            for direction in Directions::all() {
+                let next = grid.next_unbounded(point, direction);
+                assert_eq!(grid.in_bounds(next).then_some(next), grid.next_point(point, direction));
+            }
+            let outside = rng.point(25);
+            assert_eq!(grid.in_bounds(outside), grid.get(outside).is_some());
+        });
+    }
+
+    #[test]
+    fn test_flood_fill_partitions() {
+        check(100, |rng| {
+            let grid = rng.grid(15, |rng| rng.chance(2, 3));
+            let mut region = Vec2d { grid: vec![None; grid.grid.len()], line_len: grid.line_len };
+            let mut regions = 0;
+            for start in grid.find_all(&true) {
+                if region[start].is_some() {
+                    continue;
+                }
+                for point in grid.flood_fill(start, |&open| open) {
+                    assert!(grid[point], "{point:?} is a wall");
+                    assert_eq!(None, region[point].replace(regions), "{point:?} is in two regions");
+                }
+                regions += 1;
+            }
+            // Every open cell is in exactly one region, and neighboring open cells are in the same one
+            for (point, &open) in grid.iter_with_points() {
+                assert_eq!(open, region[point].is_some());
+                for next in Directions::cardinal().into_iter().filter_map(|d| grid.next_point(point, d)) {
+                    if open && grid[next] {
+                        assert_eq!(region[point], region[next]);
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,101 @@
+use std::ops::Index;
+
+use super::{Grid, Point, Vec2d};
+
+/// A borrowed rectangle of a [`Vec2d`]. Points are relative to the top left corner of the view.
+/// Created by [`Vec2d::view`] and [`Vec2d::windows`].
+#[derive(Clone, Copy)]
+pub struct GridView<'a, T: Clone> {
+    grid: &'a Vec2d<T>,
+    top_left: Point,
+    line_len: i32,
+    height: i32,
+}
+
+impl<'a, T: Clone> GridView<'a, T> {
+    /// # Panics
+    /// If any part of the view is outside the grid
+    #[must_use]
+    pub fn new(grid: &'a Vec2d<T>, top_left: Point, line_len: i32, height: i32) -> Self {
+        let bottom_right = top_left + Point::new(line_len - 1, height - 1);
+        assert!(
+            line_len > 0 && height > 0 && grid.in_bounds(top_left) && grid.in_bounds(bottom_right),
+            "View is outside the grid",
+        );
+        Self { grid, top_left, line_len, height }
+    }
+
+    #[must_use]
+    pub fn line_len(&self) -> i32 {
+        self.line_len
+    }
+
+    #[must_use]
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Where the top left corner of the view is in the full grid
+    #[must_use]
+    pub fn top_left(&self) -> Point {
+        self.top_left
+    }
+
+    #[must_use]
+    pub fn in_bounds(&self, point: Point) -> bool {
+        point.x >= 0 && point.y >= 0 && point.x < self.line_len && point.y < self.height
+    }
+
+    /// The value at `point` (relative to the view), or `None` if the point is outside the view
+    #[must_use]
+    pub fn get(&self, point: Point) -> Option<&'a T> {
+        if self.in_bounds(point) {
+            self.grid.get(self.top_left + point)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Clone> Index<Point> for GridView<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: Point) -> &Self::Output {
+        self.get(index).expect("Point is outside the view")
+    }
+}
+
+impl<T: Clone> Grid for GridView<'_, T> {
+    type Cell = T;
+
+    fn get(&self, point: Point) -> Option<&T> {
+        GridView::get(self, point)
+    }
+
+    fn in_bounds(&self, point: Point) -> bool {
+        GridView::in_bounds(self, point)
+    }
+
+    fn iter_points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0 .. self.height).flat_map(|y| (0 .. self.line_len).map(move |x| Point::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view() {
+        let grid: Vec2d<char> = "abcd\nefgh\nijkl".parse().unwrap();
+        let view = grid.view(Point::new(1, 1), 2, 2);
+        assert_eq!('f', view[Point::new(0, 0)]);
+        assert_eq!(Some(&'k'), view.get(Point::new(1, 1)));
+        assert_eq!(None, view.get(Point::new(2, 1)));
+        assert_eq!(Some(Point::new(1, 0)), view.find(&'g'));
+
+        let windows = grid.windows(3, 2).map(|window| window[Point::new(2, 1)]).collect::<String>();
+        assert_eq!("ghkl", windows);
+        assert_eq!(0, grid.windows(5, 1).count());
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// The hash function used inside rustc (`FxHash`): a rotate, xor, and multiply for each word.
+/// Much faster than the default `SipHash` for small keys like points and states,
+/// but not resistant to collision attacks, so only use it for maps keyed by puzzle data.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FxHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for &byte in chunks.remainder() {
+            self.add(u64::from(byte));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+/// A `HashMap` using [`FxHasher`], create one with `FxHashMap::default()`
+pub type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
+/// A `HashSet` using [`FxHasher`], create one with `FxHashSet::default()`
+pub type FxHashSet<T> = HashSet<T, FxBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::grid::Point;
+
+    #[test]
+    fn test_fx_map() {
+        let mut map = FxHashMap::default();
+        for x in 0 .. 100 {
+            map.insert(Point::new(x, -x), x);
+        }
+        assert_eq!(100, map.len());
+        assert_eq!(Some(&42), map.get(&Point::new(42, -42)));
+        let set = ["ab", "ba", "abc", "ab"].into_iter().collect::<FxHashSet<_>>();
+        assert_eq!(3, set.len());
+    }
+}
@@ -0,0 +1,102 @@
+/// The greatest common divisor of `a` and `b`, always non-negative. `gcd(0, 0)` is 0.
+#[must_use]
+pub fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+/// The least common multiple of `a` and `b`, always non-negative. 0 if either is 0.
+#[must_use]
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// The extended euclidean algorithm: `(g, x, y)` where `g` is the gcd of `a` and `b`
+/// and `a * x + b * y == g`
+#[must_use]
+pub fn egcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if b == 0 {
+        return (a.abs(), a.signum(), 0);
+    }
+    let (divisor, x, y) = egcd(b, a.rem_euclid(b));
+    (divisor, y, x - a.div_euclid(b) * y)
+}
+
+/// The `x` in `0 .. modulus` where `a * x` is 1 (mod `modulus`),
+/// or `None` if `a` and `modulus` share a factor
+#[must_use]
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    let (divisor, x, _) = egcd(a.rem_euclid(modulus), modulus);
+    (divisor == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Solve a set of congruences `x = residue (mod modulus)` with the chinese remainder theorem.
+/// Every modulus must be positive.
+///
+/// Returns `(x, lcm)` where `x` is the smallest non-negative solution, and every solution
+/// is `x` plus a multiple of the lcm of the moduli. The moduli don't have to be coprime,
+/// but then there is `None` if the congruences contradict each other.
+/// Also `None` if the lcm doesn't fit in an `i64`.
+#[must_use]
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    congruences.into_iter().try_fold((0, 1), |(solution, period), (residue, modulus)| {
+        // solution + period * k = residue (mod modulus), so period * k = residue - solution (mod modulus)
+        let (divisor, inverse, _) = egcd(period, modulus);
+        let diff = residue - solution;
+        if diff % divisor != 0 {
+            return None;
+        }
+        let step = modulus / divisor;
+        let times = i128::from(diff / divisor) * i128::from(inverse) % i128::from(step);
+        let combined = i64::try_from(i128::from(period) * i128::from(step)).ok()?;
+        let solution = (i128::from(solution) + i128::from(period) * times).rem_euclid(i128::from(combined));
+        Some((i64::try_from(solution).ok()?, combined))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(6, gcd(48, 18));
+        assert_eq!(6, gcd(-48, 18));
+        assert_eq!(5, gcd(0, 5));
+        assert_eq!(0, gcd(0, 0));
+        assert_eq!(144, lcm(48, 18));
+        assert_eq!(10_403, lcm(101, 103));
+        assert_eq!(0, lcm(0, 7));
+    }
+
+    #[test]
+    fn test_egcd() {
+        for (a, b) in [(240, 46), (46, 240), (-17, 5), (7, 0), (12, 18)] {
+            let (g, x, y) = egcd(a, b);
+            assert_eq!(gcd(a, b), g);
+            assert_eq!(g, a * x + b * y);
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(Some(51), mod_inverse(101, 103));
+        assert_eq!(Some(7), mod_inverse(-3, 11));
+        assert_eq!(None, mod_inverse(6, 9));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(Some((23, 105)), crt([(2, 3), (3, 5), (2, 7)]));
+        // x repeats every 101, y every 103
+        assert_eq!(Some((7_502, 10_403)), crt([(7_502 % 101, 101), (7_502 % 103, 103)]));
+        // moduli that share a factor
+        assert_eq!(Some((10, 12)), crt([(2, 4), (4, 6)]));
+        assert_eq!(None, crt([(1, 4), (2, 6)]));
+        assert_eq!(Some((0, 1)), crt([]));
+        assert_eq!(Some((3, 5)), crt([(-2, 5)]));
+    }
+}
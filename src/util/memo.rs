@@ -0,0 +1,170 @@
+use std::hash::Hash;
+
+use super::hash::FxHashMap;
+
+/// How well a [`Memo`] has been doing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoStats {
+    /// Lookups that found a value
+    pub hits: u64,
+    /// Lookups that had to compute the value
+    pub misses: u64,
+    /// Values thrown away to stay under the size limit
+    pub evictions: u64,
+    /// Values stored right now
+    pub len: usize,
+}
+
+/// A memo table for recursive functions, keyed by whatever identifies a subproblem.
+/// For speed, a key can be packed into an integer (ex: `value << 8 | depth`) rather than being a tuple.
+///
+/// Optionally limited in size: once full, the least recently used half of the values is thrown away.
+/// Hit and miss counts are kept so they can be logged, see [`Memo::stats`].
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    /// each value, with when it was last used
+    values: FxHashMap<K, (V, u64)>,
+    limit: Option<usize>,
+    clock: u64,
+    stats: MemoStats,
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self { values: FxHashMap::default(), limit: None, clock: 0, stats: MemoStats::default() }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    /// A memo with no size limit
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A memo that never holds more than `limit` values
+    ///
+    /// # Panics
+    /// If `limit` is zero
+    #[must_use]
+    pub fn with_limit(limit: usize) -> Self {
+        assert!(limit > 0, "a memo has to be able to hold at least one value");
+        Self { limit: Some(limit), ..Self::default() }
+    }
+
+    /// The stored value for `key`, counting a hit or a miss
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        self.clock += 1;
+        if let Some((value, used)) = self.values.get_mut(key) {
+            *used = self.clock;
+            self.stats.hits += 1;
+            Some(value.clone())
+        } else {
+            self.stats.misses += 1;
+            None
+        }
+    }
+
+    /// Store the value for `key`, making room first if the memo is full
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(limit) = self.limit {
+            if self.values.len() >= limit && !self.values.contains_key(&key) {
+                self.evict(limit / 2);
+            }
+        }
+        self.clock += 1;
+        self.values.insert(key, (value, self.clock));
+    }
+
+    /// The stored value for `key`, or compute it with `compute` and store it.
+    /// `compute` gets the memo back so it can recurse into smaller subproblems.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = compute(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> MemoStats {
+        MemoStats { len: self.values.len(), ..self.stats }
+    }
+
+    // Throw away the least recently used values until only `keep` are left
+    fn evict(&mut self, keep: usize) {
+        let mut used = self.values.values().map(|&(_, used)| used).collect::<Vec<_>>();
+        let remove = used.len().saturating_sub(keep);
+        if remove == 0 {
+            return;
+        }
+        if remove == used.len() {
+            // a memo with a limit of 1 keeps nothing
+            self.values.clear();
+        } else {
+            // everything used before the cutoff goes
+            let (_, &mut cutoff, _) = used.select_nth_unstable(remove);
+            self.values.retain(|_, &mut (_, used)| used >= cutoff);
+        }
+        self.stats.evictions += u64::try_from(remove).unwrap_or(u64::MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.get_or_compute(n, |memo| fibonacci(memo, n - 1) + fibonacci(memo, n - 2))
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(12_586_269_025, fibonacci(&mut memo, 50));
+        let stats = memo.stats();
+        assert_eq!(49, stats.misses);
+        // fibonacci(n - 2) is already known for every n from 4 up
+        assert_eq!(47, stats.hits);
+        assert_eq!(49, stats.len);
+        assert_eq!(0, stats.evictions);
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut memo = Memo::with_limit(4);
+        for key in 0 .. 4 {
+            memo.insert(key, key * 10);
+        }
+        // use the first two, so the other two are the least recently used
+        assert_eq!(Some(0), memo.get(&0));
+        assert_eq!(Some(10), memo.get(&1));
+        memo.insert(4, 40);
+        assert_eq!(None, memo.get(&2));
+        assert_eq!(None, memo.get(&3));
+        assert_eq!(Some(0), memo.get(&0));
+        assert_eq!(Some(40), memo.get(&4));
+        assert_eq!(MemoStats { hits: 4, misses: 2, evictions: 2, len: 3 }, memo.stats());
+
+        // only the last two values are needed, so a small memo is enough
+        let mut memo = Memo::with_limit(4);
+        assert_eq!(12_586_269_025, fibonacci(&mut memo, 50));
+        assert!(memo.stats().evictions > 0);
+    }
+
+    #[test]
+    fn test_limit_one() {
+        let mut memo = Memo::with_limit(1);
+        for key in 0 .. 3 {
+            memo.insert(key, key * 10);
+        }
+        assert_eq!(None, memo.get(&0));
+        assert_eq!(None, memo.get(&1));
+        assert_eq!(Some(20), memo.get(&2));
+        assert_eq!(MemoStats { hits: 1, misses: 2, evictions: 2, len: 1 }, memo.stats());
+    }
+}
@@ -1,9 +1,29 @@
-pub mod vec2d;
-pub mod point;
+pub mod grid;
+pub mod arena;
+pub mod collections;
+pub mod error;
+pub mod pathfinding;
+pub mod dsu;
+pub mod geometry;
+pub mod graph;
+pub mod hash;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod precedence;
+pub mod range;
+pub mod random;
+pub mod raster;
+pub mod scanner;
+pub mod sequences;
+pub mod viz;
 
-pub mod grid {
-    pub mod prelude {
-        pub use crate::util::point::*;
-        pub use crate::util::vec2d::*;
-    }
-}
\ No newline at end of file
+#[deprecated(note = "use `crate::util::grid` instead")]
+pub mod vec2d {
+    pub use super::grid::{Directions, Vec2d};
+}
+
+#[deprecated(note = "use `crate::util::grid` instead")]
+pub mod point {
+    pub use super::grid::{Coordinate, Point, Point64};
+}
@@ -0,0 +1,323 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::error::{AocError, Result};
+
+/// Puzzle input with `\r\n` line endings turned into `\n`, and without trailing blank lines or whitespace,
+/// so every parser sees the same text however the input was saved
+#[must_use]
+pub fn normalize(input: &str) -> String {
+    input.replace('\r', "").trim_end().to_string()
+}
+
+/// The blocks of the input that are separated by blank lines, without the surrounding blank lines.
+/// Lines holding only whitespace count as blank, so `\r\n` line endings work too.
+#[must_use]
+pub fn sections(input: &str) -> Vec<&str> {
+    numbered_sections(input).into_iter().map(|(_, section)| section).collect()
+}
+
+/// Like [`sections`], paired with the line number (starting at 1) of the first line in each section
+#[must_use]
+pub fn numbered_sections(input: &str) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+    // line number and byte offset of the first line in the current section, and the end of its last line so far
+    let mut start = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for (idx, line) in input.split_inclusive('\n').enumerate() {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            if let Some((number, start)) = start.take() {
+                found.push((number, &input[start .. end]));
+            }
+        } else {
+            start.get_or_insert((idx + 1, offset));
+            end = offset + content.len();
+        }
+        offset += line.len();
+    }
+    if let Some((number, start)) = start {
+        found.push((number, &input[start .. end]));
+    }
+    found
+}
+
+/// The two sections of an input made of two parts, see [`sections`]
+///
+/// # Errors
+/// If the input doesn't have exactly two sections. `expected` describes them for the error message,
+/// ex: "rules and edits"
+pub fn two_sections<'a>(input: &'a str, expected: &str) -> Result<(&'a str, &'a str)> {
+    match sections(input)[..] {
+        [first, second] => Ok((first, second)),
+        _ => Err(AocError::parse(format!("expected {expected} separated by a blank line"))),
+    }
+}
+
+/// Every integer in the text, ignoring anything in between them.
+/// A `-` right before the digits makes the number negative, ex: `p=-3,4 v=+5` is `[-3, 4, 5]`.
+/// Numbers too large for an `i64` are skipped.
+#[must_use]
+pub fn ints(text: &str) -> Vec<i64> {
+    let bytes = text.as_bytes();
+    let mut found = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx].is_ascii_digit() {
+            let start = if idx > 0 && bytes[idx - 1] == b'-' { idx - 1 } else { idx };
+            idx += bytes[idx ..].iter().take_while(|b| b.is_ascii_digit()).count();
+            if let Ok(number) = text[start .. idx].parse() {
+                found.push(number);
+            }
+        } else {
+            idx += 1;
+        }
+    }
+    found
+}
+
+/// Parse each line of the input on its own, trimming whitespace and skipping blank lines
+///
+/// # Errors
+/// If any line fails to parse, naming the line number
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    input.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(number, line)| line.parse().map_err(|e| AocError::parse(format!("line {number}: {e} ({line})"))))
+        .collect()
+}
+
+/// Split a line like `x00: 1` or `ab -> cd` into its trimmed key and value
+///
+/// # Errors
+/// If `separator` isn't in the line
+pub fn key_value<'a>(line: &'a str, separator: &str) -> Result<(&'a str, &'a str)> {
+    let (key, value) = line.split_once(separator)
+        .ok_or_else(|| AocError::parse(format!("expected `key{separator}value`, found {line}")))?;
+    Ok((key.trim(), value.trim()))
+}
+
+/// Parse text described by a format string, where each `{}` holds a value of the matching type.
+/// Evaluates to a `Result` with a tuple of the values, see [`Captures`] for how the text is matched.
+///
+/// ```
+/// use advent2024::parse;
+///
+/// let (x, y) = parse!("Button A: X+94, Y+34", "Button A: X+{}, Y+{}" => i64, i64).unwrap();
+/// assert_eq!((94, 34), (x, y));
+/// ```
+///
+/// Add `; line n` to number the lines in error messages from `n` instead of 1.
+#[macro_export]
+macro_rules! parse {
+    ($text:expr, $pattern:literal => $($ty:ty),+; line $line:expr) => {
+        $crate::util::parse::Captures::new($pattern, $text, $line)
+            .and_then(|mut captures| -> $crate::util::error::Result<_> {
+                Ok(($(captures.next_value::<$ty>()?,)+))
+            })
+    };
+    ($text:expr, $pattern:literal => $($ty:ty),+ $(,)?) => {
+        $crate::parse!($text, $pattern => $($ty),+; line 1)
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Piece<'p> {
+    Literal(&'p str),
+    Space,
+    Value,
+}
+
+/// The values matched by each `{}` when text is matched against a format string, like `p={},{} v={},{}`.
+///
+/// Text in the pattern has to match exactly, except that whitespace in the pattern matches
+/// any amount of whitespace (at least one character). So a `\n` in the pattern also matches `\r\n`
+/// or a line break followed by indentation. Leading and trailing whitespace in the text is ignored.
+///
+/// A value extends up to the first place the pattern text after it appears (ex: `{} -> {}` on `a AND b -> c`
+/// gives `a AND b` and `c`), or up to whitespace if there's no text before the next value.
+/// Values can't be empty, and two `{}` in a row aren't supported.
+#[derive(Debug, Clone)]
+pub struct Captures<'p, 't> {
+    pattern: &'p str,
+    /// each value with the line number it's on
+    values: std::vec::IntoIter<(usize, &'t str)>,
+}
+
+impl<'p, 't> Captures<'p, 't> {
+    /// Match `text` against `pattern`. `first_line` is the line number of the start of the text, for error messages.
+    ///
+    /// # Errors
+    /// If the text doesn't match, naming the line where it stops matching
+    pub fn new(pattern: &'p str, text: &'t str, first_line: usize) -> Result<Self> {
+        let line_at = |offset: usize| first_line + text[.. offset].matches('\n').count();
+        let mismatch = |offset: usize| {
+            let line_start = text[.. offset].rfind('\n').map_or(0, |idx| idx + 1);
+            let found = text[line_start ..].lines().next().unwrap_or_default().trim();
+            AocError::parse(format!("line {}: expected {pattern:?}, found {found:?}", line_at(offset)))
+        };
+
+        let pieces = Self::pieces(pattern);
+        let mut pos = text.len() - text.trim_start().len();
+        // text that is all whitespace has nothing to match (and trims to before `pos`)
+        let end = text.trim_end().len().max(pos);
+        let mut values = Vec::new();
+        for (idx, piece) in pieces.iter().enumerate() {
+            let rest = &text[pos .. end];
+            let len = match piece {
+                Piece::Literal(literal) => rest.starts_with(literal).then_some(literal.len()),
+                Piece::Space => Some(rest.len() - rest.trim_start().len()).filter(|&len| len > 0),
+                Piece::Value => {
+                    let len = Self::value_len(rest, &pieces[idx + 1 ..]);
+                    values.push((line_at(pos), &rest[.. len]));
+                    Some(len).filter(|&len| len > 0)
+                }
+            };
+            pos += len.ok_or_else(|| mismatch(pos))?;
+        }
+        if pos < end {
+            return Err(mismatch(pos));
+        }
+        Ok(Self { pattern, values: values.into_iter() })
+    }
+
+    /// Parse the next value
+    ///
+    /// # Errors
+    /// If the value doesn't parse as a `T`, or every value has already been used
+    pub fn next_value<T>(&mut self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let pattern = self.pattern;
+        let (line, value) = self.values.next()
+            .ok_or_else(|| AocError::parse(format!("not enough values in {pattern:?}")))?;
+        value.parse()
+            .map_err(|e| AocError::parse(format!("line {line}: invalid value {value:?} for {pattern:?} ({e})")))
+    }
+
+    // How much of `rest` the value at its start takes up, given the pieces of the pattern after the value
+    fn value_len(rest: &str, after: &[Piece]) -> usize {
+        let found = match after {
+            [] => None,
+            [Piece::Literal(literal), ..] => rest.find(literal),
+            [Piece::Space, Piece::Literal(literal), ..] => rest.char_indices()
+                .find(|&(pos, c)| c.is_whitespace() && rest[pos ..].trim_start().starts_with(literal))
+                .map(|(pos, _)| pos),
+            _ => rest.find(char::is_whitespace),
+        };
+        found.unwrap_or(rest.len())
+    }
+
+    fn pieces(pattern: &str) -> Vec<Piece<'_>> {
+        let mut pieces = Vec::new();
+        for (idx, literal) in pattern.split("{}").enumerate() {
+            if idx > 0 {
+                pieces.push(Piece::Value);
+            }
+            let mut start = 0;
+            for (pos, c) in literal.char_indices() {
+                if c.is_whitespace() {
+                    if start < pos {
+                        pieces.push(Piece::Literal(&literal[start .. pos]));
+                    }
+                    if pieces.last() != Some(&Piece::Space) {
+                        pieces.push(Piece::Space);
+                    }
+                    start = pos + c.len_utf8();
+                }
+            }
+            if start < literal.len() {
+                pieces.push(Piece::Literal(&literal[start ..]));
+            }
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!("#.#\n...\n\n.#.", normalize("#.#\r\n...\r\n\r\n.#.\r\n\r\n  \n"));
+        assert_eq!("  1\n2", normalize("  1\n2"));
+    }
+
+    #[test]
+    fn test_sections() {
+        assert_eq!(vec!["a\nb", "c", "d"], sections("\na\nb\n\nc\n  \n\n\nd\n"));
+        assert_eq!(vec!["a\r\nb", "c"], sections("a\r\nb\r\n\r\nc\r\n"));
+        assert!(sections("").is_empty());
+        assert_eq!(("1|2", "1,2"), two_sections("1|2\n\n1,2", "rules and edits").unwrap());
+        assert!(two_sections("1|2\n1,2", "rules and edits").is_err());
+    }
+
+    #[test]
+    fn test_numbered_sections() {
+        assert_eq!(vec![(2, "a\nb"), (5, "c")], numbered_sections("\na\nb\n\nc"));
+    }
+
+    #[test]
+    fn test_parse_macro() {
+        let claw = "Button A: X+94, Y+34\r\nButton B: X+22, Y+67\n  Prize: X=8400, Y=5400\n";
+        let pattern = parse!(claw, "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}" => i64, i64, i64, i64, i64, i64);
+        assert_eq!((94, 34, 22, 67, 8400, 5400), pattern.unwrap());
+        let (gate, output) = parse!("x00 AND y00 -> z00", "{} -> {}" => String, String).unwrap();
+        assert_eq!("x00 AND y00", gate);
+        assert_eq!("z00", output);
+        assert_eq!((-3, "0,1,5".to_string()), parse!("A: -3\n\nProgram: 0,1,5", "A: {}\nProgram: {}" => i32, String).unwrap());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse!("p=0,4 v=3,-3\np=6,3 w=-1,-3", "p={},{} v={},{}\np={},{} v={},{}" => i32, i32, i32, i32; line 10)
+            .unwrap_err();
+        assert_eq!(
+            r#"Invalid puzzle input: line 11: expected "p={},{} v={},{}\np={},{} v={},{}", found "p=6,3 w=-1,-3""#,
+            error.to_string(),
+        );
+        let error = parse!("p=0,x", "p={},{}" => i32, i32; line 3).unwrap_err();
+        assert_eq!(
+            r#"Invalid puzzle input: line 3: invalid value "x" for "p={},{}" (invalid digit found in string)"#,
+            error.to_string(),
+        );
+        assert!(parse!("p=0,4 extra", "p={},{}" => i32, i32).is_err());
+        assert!(parse!("p=,4", "p={},{}" => i32, i32).is_err());
+        assert!(parse!("p=1,4", "p={},{}" => i32, i32, i32).is_err());
+        assert!(parse!("  ", "p={},{}" => i32, i32).is_err());
+        assert!(parse!("", "p={},{}" => i32, i32).is_err());
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(vec![94, 34], ints("Button A: X+94, Y+34"));
+        assert_eq!(vec![0, 4, 3, -3], ints("p=0,4 v=3,-3"));
+        assert_eq!(vec![5, -3], ints("5-3"));
+        assert!(ints("no numbers - here").is_empty());
+        assert_eq!(vec![1], ints("1 99999999999999999999"));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        assert_eq!(vec![1, 10, 100], parse_lines::<i64>("1\n  10\n\n100\n").unwrap());
+        let error = parse_lines::<i64>("1\n2\nx").unwrap_err();
+        assert_eq!("Invalid puzzle input: line 3: invalid digit found in string (x)", error.to_string());
+    }
+
+    #[test]
+    fn test_key_value() {
+        assert_eq!(("x00", "1"), key_value("x00: 1", ":").unwrap());
+        assert_eq!(("x00 AND y00", "z00"), key_value("x00 AND y00 -> z00", "->").unwrap());
+        assert!(key_value("x00 1", ":").is_err());
+    }
+}
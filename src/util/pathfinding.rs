@@ -0,0 +1,419 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use super::arena::SearchTree;
+use super::grid::{Grid, Point};
+use super::hash::{FxHashMap, FxHashSet};
+
+/// The explored states from a [`dijkstra`] search
+#[derive(Debug, Clone)]
+pub struct Dijkstra<S, C> {
+    /// Every explored state, with the previous states on the lowest cost paths to it.
+    /// A state reached by more than one equally good path has more than one parent.
+    pub tree: SearchTree<S>,
+    /// The lowest cost to reach each state, indexed by its id in `tree`
+    costs: Vec<C>,
+    /// Every goal state reached at the lowest goal cost. Empty if no goal was reached.
+    pub goals: Vec<S>,
+}
+
+impl<S, C> Dijkstra<S, C>
+    where S: Clone + Eq + Hash, C: Copy
+{
+    /// The lowest cost to reach `state`, or `None` if it was not reached
+    #[must_use]
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.tree.id(state).map(|id| self.costs[id])
+    }
+
+    /// Every explored state along with the lowest cost to reach it
+    pub fn costs(&self) -> impl Iterator<Item = (&S, C)> + '_ {
+        self.costs.iter().enumerate().map(|(id, &cost)| (self.tree.state(id), cost))
+    }
+
+    /// The number of states explored
+    #[must_use]
+    pub fn explored(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// The lowest cost to reach a goal, or `None` if no goal is reachable
+    #[must_use]
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goals.first().and_then(|goal| self.cost(goal))
+    }
+
+    /// One of the lowest cost paths from the start to `end`, including both ends.
+    /// Empty if `end` was not reached.
+    #[must_use]
+    pub fn path_to(&self, end: &S) -> Vec<S> {
+        self.tree.id(end)
+            .map(|id| self.tree.path_to(id).into_iter().map(|id| self.tree.state(id).clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Every state on any lowest cost path from the start to any of the goals
+    #[must_use]
+    pub fn best_path_states(&self) -> FxHashSet<S> {
+        let goals = self.goals.iter().filter_map(|goal| self.tree.id(goal));
+        self.tree.ancestors(goals).into_iter()
+            .enumerate()
+            .filter(|&(_, included)| included)
+            .map(|(id, _)| self.tree.state(id).clone())
+            .collect()
+    }
+}
+
+/// Dijkstra's algorithm over any graph, starting from `start`.
+///
+/// `neighbors` returns the states reachable from a state along with the cost to move there.
+/// The search stops once every goal state with the lowest cost has been reached
+/// (more than one state can satisfy `goal`). Use `|_| false` to explore every reachable state.
+///
+/// `C::default()` must be the zero cost, and costs must not be negative.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbors: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Dijkstra<S, C>
+    where S: Clone + Eq + Hash, C: Copy + Ord + Default + Add<Output = C>, I: IntoIterator<Item = (S, C)>
+{
+    dijkstra_multi([start], neighbors, goal)
+}
+
+/// [`dijkstra`] starting from several states at once, each with zero cost.
+/// Useful for searching backwards from any of several end states.
+pub fn dijkstra_multi<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Dijkstra<S, C>
+    where S: Clone + Eq + Hash, C: Copy + Ord + Default + Add<Output = C>, I: IntoIterator<Item = (S, C)>
+{
+    let mut tree = SearchTree::new();
+    let mut costs = Vec::new();
+    let mut goals = Vec::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        let (id, added) = tree.insert(start);
+        if added {
+            costs.push(C::default());
+            queue.push(Node { cost: C::default(), state: id });
+        }
+    }
+
+    while let Some(Node { cost, state: id }) = queue.pop() {
+        if cost > costs[id] {
+            continue;
+        }
+        if let Some(&goal) = goals.first() {
+            if cost > costs[goal] {
+                break;
+            }
+        }
+        let state = tree.state(id).clone();
+        if goal(&state) {
+            goals.push(id);
+            continue;
+        }
+        for (next, step_cost) in neighbors(&state) {
+            let next_cost = cost + step_cost;
+            let (next_id, added) = tree.insert(next);
+            if added {
+                costs.push(next_cost);
+            } else {
+                match next_cost.cmp(&costs[next_id]) {
+                    Ordering::Greater => continue,
+                    Ordering::Equal => {
+                        tree.add_parent(next_id, id);
+                        continue;
+                    }
+                    Ordering::Less => costs[next_id] = next_cost,
+                }
+            }
+            tree.set_parent(next_id, id);
+            queue.push(Node { cost: next_cost, state: next_id });
+        }
+    }
+    let goals = goals.into_iter().map(|id| tree.state(id).clone()).collect();
+    Dijkstra { tree, costs, goals }
+}
+
+/// [`dijkstra`] on a grid, where each step up, down, left, or right onto a `passable` cell costs 1
+pub fn dijkstra_grid<G: Grid>(
+    grid: &G,
+    start: Point,
+    passable: impl Fn(&G::Cell) -> bool,
+    goal: impl FnMut(&Point) -> bool,
+) -> Dijkstra<Point, i32> {
+    let neighbors = |&point: &Point| {
+        point.neighbors4()
+            .filter(|&next| grid.get(next).is_some_and(&passable))
+            .map(|next| (next, 1))
+            .collect::<Vec<_>>()
+    };
+    dijkstra(start, neighbors, goal)
+}
+
+/// Breadth first search iterator created by [`bfs`]
+pub struct Bfs<S, F> {
+    queue: VecDeque<(S, usize)>,
+    seen: FxHashSet<S>,
+    neighbors: F,
+}
+
+impl<S, F, I> Iterator for Bfs<S, F>
+    where S: Clone + Eq + Hash, F: FnMut(&S) -> I, I: IntoIterator<Item = S>
+{
+    type Item = (S, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (state, depth) = self.queue.pop_front()?;
+        for next in (self.neighbors)(&state) {
+            if self.seen.insert(next.clone()) {
+                self.queue.push_back((next, depth + 1));
+            }
+        }
+        Some((state, depth))
+    }
+}
+
+/// Breadth first search from `start`, yielding every reachable state once along with
+/// the number of steps it takes to get there. States are yielded in order of depth.
+pub fn bfs<S, F, I>(start: S, neighbors: F) -> Bfs<S, F>
+    where S: Clone + Eq + Hash, F: FnMut(&S) -> I, I: IntoIterator<Item = S>
+{
+    Bfs {
+        queue: VecDeque::from([(start.clone(), 0)]),
+        seen: FxHashSet::from_iter([start]),
+        neighbors,
+    }
+}
+
+/// How [`astar`] chooses between queued states with the same estimated total cost
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Explore the state that was queued first
+    #[default]
+    Fifo,
+    /// Explore the state that was queued most recently
+    Lifo,
+    /// Explore the state the heuristic says is closest to a goal, then the one queued first
+    LowestHeuristic,
+}
+
+/// A* search from `start` to the first state that satisfies `goal`.
+///
+/// `neighbors` returns the states reachable from a state along with the cost to move there.
+/// `heuristic` estimates the remaining cost from a state to the nearest goal. The result is only
+/// guaranteed to be a lowest cost path if the heuristic never overestimates that cost.
+///
+/// Returns the path from the start to the goal (including both ends) and its total cost,
+/// or `None` if no goal is reachable.
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+    tie_break: TieBreak,
+) -> Option<(Vec<S>, C)>
+    where S: Clone + Eq + Hash, C: Copy + Ord + Default + Add<Output = C>, I: IntoIterator<Item = (S, C)>
+{
+    let mut costs = FxHashMap::from_iter([(start.clone(), C::default())]);
+    let mut parents: FxHashMap<S, S> = FxHashMap::default();
+    let mut queue = BinaryHeap::new();
+    let mut sequence = 0;
+    let estimate = heuristic(&start);
+    queue.push(Candidate { estimate, heuristic: estimate, sequence, tie_break, cost: C::default(), state: start });
+
+    while let Some(Candidate { cost, state, .. }) = queue.pop() {
+        if cost > costs[&state] {
+            continue;
+        }
+        if goal(&state) {
+            let mut path = vec![state];
+            while let Some(parent) = parents.remove(path.last()?) {
+                path.push(parent);
+            }
+            path.reverse();
+            return Some((path, cost));
+        }
+        for (next, step_cost) in neighbors(&state) {
+            let next_cost = cost + step_cost;
+            if costs.get(&next).is_some_and(|&current| current <= next_cost) {
+                continue;
+            }
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), state.clone());
+            let remaining = heuristic(&next);
+            sequence += 1;
+            queue.push(Candidate {
+                estimate: next_cost + remaining,
+                heuristic: remaining,
+                sequence,
+                tie_break,
+                cost: next_cost,
+                state: next,
+            });
+        }
+    }
+    None
+}
+
+/// Priority queue entry, ordered so the lowest cost is popped first from the max heap
+struct Node<S, C> {
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Node<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Node<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Node<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl<S, C: Ord> Eq for Node<S, C> {}
+
+/// Priority queue entry for [`astar`], ordered so the lowest estimated total cost is popped first
+struct Candidate<S, C> {
+    estimate: C,
+    heuristic: C,
+    sequence: usize,
+    tie_break: TieBreak,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Candidate<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let fifo = other.sequence.cmp(&self.sequence);
+        other.estimate.cmp(&self.estimate).then(match self.tie_break {
+            TieBreak::Fifo => fifo,
+            TieBreak::Lifo => fifo.reverse(),
+            TieBreak::LowestHeuristic => other.heuristic.cmp(&self.heuristic).then(fifo),
+        })
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Candidate<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Candidate<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for Candidate<S, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::util::grid::{Directions, Vec2d};
+
+    // a -1-> b -1-> d
+    // a -2-> c -5-> d
+    // a -1-> e -1-> d
+    fn graph(node: char) -> Vec<(char, i32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 2), ('e', 1)],
+            'b' | 'e' => vec![('d', 1)],
+            'c' => vec![('d', 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let result = dijkstra('a', |&node| graph(node), |&node| node == 'd');
+        assert_eq!(Some(2), result.goal_cost());
+        assert_eq!(3, result.path_to(&'d').len());
+        assert_eq!(FxHashSet::from_iter(['a', 'b', 'd', 'e']), result.best_path_states());
+
+        let result = dijkstra('a', |&node| graph(node), |_| false);
+        assert_eq!(None, result.goal_cost());
+        assert_eq!(Some(2), result.cost(&'c'));
+        assert_eq!(Some(2), result.cost(&'d'));
+        assert_eq!(5, result.explored());
+        assert!(result.path_to(&'z').is_empty());
+
+        // searching backwards from both b and c
+        let reversed = |&node: &char| {
+            ['a', 'b', 'c', 'e'].into_iter()
+                .flat_map(|from| graph(from).into_iter().filter(|&(to, _)| to == node).map(move |(_, cost)| (from, cost)))
+                .collect::<Vec<_>>()
+        };
+        let result = dijkstra_multi(['b', 'c'], reversed, |&node| node == 'a');
+        assert_eq!(Some(1), result.goal_cost());
+        assert_eq!(vec!['b', 'a'], result.path_to(&'a'));
+    }
+
+    #[test]
+    fn test_dijkstra_grid() {
+        let grid = Vec2d {
+            grid: "..#...#.....#...".chars().collect(),
+            line_len: 4,
+        };
+        let end = Point::new(3, 3);
+        let result = dijkstra_grid(&grid, Point::new(0, 0), |&c| c == '.', |&p| p == end);
+        assert_eq!(Some(6), result.goal_cost());
+        assert_eq!(vec![Point::new(0, 0), Point::new(1, 0)], result.path_to(&Point::new(1, 0)));
+        assert_eq!(None, result.cost(&Point::new(2, 0)));
+    }
+
+    #[test]
+    fn test_astar() {
+        let end = Point::new(3, 3);
+        let grid = Vec2d {
+            grid: "..#...#.....#...".chars().collect(),
+            line_len: 4,
+        };
+        let neighbors = |&point: &Point| {
+            Directions::cardinal().into_iter()
+                .filter_map(|direction| grid.next_point(point, direction))
+                .filter(|&next| grid[next] == '.')
+                .map(|next| (next, 1))
+                .collect::<Vec<_>>()
+        };
+        for tie_break in [TieBreak::Fifo, TieBreak::Lifo, TieBreak::LowestHeuristic] {
+            let (path, cost) = astar(
+                Point::new(0, 0),
+                neighbors,
+                |point| point.manhattan_distance(&end),
+                |&point| point == end,
+                tie_break,
+            ).unwrap();
+            assert_eq!(6, cost);
+            assert_eq!(7, path.len());
+            assert_eq!(Some(&end), path.last());
+        }
+
+        let result = astar('a', |&node| graph(node), |_| 0, |&node| node == 'z', TieBreak::default());
+        assert_eq!(None, result);
+    }
+
+    #[test]
+    fn test_bfs() {
+        let depths = bfs('a', |&node| graph(node).into_iter().map(|(next, _)| next))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(HashMap::from([('a', 0), ('b', 1), ('c', 1), ('e', 1), ('d', 2)]), depths);
+    }
+}
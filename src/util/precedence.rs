@@ -0,0 +1,186 @@
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+use super::hash::{FxHashMap, FxHashSet};
+
+/// Rules that some items have to come before others, like pages in a print queue or steps in a build.
+/// The rules form a directed graph from each item to the items that have to come after it.
+#[derive(Debug, Clone)]
+pub struct Precedence<T> {
+    after: FxHashMap<T, FxHashSet<T>>,
+}
+
+impl<T> Default for Precedence<T> {
+    fn default() -> Self {
+        Self { after: FxHashMap::default() }
+    }
+}
+
+impl<T: Copy + Eq + Hash> Precedence<T> {
+    /// No rules at all, so any order is allowed
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the rule that `before` has to come before `after`
+    pub fn add(&mut self, before: T, after: T) {
+        self.after.entry(before).or_default().insert(after);
+    }
+
+    /// Whether there is a rule that `before` has to come before `after`
+    #[must_use]
+    pub fn directly_before(&self, before: T, after: T) -> bool {
+        self.after.get(&before).is_some_and(|items| items.contains(&after))
+    }
+
+    /// Every item that has to come after `item`, directly or through a chain of rules.
+    /// Includes `item` itself if it is part of a cycle.
+    #[must_use]
+    pub fn all_after(&self, item: T) -> FxHashSet<T> {
+        let mut found = FxHashSet::default();
+        let mut stack = vec![item];
+        while let Some(current) = stack.pop() {
+            for &next in self.after.get(&current).into_iter().flatten() {
+                if found.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        found
+    }
+
+    /// Whether `before` has to come before `after`, directly or through a chain of rules
+    #[must_use]
+    pub fn must_precede(&self, before: T, after: T) -> bool {
+        self.all_after(before).contains(&after)
+    }
+
+    /// A cycle of rules (each item has to come before the next, and the last before the first),
+    /// or `None` if the rules can all be followed at once
+    #[must_use]
+    pub fn find_cycle(&self) -> Option<Vec<T>> {
+        let followers = |item: &T| self.after.get(item).into_iter().flatten().copied().collect::<Vec<_>>();
+        let mut finished = FxHashSet::default();
+        for start in self.after.keys() {
+            if finished.contains(start) {
+                continue;
+            }
+            // depth first search, keeping the path from the start and the followers left to visit at each step
+            let mut path = vec![*start];
+            let mut stack = vec![followers(start).into_iter()];
+            while let Some(remaining) = stack.last_mut() {
+                match remaining.next() {
+                    Some(next) if finished.contains(&next) => (),
+                    Some(next) => {
+                        if let Some(idx) = path.iter().position(|&item| item == next) {
+                            return Some(path.split_off(idx));
+                        }
+                        path.push(next);
+                        stack.push(followers(&next).into_iter());
+                    }
+                    None => {
+                        stack.pop();
+                        finished.extend(path.pop());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Put the items in an order that follows every rule between them (Kahn's algorithm).
+    /// Rules involving items that aren't in the list are ignored,
+    /// and items with no rules between them stay in the order they were given.
+    /// A repeated item is kept, with every copy next to each other.
+    /// Returns `None` if the rules between the items form a cycle.
+    #[must_use]
+    pub fn sort(&self, items: &[T]) -> Option<Vec<T>> {
+        // how many times each item is in the list
+        let mut included = FxHashMap::default();
+        let mut distinct = Vec::new();
+        for &item in items {
+            let count = included.entry(item).or_insert(0);
+            if *count == 0 {
+                distinct.push(item);
+            }
+            *count += 1;
+        }
+        let after = |item: T| self.after.get(&item).into_iter().flatten().filter(|next| included.contains_key(next));
+        // how many of the items have to come before each item
+        let mut waiting = distinct.iter().map(|&item| (item, 0)).collect::<FxHashMap<_, _>>();
+        for &item in &distinct {
+            for next in after(item) {
+                *waiting.entry(*next).or_default() += 1;
+            }
+        }
+        let mut ready = distinct.iter().copied().filter(|item| waiting[item] == 0).collect::<VecDeque<_>>();
+        let mut ordered = Vec::with_capacity(items.len());
+        while let Some(item) = ready.pop_front() {
+            ordered.extend(std::iter::repeat_n(item, included[&item]));
+            for &next in after(item) {
+                let count = waiting.entry(next).or_default();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+        (ordered.len() == items.len()).then_some(ordered)
+    }
+}
+
+impl<T: Copy + Eq + Hash> FromIterator<(T, T)> for Precedence<T> {
+    /// Build from `(before, after)` rules
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(rules: I) -> Self {
+        let mut precedence = Self::new();
+        for (before, after) in rules {
+            precedence.add(before, after);
+        }
+        precedence
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_must_precede() {
+        let rules = [(1, 2), (2, 3), (4, 3)].into_iter().collect::<Precedence<_>>();
+        assert!(rules.directly_before(1, 2));
+        assert!(!rules.directly_before(1, 3));
+        assert!(rules.must_precede(1, 3));
+        assert!(!rules.must_precede(3, 1));
+        assert!(!rules.must_precede(1, 4));
+        assert_eq!(FxHashSet::from_iter([2, 3]), rules.all_after(1));
+    }
+
+    #[test]
+    fn test_sort() {
+        let rules = [(1, 2), (2, 3), (4, 3)].into_iter().collect::<Precedence<_>>();
+        let sorted = rules.sort(&[3, 2, 4, 1]).unwrap();
+        let position = |item| sorted.iter().position(|&i| i == item).unwrap();
+        assert!(position(1) < position(2) && position(2) < position(3) && position(4) < position(3));
+        // only the rules between the items count, so 1 doesn't have to come before 3 without 2
+        assert_eq!(Some(vec![1, 3]), rules.sort(&[1, 3]));
+        assert_eq!(Some(vec![3, 1]), rules.sort(&[3, 1]));
+        assert_eq!(None, [(1, 2), (2, 1)].into_iter().collect::<Precedence<_>>().sort(&[1, 2]));
+        // a repeated page isn't a cycle
+        assert_eq!(Some(vec![1, 2, 2, 3]), rules.sort(&[2, 3, 1, 2]));
+        assert_eq!(Some(vec![5, 5]), rules.sort(&[5, 5]));
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let mut rules = [(1, 2), (2, 3), (3, 4), (1, 4)].into_iter().collect::<Precedence<_>>();
+        assert_eq!(None, rules.find_cycle());
+        rules.add(4, 2);
+        let cycle = rules.find_cycle().unwrap();
+        assert_eq!(3, cycle.len());
+        for (idx, &item) in cycle.iter().enumerate() {
+            assert!(rules.directly_before(item, cycle[(idx + 1) % cycle.len()]));
+        }
+        assert!(rules.must_precede(2, 2));
+    }
+}
@@ -0,0 +1,58 @@
+/// A small, seeded pseudorandom generator (splitmix64). The same seed always gives the same numbers,
+/// so anything built on it (test vectors, a failing property test case) can be replayed.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `low .. high`
+    ///
+    /// # Panics
+    /// If the range is empty
+    pub fn range(&mut self, low: i32, high: i32) -> i32 {
+        assert!(low < high, "empty range {low}..{high}");
+        let len = u64::from(high.abs_diff(low));
+        low + i32::try_from(self.next_u64() % len).expect("fits in the range")
+    }
+
+    /// A number in `0 .. high`
+    ///
+    /// # Panics
+    /// If `high` is 0
+    pub fn below(&mut self, high: usize) -> usize {
+        assert!(high > 0, "empty range 0..0");
+        usize::try_from(self.next_u64() % high as u64).expect("fits in the range")
+    }
+
+    /// True with probability `numerator / denominator`
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.next_u64() % denominator < numerator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        assert_eq!(Rng::new(7).next_u64(), rng.next_u64());
+        assert_ne!(Rng::new(8).next_u64(), Rng::new(7).next_u64());
+        assert!((0 .. 1000).map(|_| rng.range(-3, 4)).all(|n| (-3 .. 4).contains(&n)));
+        assert!((0 .. 1000).map(|_| rng.below(5)).all(|n| n < 5));
+        assert!((0 .. 100).all(|_| !rng.chance(0, 3)));
+    }
+}
@@ -0,0 +1,142 @@
+use std::fmt::{self, Display};
+
+/// A half open interval of integers, `start .. end`. Empty when `end <= start`.
+///
+/// Ordered by start, then end, so sorting spans puts them left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Span {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Span {
+    #[must_use]
+    pub const fn new(start: i64, end: i64) -> Self {
+        Self { start, end }
+    }
+
+    /// The span of `len` integers starting at `start`
+    #[must_use]
+    pub const fn with_len(start: i64, len: i64) -> Self {
+        Self { start, end: start + len }
+    }
+
+    /// The number of integers in the span (0 if empty)
+    #[must_use]
+    pub fn len(self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.end <= self.start
+    }
+
+    #[must_use]
+    pub fn contains(self, value: i64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The sum of every integer in the span
+    #[must_use]
+    pub fn sum(self) -> i64 {
+        let len = self.len();
+        self.start * len + len * (len - 1) / 2
+    }
+
+    /// The integers in both spans, or `None` if they don't overlap
+    #[must_use]
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let span = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!span.is_empty()).then_some(span)
+    }
+
+    /// The integers in either span, or `None` if there would be a gap between them.
+    /// Spans that only touch (ex: `0 .. 3` and `3 .. 5`) are joined.
+    #[must_use]
+    pub fn union(self, other: Self) -> Option<Self> {
+        if self.is_empty() {
+            return Some(other);
+        }
+        if other.is_empty() {
+            return Some(self);
+        }
+        (self.start <= other.end && other.start <= self.end)
+            .then(|| Self::new(self.start.min(other.start), self.end.max(other.end)))
+    }
+
+    /// The integers in this span that aren't in `other`: the part left of `other`, and the part right of it.
+    /// Either part is `None` if it would be empty.
+    #[must_use]
+    pub fn subtract(self, other: Self) -> (Option<Self>, Option<Self>) {
+        let left = Self::new(self.start, self.end.min(other.start));
+        let right = Self::new(self.start.max(other.end), self.end);
+        ((!left.is_empty()).then_some(left), (!right.is_empty()).then_some(right))
+    }
+
+    /// Combine spans that overlap or touch, giving the fewest spans with the same integers, left to right.
+    /// Empty spans are dropped.
+    #[must_use]
+    pub fn merge(spans: impl IntoIterator<Item = Self>) -> Vec<Self> {
+        let mut spans = spans.into_iter().filter(|span| !span.is_empty()).collect::<Vec<_>>();
+        spans.sort_unstable();
+        let mut merged: Vec<Self> = Vec::with_capacity(spans.len());
+        for span in spans {
+            if let Some(last) = merged.last_mut() {
+                if let Some(union) = last.union(span) {
+                    *last = union;
+                    continue;
+                }
+            }
+            merged.push(span);
+        }
+        merged
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span() {
+        let span = Span::with_len(3, 4);
+        assert_eq!(Span::new(3, 7), span);
+        assert_eq!(4, span.len());
+        assert!(span.contains(3) && span.contains(6) && !span.contains(7));
+        assert_eq!(3 + 4 + 5 + 6, span.sum());
+        assert_eq!(0, Span::new(5, 2).len());
+        assert_eq!("3..7", span.to_string());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = Span::new(0, 10);
+        let b = Span::new(5, 15);
+        assert_eq!(Some(Span::new(5, 10)), a.intersection(b));
+        assert_eq!(None, a.intersection(Span::new(10, 12)));
+        assert_eq!(Some(Span::new(0, 15)), a.union(b));
+        assert_eq!(Some(Span::new(0, 12)), a.union(Span::new(10, 12)));
+        assert_eq!(None, a.union(Span::new(11, 12)));
+
+        assert_eq!((Some(Span::new(0, 5)), None), a.subtract(b));
+        assert_eq!((None, Some(Span::new(10, 15))), b.subtract(a));
+        assert_eq!((Some(Span::new(0, 2)), Some(Span::new(4, 10))), a.subtract(Span::new(2, 4)));
+        assert_eq!((None, None), Span::new(2, 4).subtract(a));
+        assert_eq!((Some(a), None), a.subtract(Span::new(20, 30)));
+        assert_eq!((None, Some(a)), a.subtract(Span::new(-5, -1)));
+    }
+
+    #[test]
+    fn test_merge() {
+        let spans = [Span::new(10, 12), Span::new(0, 3), Span::new(2, 5), Span::new(5, 6), Span::new(8, 8), Span::new(11, 20)];
+        assert_eq!(vec![Span::new(0, 6), Span::new(10, 20)], Span::merge(spans));
+        assert!(Span::merge([]).is_empty());
+    }
+}
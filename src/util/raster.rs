@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use super::grid::{Point, Vec2d};
+
+/// A color as red, green, and blue
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+/// An image made of colored pixels, for saving grid puzzles as pictures.
+/// Encode it with [`Raster::to_png`], or a list of frames with [`animated_gif`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Raster {
+    /// An image filled with the `background` color
+    #[must_use]
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self { width, height, pixels: vec![background; width * height] }
+    }
+
+    /// Draw each cell of the grid as a `scale` by `scale` square of pixels
+    #[must_use]
+    pub fn from_grid<T: Clone>(grid: &Vec2d<T>, scale: usize, color: impl Fn(&T) -> Rgb) -> Self {
+        let width = grid.line_len.unsigned_abs() as usize;
+        let height = grid.grid.len().checked_div(width).unwrap_or(0);
+        let mut raster = Self::new(width * scale, height * scale, BLACK);
+        for (point, value) in grid.iter_with_points() {
+            raster.fill_cell(point, scale, color(value));
+        }
+        raster
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The color of the pixel at `x`, `y`, or `None` if it is outside the image
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Color the pixel at `x`, `y`. Pixels outside the image are ignored
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    /// Color the `scale` by `scale` square of pixels for a grid cell
+    pub fn fill_cell(&mut self, cell: Point, scale: usize, color: Rgb) {
+        let (Ok(x), Ok(y)) = (usize::try_from(cell.x), usize::try_from(cell.y)) else {
+            return;
+        };
+        for dy in 0 .. scale {
+            for dx in 0 .. scale {
+                self.set(x * scale + dx, y * scale + dy, color);
+            }
+        }
+    }
+
+    /// Encode as a PNG file: 8 bit RGB, with every row unfiltered
+    ///
+    /// # Panics
+    /// If the image is wider or taller than a PNG allows
+    #[must_use]
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut rows = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            rows.push(0);
+            rows.extend(row.iter().flatten());
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&rows).expect("writing to a Vec cannot fail");
+        let data = encoder.finish().expect("writing to a Vec cannot fail");
+
+        let mut header = Vec::new();
+        header.extend(u32::try_from(self.width).expect("PNG width fits in 32 bits").to_be_bytes());
+        header.extend(u32::try_from(self.height).expect("PNG height fits in 32 bits").to_be_bytes());
+        // bit depth 8, RGB color, then the default compression, filter, and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, *b"IHDR", &header);
+        png_chunk(&mut png, *b"IDAT", &data);
+        png_chunk(&mut png, *b"IEND", &[]);
+        png
+    }
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: [u8; 4], data: &[u8]) {
+    png.extend(u32::try_from(data.len()).expect("PNG chunk fits in 32 bits").to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(&kind);
+    crc.update(data);
+    png.extend(crc.finalize().to_be_bytes());
+}
+
+/// Encode the frames as a GIF that loops forever, showing each frame for `delay`
+/// (GIF delays are in hundredths of a second).
+///
+/// # Panics
+/// If there are no frames, the frames are different sizes, a frame is larger than 65535 pixels
+/// on a side, or the frames use more than 256 colors between them
+#[must_use]
+pub fn animated_gif(frames: &[Raster], delay: Duration) -> Vec<u8> {
+    let first = frames.first().expect("a GIF needs at least one frame");
+    assert!(
+        frames.iter().all(|frame| frame.width == first.width && frame.height == first.height),
+        "every GIF frame must be the same size",
+    );
+    let mut palette = Vec::new();
+    let mut palette_idx = HashMap::new();
+    for &color in frames.iter().flat_map(|frame| &frame.pixels) {
+        palette_idx.entry(color).or_insert_with(|| {
+            palette.push(color);
+            u8::try_from(palette.len() - 1).expect("a GIF can only use 256 colors")
+        });
+    }
+    // the color table has 2^bits entries, at least 2
+    let bits = (1 ..= 8).find(|&bits| 1 << bits >= palette.len()).unwrap_or(8);
+    let width = u16::try_from(first.width).expect("GIF width fits in 16 bits").to_le_bytes();
+    let height = u16::try_from(first.height).expect("GIF height fits in 16 bits").to_le_bytes();
+    let delay = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX).to_le_bytes();
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend(width);
+    gif.extend(height);
+    // a global color table of 2^bits colors, with 8 bits per primary color
+    gif.extend([0xF0 | (bits - 1), 0, 0]);
+    gif.extend(palette.iter().flatten());
+    gif.extend(std::iter::repeat_n(0, 3 * ((1 << bits) - palette.len())));
+    // the netscape extension, to loop forever
+    gif.extend(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+    for frame in frames {
+        // graphic control extension, for the delay
+        gif.extend([0x21, 0xF9, 0x04, 0x00, delay[0], delay[1], 0x00, 0x00]);
+        // image descriptor, covering the whole image
+        gif.extend([0x2C, 0, 0, 0, 0, width[0], width[1], height[0], height[1], 0]);
+        let min_code_size = bits.max(2);
+        gif.push(min_code_size);
+        let indices = frame.pixels.iter().map(|color| palette_idx[color]);
+        for block in lzw_encode(indices, min_code_size).chunks(255) {
+            gif.push(u8::try_from(block.len()).expect("blocks are at most 255 bytes"));
+            gif.extend(block);
+        }
+        gif.push(0);
+    }
+    gif.push(0x3B);
+    gif
+}
+
+/// The variable code width LZW compression used by GIF, with codes packed least significant bit first
+fn lzw_encode(indices: impl Iterator<Item = u8>, min_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4096;
+    let clear = 1_u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut bytes = Vec::new();
+    let mut buffer = 0_u32;
+    let mut buffered_bits = 0;
+    let mut emit = |code: u16, code_size: u8| {
+        buffer |= u32::from(code) << buffered_bits;
+        buffered_bits += code_size;
+        while buffered_bits >= 8 {
+            bytes.push(buffer.to_le_bytes()[0]);
+            buffer >>= 8;
+            buffered_bits -= 8;
+        }
+    };
+
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut code_size = min_code_size + 1;
+    emit(clear, code_size);
+    let mut prefix = None;
+    for idx in indices {
+        let Some(current) = prefix else {
+            prefix = Some(u16::from(idx));
+            continue;
+        };
+        if let Some(&code) = codes.get(&(current, idx)) {
+            prefix = Some(code);
+            continue;
+        }
+        emit(current, code_size);
+        if next_code < MAX_CODE {
+            if next_code == 1 << code_size {
+                code_size += 1;
+            }
+            codes.insert((current, idx), next_code);
+            next_code += 1;
+        } else {
+            emit(clear, code_size);
+            codes.clear();
+            next_code = end + 1;
+            code_size = min_code_size + 1;
+        }
+        prefix = Some(u16::from(idx));
+    }
+    if let Some(current) = prefix {
+        emit(current, code_size);
+    }
+    emit(end, code_size);
+    if buffered_bits > 0 {
+        bytes.push(buffer.to_le_bytes()[0]);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_grid() {
+        let grid: Vec2d<char> = "#.\n.#".parse().unwrap();
+        let raster = Raster::from_grid(&grid, 2, |&c| if c == '#' { WHITE } else { BLACK });
+        assert_eq!((4, 4), (raster.width(), raster.height()));
+        assert_eq!(Some(WHITE), raster.get(1, 1));
+        assert_eq!(Some(BLACK), raster.get(2, 1));
+        assert_eq!(Some(WHITE), raster.get(3, 3));
+        assert_eq!(None, raster.get(4, 0));
+    }
+
+    #[test]
+    fn test_to_png() {
+        let png = Raster::new(3, 2, [255, 0, 0]).to_png();
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[.. 8]);
+        assert_eq!(b"IHDR", &png[12 .. 16]);
+        assert_eq!([0, 0, 0, 3, 0, 0, 0, 2, 8, 2], png[16 .. 26]);
+        assert_eq!(b"IEND", &png[png.len() - 8 .. png.len() - 4]);
+    }
+
+    #[test]
+    fn test_lzw_encode() {
+        // from the example at https://www.matthewflickinger.com/lab/whatsinagif/lzw_image_data.asp
+        let indices = [
+            1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2,
+            1, 1, 1, 0, 0, 0, 0, 2, 2, 2, 1, 1, 1, 0, 0, 0, 0, 2, 2, 2, 2, 2, 2, 0, 0, 0, 0, 1, 1, 1,
+            2, 2, 2, 0, 0, 0, 0, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1,
+            2, 2, 2, 2, 2, 1, 1, 1, 1, 1,
+        ];
+        let expected = [
+            0x8C, 0x2D, 0x99, 0x87, 0x2A, 0x1C, 0xDC, 0x33, 0xA0, 0x02, 0x75, 0xEC, 0x95, 0xFA, 0xA8,
+            0xDE, 0x60, 0x8C, 0x04, 0x91, 0x4C, 0x01,
+        ];
+        assert_eq!(expected.to_vec(), lzw_encode(indices.into_iter(), 2));
+    }
+
+    #[test]
+    fn test_animated_gif() {
+        let frames = [Raster::new(2, 2, BLACK), Raster::new(2, 2, WHITE)];
+        let gif = animated_gif(&frames, Duration::from_millis(100));
+        assert_eq!(b"GIF89a", &gif[.. 6]);
+        // 2x2 with a 2 color table
+        assert_eq!([2, 0, 2, 0, 0xF0], gif[6 .. 11]);
+        assert_eq!([0, 0, 0, 255, 255, 255], gif[13 .. 19]);
+        assert_eq!(2, gif.windows(3).filter(|w| w == &[0x21, 0xF9, 0x04]).count());
+        assert_eq!(Some(&0x3B), gif.last());
+    }
+}
@@ -0,0 +1,160 @@
+/// An operation the scanner recognizes, written as `name(arg,arg,...)` with exactly `arity` arguments
+#[derive(Debug, Clone, Copy)]
+pub struct OpSpec {
+    pub name: &'static str,
+    pub arity: usize,
+    /// The value of the operation from its arguments, or `None` if it can't be evaluated (ex: overflow).
+    /// An operation that can't be evaluated is treated as corrupted.
+    pub eval: fn(&[i64]) -> Option<i64>,
+}
+
+/// Describes which instructions are valid when extracting them from a corrupted program.
+/// Anything in the program that isn't exactly a valid instruction is noise and gets skipped.
+#[derive(Debug, Clone, Copy)]
+pub struct ScannerSpec {
+    pub ops: &'static [OpSpec],
+    /// Token that turns operations back on, ex: `do()`
+    pub enable: Option<&'static str>,
+    /// Token that turns off the operations that come after it, ex: `don't()`
+    pub disable: Option<&'static str>,
+    /// Number arguments are between 1 and this many digits long
+    pub max_digits: usize,
+    /// Whether an argument can itself be an operation, ex: `mul(add(1,2),3)`
+    pub nested: bool,
+}
+
+/// A valid instruction found in the program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    Op { name: &'static str, args: Vec<i64>, value: i64 },
+    Enable,
+    Disable,
+}
+
+pub const MUL: OpSpec = OpSpec { name: "mul", arity: 2, eval: |args| args[0].checked_mul(args[1]) };
+pub const ADD: OpSpec = OpSpec { name: "add", arity: 2, eval: |args| args[0].checked_add(args[1]) };
+
+impl ScannerSpec {
+    /// Every valid instruction in the program, with the byte offset it starts at.
+    /// Scanning is done on the program as a whole, so an instruction broken across lines is corrupted.
+    #[must_use]
+    pub fn scan(&self, program: &str) -> Vec<(usize, Instruction)> {
+        let mut found = Vec::new();
+        let mut pos = 0;
+        while let Some(next) = program[pos..].chars().next() {
+            if let Some((instruction, len)) = self.parse_instruction(&program[pos..]) {
+                found.push((pos, instruction));
+                pos += len;
+            } else {
+                // an instruction can start inside a corrupted one, ex: `mul(mul(2,3)`
+                pos += next.len_utf8();
+            }
+        }
+        found
+    }
+
+    /// Sum the values of the operations in the program, skipping the ones that are disabled.
+    /// The program starts out enabled.
+    #[must_use]
+    pub fn evaluate(&self, program: &str) -> i64 {
+        let mut enabled = true;
+        let mut sum = 0;
+        for (_, instruction) in self.scan(program) {
+            match instruction {
+                Instruction::Enable => enabled = true,
+                Instruction::Disable => enabled = false,
+                Instruction::Op { value, .. } if enabled => sum += value,
+                Instruction::Op { .. } => (),
+            }
+        }
+        sum
+    }
+
+    // The instruction at the very start of `text` and how many bytes long it is
+    fn parse_instruction(&self, text: &str) -> Option<(Instruction, usize)> {
+        if let Some(enable) = self.enable.filter(|token| text.starts_with(token)) {
+            return Some((Instruction::Enable, enable.len()));
+        }
+        if let Some(disable) = self.disable.filter(|token| text.starts_with(token)) {
+            return Some((Instruction::Disable, disable.len()));
+        }
+        self.ops.iter().find_map(|op| self.parse_op(op, text))
+    }
+
+    fn parse_op(&self, op: &OpSpec, text: &str) -> Option<(Instruction, usize)> {
+        let mut pos = op.name.len();
+        if !text.starts_with(op.name) || !text[pos..].starts_with('(') {
+            return None;
+        }
+        pos += 1;
+        let mut args = Vec::with_capacity(op.arity);
+        for idx in 0 .. op.arity {
+            if idx > 0 {
+                pos += text[pos..].starts_with(',').then_some(1)?;
+            }
+            let (arg, len) = self.parse_arg(&text[pos..])?;
+            args.push(arg);
+            pos += len;
+        }
+        pos += text[pos..].starts_with(')').then_some(1)?;
+        let value = (op.eval)(&args)?;
+        Some((Instruction::Op { name: op.name, args, value }, pos))
+    }
+
+    fn parse_arg(&self, text: &str) -> Option<(i64, usize)> {
+        let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+        if (1 ..= self.max_digits).contains(&digits) {
+            return Some((text[.. digits].parse().ok()?, digits));
+        }
+        if !self.nested || digits > 0 {
+            return None;
+        }
+        match self.ops.iter().find_map(|op| self.parse_op(op, text))? {
+            (Instruction::Op { value, .. }, len) => Some((value, len)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXTENDED: ScannerSpec = ScannerSpec {
+        ops: &[MUL, ADD],
+        enable: Some("on!"),
+        disable: Some("off!"),
+        max_digits: 3,
+        nested: true,
+    };
+
+    #[test]
+    fn test_scan() {
+        let spec = ScannerSpec { ops: &[MUL], enable: None, disable: None, max_digits: 3, nested: false };
+        let found = spec.scan("xmul(2,4)%mul(mul(3,5)mul(1234,1)mul(4,\n5)mul ( 1,2)");
+        assert_eq!(vec![
+            (1, Instruction::Op { name: "mul", args: vec![2, 4], value: 8 }),
+            (14, Instruction::Op { name: "mul", args: vec![3, 5], value: 15 }),
+        ], found);
+        assert_eq!(0, spec.evaluate("mul(4,\n5)"));
+        // without toggles in the spec, `don't()` is just more noise
+        assert_eq!(2, spec.evaluate("don't()mul(1,2)é"));
+    }
+
+    #[test]
+    fn test_nested() {
+        assert_eq!(24, EXTENDED.evaluate("mul(add(1,2),mul(2,4))"));
+        // the outer mul is corrupted, but the ones inside it are still valid
+        assert_eq!(11, EXTENDED.evaluate("mul(add(1,2),mul(2,4)"));
+        assert_eq!(9, EXTENDED.evaluate("add(mul(2,4),1)x"));
+        // numbers are limited to 3 digits, but nested values aren't
+        assert_eq!(998_001, EXTENDED.evaluate("mul(mul(999,999),1)"));
+        assert_eq!(0, EXTENDED.evaluate("add(1,2,3)"));
+    }
+
+    #[test]
+    fn test_toggles() {
+        assert_eq!(5, EXTENDED.evaluate("add(2,3)off!mul(4,4)don't()on!off!on!"));
+        assert_eq!(21, EXTENDED.evaluate("add(2,3)off!mul(4,4)on!mul(4,4)"));
+    }
+}
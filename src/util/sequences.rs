@@ -0,0 +1,103 @@
+/// Which way a sequence has to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+    /// Either way, as long as every step goes the same way as the first one that moves
+    Either,
+}
+
+/// A sequence is valid when each step between adjacent values is between `min_step` and `max_step` in size
+/// and goes in the allowed direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepRule {
+    pub min_step: i64,
+    pub max_step: i64,
+    pub direction: Direction,
+}
+
+impl StepRule {
+    /// The index of the value ending the first step that breaks the rule, or `None` if the sequence is valid
+    #[must_use]
+    pub fn first_violation<T: Copy + Into<i64>>(&self, sequence: &[T]) -> Option<usize> {
+        self.violation(sequence.iter().map(|&value| value.into()))
+    }
+
+    #[must_use]
+    pub fn is_valid<T: Copy + Into<i64>>(&self, sequence: &[T]) -> bool {
+        self.first_violation(sequence).is_none()
+    }
+
+    /// Whether the sequence is valid once the value at `skip` is left out
+    #[must_use]
+    pub fn is_valid_without<T: Copy + Into<i64>>(&self, sequence: &[T], skip: usize) -> bool {
+        let values = sequence.iter().enumerate()
+            .filter(|(idx, _)| *idx != skip)
+            .map(|(_, &value)| value.into());
+        self.violation(values).is_none()
+    }
+
+    /// Whether the sequence is valid, or can be made valid by removing a single value.
+    ///
+    /// If the first bad step is from `i - 1` to `i`, removing anything after `i` leaves that step in place,
+    /// and so does removing anything before `i - 1` unless it changes the direction set by the first step.
+    /// So only `i - 1`, `i`, and the first value are worth trying.
+    #[must_use]
+    pub fn is_valid_with_removal<T: Copy + Into<i64>>(&self, sequence: &[T]) -> bool {
+        match self.first_violation(sequence) {
+            None => true,
+            Some(idx) => [0, idx - 1, idx].into_iter().any(|skip| self.is_valid_without(sequence, skip)),
+        }
+    }
+
+    fn violation(&self, mut values: impl Iterator<Item = i64>) -> Option<usize> {
+        let mut sign = match self.direction {
+            Direction::Increasing => 1,
+            Direction::Decreasing => -1,
+            Direction::Either => 0,
+        };
+        let mut previous = values.next()?;
+        for (idx, value) in values.enumerate() {
+            let step = value - previous;
+            if !(self.min_step ..= self.max_step).contains(&step.abs()) || step.signum() * sign < 0 {
+                return Some(idx + 1);
+            }
+            if sign == 0 {
+                sign = step.signum();
+            }
+            previous = value;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_violation() {
+        let rule = StepRule { min_step: 1, max_step: 3, direction: Direction::Either };
+        assert_eq!(None, rule.first_violation(&[7, 6, 4, 2, 1]));
+        assert_eq!(Some(2), rule.first_violation(&[1, 2, 7, 8, 9]));
+        assert_eq!(Some(2), rule.first_violation(&[1, 3, 2, 4, 5]));
+        assert_eq!(Some(3), rule.first_violation(&[8, 6, 4, 4, 1]));
+        assert_eq!(None, rule.first_violation::<i32>(&[]));
+
+        let rule = StepRule { min_step: 0, max_step: 10, direction: Direction::Increasing };
+        assert!(rule.is_valid(&[1, 1, 5, 15]));
+        assert_eq!(Some(1), rule.first_violation(&[5, 4]));
+    }
+
+    #[test]
+    fn test_removal() {
+        let rule = StepRule { min_step: 1, max_step: 3, direction: Direction::Either };
+        assert!(rule.is_valid_with_removal(&[1, 3, 2, 4, 5]));
+        assert!(rule.is_valid_with_removal(&[8, 6, 4, 4, 1]));
+        assert!(!rule.is_valid_with_removal(&[1, 2, 7, 8, 9]));
+        // the first step sets the direction, so removing it is the fix
+        assert!(rule.is_valid_with_removal(&[5, 6, 4, 3, 2]));
+        assert!(rule.is_valid_without(&[5, 6, 4, 3, 2], 0));
+        assert!(!rule.is_valid_without(&[5, 6, 4, 3, 2], 4));
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt::{Display, Write as _};
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use super::grid::Point;
+
+/// Move the cursor to the top left and clear the terminal
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+const RESET_COLOR: &str = "\x1b[0m";
+
+/// The colors a terminal can draw text in with ANSI escape codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Default,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+
+impl Color {
+    /// The ANSI select graphic rendition code for the text color
+    fn code(self) -> u8 {
+        match self {
+            Color::Default => 39,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::Gray => 90,
+        }
+    }
+}
+
+/// Draw a `line_len` by `height` grid as text, with the character and color for each point from `cell`.
+/// The escape code is only written when the color changes, to keep frames small.
+#[must_use]
+pub fn render(line_len: i32, height: i32, cell: impl Fn(Point) -> (char, Color)) -> String {
+    let mut rendered = String::new();
+    let mut current = Color::Default;
+    for y in 0 .. height {
+        if y > 0 {
+            rendered.push('\n');
+        }
+        for x in 0 .. line_len {
+            let (c, color) = cell(Point::new(x, y));
+            if color != current {
+                write!(rendered, "\x1b[{}m", color.code()).unwrap();
+                current = color;
+            }
+            rendered.push(c);
+        }
+    }
+    if current != Color::Default {
+        rendered.push_str(RESET_COLOR);
+    }
+    rendered
+}
+
+/// How often to keep a frame, to show about `max_frames` out of `steps`
+#[must_use]
+pub fn frame_interval(steps: usize, max_frames: usize) -> usize {
+    steps.div_ceil(max_frames.max(1)).max(1)
+}
+
+/// Plays frames in the terminal, clearing the screen before each one
+pub struct Animation<W: Write> {
+    out: W,
+    delay: Duration,
+}
+
+impl<W: Write> Animation<W> {
+    /// Show each frame for `delay`
+    pub fn new(out: W, delay: Duration) -> Self {
+        Self { out, delay }
+    }
+
+    /// Draw the frame, then wait for the frame delay
+    ///
+    /// # Errors
+    /// If the frame can't be written
+    pub fn show(&mut self, frame: &str) -> io::Result<()> {
+        writeln!(self.out, "{CLEAR_SCREEN}{frame}")?;
+        self.out.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+
+    /// Show every frame in order
+    ///
+    /// # Errors
+    /// If a frame can't be written
+    pub fn play(&mut self, frames: impl IntoIterator<Item = String>) -> io::Result<()> {
+        frames.into_iter().try_for_each(|frame| self.show(&frame))
+    }
+}
+
+/// The width and height of a grid cell in an [`Svg`], in SVG units
+const SVG_CELL: i32 = 10;
+
+/// A picture of a grid as an SVG image: cells filled in with colors,
+/// and paths drawn as lines through the centers of their cells.
+/// Colors are any CSS color, such as `"#333"` or `"red"`.
+/// Elements are drawn in the order they are added, so add the background first.
+#[derive(Debug, Clone)]
+pub struct Svg {
+    line_len: i32,
+    height: i32,
+    elements: Vec<String>,
+}
+
+impl Svg {
+    /// An empty picture of a `line_len` by `height` grid
+    #[must_use]
+    pub fn new(line_len: i32, height: i32) -> Self {
+        Self { line_len, height, elements: Vec::new() }
+    }
+
+    /// Fill in every cell of a region with one color
+    pub fn region(&mut self, points: impl IntoIterator<Item = Point>, color: &str) {
+        let mut outline = String::new();
+        for point in points {
+            write!(outline, "M{} {}h{SVG_CELL}v{SVG_CELL}h-{SVG_CELL}z", point.x * SVG_CELL, point.y * SVG_CELL).unwrap();
+        }
+        if !outline.is_empty() {
+            self.elements.push(format!("<path d=\"{outline}\" fill=\"{color}\"/>"));
+        }
+    }
+
+    /// Draw a line through the centers of the points, in order
+    pub fn path(&mut self, points: &[Point], color: &str) {
+        let center = |point: &Point| format!("{},{}", point.x * SVG_CELL + SVG_CELL / 2, point.y * SVG_CELL + SVG_CELL / 2);
+        let points = points.iter().map(center).collect::<Vec<_>>().join(" ");
+        self.elements.push(format!(
+            "<polyline points=\"{points}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"3\" \
+            stroke-linecap=\"round\" stroke-linejoin=\"round\"/>"
+        ));
+    }
+}
+
+impl Display for Svg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (width, height) = (self.line_len * SVG_CELL, self.height * SVG_CELL);
+        writeln!(f, "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width} {height}\" width=\"{width}\" height=\"{height}\">")?;
+        writeln!(f, "<rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>")?;
+        for element in &self.elements {
+            writeln!(f, "{element}")?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+/// A color for the `idx`th of many things, stepping around the color wheel
+/// by the golden angle so that consecutive colors are easy to tell apart
+#[must_use]
+pub fn distinct_color(idx: usize) -> String {
+    format!("hsl({}, 65%, 60%)", idx * 137 % 360)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let rendered = render(3, 2, |point| match (point.x, point.y) {
+            (0 | 1, 0) => ('#', Color::Gray),
+            (2, 1) => ('@', Color::Red),
+            _ => ('.', Color::Default),
+        });
+        assert_eq!("\x1b[90m##\x1b[39m.\n..\x1b[31m@\x1b[0m", rendered);
+    }
+
+    #[test]
+    fn test_play() {
+        let mut out = Vec::new();
+        Animation::new(&mut out, Duration::ZERO).play(["a".to_string(), "b".to_string()]).unwrap();
+        assert_eq!(format!("{CLEAR_SCREEN}a\n{CLEAR_SCREEN}b\n"), String::from_utf8(out).unwrap());
+        assert_eq!(5, frame_interval(1000, 200));
+        assert_eq!(1, frame_interval(10, 200));
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut svg = Svg::new(3, 2);
+        svg.region([Point::new(0, 0), Point::new(2, 1)], "#333");
+        svg.region([], "red");
+        svg.path(&[Point::new(0, 1), Point::new(1, 1)], "green");
+        let svg = svg.to_string();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 30 20\""));
+        assert!(svg.contains("<path d=\"M0 0h10v10h-10zM20 10h10v10h-10z\" fill=\"#333\"/>"));
+        assert!(svg.contains("<polyline points=\"5,15 15,15\" fill=\"none\" stroke=\"green\""));
+        assert_eq!(1, svg.matches("<path").count());
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!("hsl(274, 65%, 60%)", distinct_color(2));
+    }
+}
@@ -0,0 +1,144 @@
+use std::fs;
+
+use advent2024::config::Config;
+use advent2024::day::{find_day, registry, Answer, DaySolution};
+
+/// Expected answers for each day, kept out of version control.
+///
+/// ```toml
+/// [day1]
+/// part1 = "1151792"
+/// part2 = "21790168"
+/// ```
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// The expected answers for a single day. A missing part is not checked.
+#[derive(Debug, Default, PartialEq)]
+pub struct Expected {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+/// Load the expected answers for a day from the contents of an answers file
+///
+/// # Errors
+/// If the answers are not valid toml
+pub fn expected_answers(answers: &str, day: i32) -> Result<Expected, String> {
+    let table = answers.parse::<toml::Table>()
+        .map_err(|e| format!("Invalid {ANSWERS_FILE}: {e}"))?;
+    let Some(day_table) = table.get(&format!("day{day}")) else {
+        return Ok(Expected::default());
+    };
+    // Allow answers to be written as integers or strings
+    let part = |key: &str| day_table.get(key).map(|value| match value {
+        toml::Value::Integer(value) => Answer::Int(*value),
+        toml::Value::String(s) => {
+            let Ok(answer) = s.parse();
+            answer
+        }
+        other => Answer::Text(other.to_string()),
+    });
+    Ok(Expected { part1: part("part1"), part2: part("part2") })
+}
+
+/// Compare a solution to the expected answers, returning a description of each mismatch
+#[must_use]
+pub fn compare(expected: &Expected, solution: &DaySolution) -> Vec<String> {
+    [(1, &expected.part1, &solution.part1), (2, &expected.part2, &solution.part2)].into_iter()
+        .filter_map(|(part, expected, actual)| match expected {
+            Some(expected) if expected != actual => {
+                Some(format!("part {part}: expected {expected}, got {actual}"))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Run each day against the real puzzle input and compare to the stored expected answers.
+/// Runs every registered day if `days` is empty, with the input and context from the `config`.
+/// Returns true if there are no regressions.
+///
+/// # Errors
+/// If the answers file is missing or invalid
+pub fn verify(days: &[i32], config: &Config) -> Result<bool, String> {
+    let answers = fs::read_to_string(ANSWERS_FILE)
+        .map_err(|_| format!("{ANSWERS_FILE} not found"))?;
+    let runners = if days.is_empty() {
+        registry()
+    } else {
+        days.iter().filter_map(|&day| find_day(day)).collect()
+    };
+
+    let mut success = true;
+    for runner in runners {
+        let day = runner.day();
+        let expected = expected_answers(&answers, day)?;
+        if expected == Expected::default() {
+            println!("Day {day}: no expected answers");
+            continue;
+        }
+        let solution = match runner.solve(&config.input_path(day), &config.context(day)) {
+            Ok(solution) => solution,
+            Err(e) => {
+                success = false;
+                println!("Day {day}: {e}");
+                continue;
+            }
+        };
+        let mismatches = compare(&expected, &solution);
+        if mismatches.is_empty() {
+            println!("Day {day}: ok");
+        } else {
+            success = false;
+            for mismatch in mismatches {
+                println!("Day {day} {mismatch}");
+            }
+        }
+    }
+    Ok(success)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const ANSWERS: &str = "
+[day1]
+part1 = \"11\"
+part2 = 31
+
+[day17]
+part1 = \"4,6,3,5,6,3,5,2,1,0\"";
+
+    fn solution(part1: Answer, part2: Answer) -> DaySolution {
+        DaySolution {
+            part1,
+            part2,
+            parse_time: Duration::ZERO,
+            part1_time: Duration::ZERO,
+            part2_time: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_expected_answers() {
+        let day1 = expected_answers(ANSWERS, 1).unwrap();
+        assert_eq!(Some(Answer::Int(11)), day1.part1);
+        assert_eq!(Some(Answer::Int(31)), day1.part2);
+        let day17 = expected_answers(ANSWERS, 17).unwrap();
+        assert_eq!(Some(Answer::from("4,6,3,5,6,3,5,2,1,0")), day17.part1);
+        let day17 = expected_answers(ANSWERS, 17).unwrap();
+        assert_eq!(None, day17.part2);
+        assert_eq!(Expected::default(), expected_answers(ANSWERS, 2).unwrap());
+    }
+
+    #[test]
+    fn test_compare() {
+        let expected = expected_answers(ANSWERS, 1).unwrap();
+        assert!(compare(&expected, &solution(Answer::Int(11), Answer::Int(31))).is_empty());
+        assert_eq!(vec!["part 2: expected 31, got 32"], compare(&expected, &solution(Answer::Int(11), Answer::Int(32))));
+        let expected = expected_answers(ANSWERS, 17).unwrap();
+        assert!(compare(&expected, &solution(Answer::from("4,6,3,5,6,3,5,2,1,0"), Answer::from("anything"))).is_empty());
+    }
+}
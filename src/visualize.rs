@@ -0,0 +1,105 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use advent2024::day::{day12, day14, day15, day16, day17, day18, day20, day24, day6, input_path, Context, Day};
+use advent2024::util::viz::Animation;
+use crate::report::OUTPUT_DIR;
+
+/// How many frames to show in a terminal animation
+const MAX_FRAMES: usize = 300;
+
+/// How long to show each frame of a terminal animation, unless `--delay` is given
+pub const DEFAULT_DELAY: Duration = Duration::from_millis(50);
+
+/// Draw a day's puzzle input, for viewing in the terminal or an external tool.
+/// Days 6, 15, and 18 are animated in the terminal, showing each frame for `delay`.
+/// Day 24 is printed as a Graphviz DOT graph.
+/// Day 14 prints the christmas tree, and writes pictures of it to `output/day14`.
+/// Uses the input file at `input` if provided, otherwise the default [`input_path`],
+/// and the day's parameters from `context`.
+///
+/// # Errors
+/// If the day has no visualization, or its input cannot be read or the pictures written
+pub fn visualize(day: i32, input: Option<&Path>, delay: Duration, context: &Context) -> Result<String, String> {
+    let path = input.map_or_else(|| input_path(day), Path::to_path_buf);
+    let mut animation = Animation::new(io::stdout(), delay);
+    let played = |result: io::Result<()>| result.map(|()| String::new()).map_err(|e| e.to_string());
+    match day {
+        6 => {
+            let map = day6::Day6::read_input_from(&path).map_err(|e| e.to_string())?;
+            let frames = day6::patrol_frames(&map, MAX_FRAMES).map_err(|e| e.to_string())?;
+            played(animation.play(frames))
+        }
+        15 => {
+            let warehouse = day15::Day15::read_input_from(&path).map_err(|e| e.to_string())?;
+            let frames = day15::warehouse_frames(&warehouse, MAX_FRAMES).map_err(|e| e.to_string())?;
+            played(animation.play(frames))
+        }
+        18 => {
+            let bytes = day18::Day18::read_input_from(&path).map_err(|e| e.to_string())?;
+            let frames = day18::falling_frames(&bytes, context, MAX_FRAMES).map_err(|e| e.to_string())?;
+            played(animation.play(frames))
+        }
+        14 => {
+            let robots = day14::Day14::read_input_from(&path).map_err(|e| e.to_string())?;
+            let pictures = day14::tree_pictures(&robots, context).map_err(|e| e.to_string())?;
+            let dir = PathBuf::from(OUTPUT_DIR).join("day14");
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+            let png = dir.join("tree.png");
+            let gif = dir.join("tree.gif");
+            for (file, bytes) in [(&png, &pictures.png), (&gif, &pictures.gif)] {
+                fs::write(file, bytes).map_err(|e| format!("Failed to write {}: {e}", file.display()))?;
+            }
+            Ok(format!(
+                "{}\nThe tree appears after {} seconds\nWrote {} and {}\n",
+                pictures.rendering, pictures.seconds, png.display(), gif.display(),
+            ))
+        }
+        24 => {
+            let (_, gates) = day24::Day24::read_input_from(&path).map_err(|e| e.to_string())?;
+            Ok(day24::to_dot(&gates))
+        }
+        _ => Err(format!("Day {day} has no visualization")),
+    }
+}
+
+/// Save an SVG picture of a day's solution as `output/dayN.svg`, and print where it is:
+/// the garden regions for day 12, every best path through the maze for day 16,
+/// or the race track and its cheats for day 20.
+/// Uses the input file at `input` if provided, otherwise the default [`input_path`].
+///
+/// # Errors
+/// If the day has no SVG picture, or its input cannot be read, solved, or the picture written
+pub fn svg(day: i32, input: Option<&Path>) -> Result<String, String> {
+    let path = input.map_or_else(|| input_path(day), Path::to_path_buf);
+    let svg = match day {
+        12 => day12::to_svg(&day12::Day12::read_input_from(&path).map_err(|e| e.to_string())?),
+        16 => day16::to_svg(&day16::Day16::read_input_from(&path).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?,
+        20 => day20::to_svg(&day20::Day20::read_input_from(&path).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?,
+        _ => return Err(format!("Day {day} has no SVG picture")),
+    };
+    fs::create_dir_all(OUTPUT_DIR).map_err(|e| format!("Failed to create {OUTPUT_DIR}: {e}"))?;
+    let file = PathBuf::from(OUTPUT_DIR).join(format!("day{day}.svg"));
+    fs::write(&file, svg).map_err(|e| format!("Failed to write {}: {e}", file.display()))?;
+    Ok(format!("Wrote {}\n", file.display()))
+}
+
+/// Print a day's program as readable pseudo-code. Only some days have a program.
+/// Uses the input file at `input` if provided, otherwise the default [`input_path`].
+///
+/// # Errors
+/// If the day has no program to disassemble, or its input cannot be read
+pub fn disassemble(day: i32, input: Option<&Path>) -> Result<String, String> {
+    let path = input.map_or_else(|| input_path(day), Path::to_path_buf);
+    match day {
+        17 => {
+            let (_, program) = day17::Day17::read_input_from(&path).map_err(|e| e.to_string())?;
+            Ok(day17::disassemble(&program))
+        }
+        _ => Err(format!("Day {day} has no program to disassemble")),
+    }
+}
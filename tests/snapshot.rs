@@ -0,0 +1,60 @@
+#![warn(clippy::all, clippy::pedantic)]
+//! Runs every day on the real puzzle inputs in `resources/` and compares the output with
+//! `tests/snapshots/all_days.jsonl`, so a change to the runner, the `Day` trait, or a util
+//! module can't quietly change an answer or the output format.
+//!
+//! Puzzle inputs are personal to an account, and a checkout with other (or no) inputs would
+//! fail, so the test only runs when asked:
+//! ```sh
+//! AOC_SNAPSHOT=1 cargo test --test snapshot
+//! ```
+//! Set `AOC_SNAPSHOT=update` to rewrite the snapshot after an intended change.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use advent2024::day::{input_path, registry, Context, DaySolution};
+
+fn snapshot_path() -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", "all_days.jsonl"].iter().collect()
+}
+
+/// The JSON output for a day, with the timings zeroed so only the format is compared
+fn snapshot_line(day: i32, solution: DaySolution) -> String {
+    DaySolution {
+        parse_time: Duration::ZERO,
+        part1_time: Duration::ZERO,
+        part2_time: Duration::ZERO,
+        ..solution
+    }.to_json(day)
+}
+
+#[test]
+fn test_all_days() {
+    let Ok(mode) = env::var("AOC_SNAPSHOT") else {
+        eprintln!("skipping the snapshot of every day, set AOC_SNAPSHOT=1 to run it");
+        return;
+    };
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let actual = registry().into_iter()
+        .map(|runner| {
+            let day = runner.day();
+            let solution = runner.solve(&root.join(input_path(day)), &Context::default())
+                .unwrap_or_else(|e| panic!("day {day}: {e}"));
+            snapshot_line(day, solution) + "\n"
+        })
+        .collect::<String>();
+
+    let path = snapshot_path();
+    if mode == "update" {
+        fs::write(&path, &actual).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    for (expected, actual) in expected.lines().zip(actual.lines()) {
+        assert_eq!(expected, actual);
+    }
+    assert_eq!(expected.lines().count(), actual.lines().count(), "the number of days changed");
+}